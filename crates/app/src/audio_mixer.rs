@@ -1,43 +1,209 @@
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use ringbuf::traits::{Consumer, Observer, Producer, Split};
 use ringbuf::HeapRb;
 use wizard_audio::output::{AudioConsumer, AudioProducer};
 use wizard_media::gst_pipeline::GstAudioOnlyHandle;
+use wizard_state::timeline::{db_to_linear, finish_mix, SourceEnvelope, TimelineClipId, TrackId};
+
+use crate::pipeline::ShadowAudioSource;
 
 struct AudioSource {
     consumer: AudioConsumer,
+    track_id: TrackId,
+    timeline_clip: TimelineClipId,
+    envelope: SourceEnvelope,
+    // Interleaved samples taken so far, which places the source within its clip.
+    consumed: usize,
     _handle: GstAudioOnlyHandle,
 }
 
+impl AudioSource {
+    fn new(source: ShadowAudioSource) -> Self {
+        let (handle, consumer, track_id, timeline_clip, envelope) = source;
+        Self {
+            consumer,
+            track_id,
+            timeline_clip,
+            envelope,
+            consumed: 0,
+            _handle: handle,
+        }
+    }
+
+    fn envelope_gain(&self, samples_per_second: f64) -> f32 {
+        self.envelope.gain(self.consumed, samples_per_second)
+    }
+}
+
+// Where a source is within its own crossfade ramp, in interleaved samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fade {
+    Full,
+    In(usize),
+    Out(usize),
+}
+
+impl Fade {
+    fn gain(self, len: usize) -> f32 {
+        match self {
+            Fade::Full => 1.0,
+            Fade::In(pos) => crossfade_gains(pos, len).0,
+            Fade::Out(pos) => crossfade_gains(pos, len).1,
+        }
+    }
+
+    fn advance(self, len: usize) -> Fade {
+        match self {
+            Fade::In(pos) if pos + 1 >= len => Fade::Full,
+            Fade::In(pos) => Fade::In(pos + 1),
+            Fade::Out(pos) => Fade::Out(pos + 1),
+            Fade::Full => Fade::Full,
+        }
+    }
+
+    // Starts the fade-out at the level the source is playing at now, so a source that was
+    // still ramping in or out carries on from there instead of jumping.
+    fn fading_out(self, len: usize) -> Fade {
+        match self {
+            Fade::Full => Fade::Out(0),
+            Fade::In(pos) => Fade::Out(len.saturating_sub(pos)),
+            Fade::Out(pos) => Fade::Out(pos),
+        }
+    }
+
+    fn finished(self, len: usize) -> bool {
+        matches!(self, Fade::Out(pos) if pos >= len)
+    }
+}
+
+struct Faded<S> {
+    source: S,
+    fade: Fade,
+}
+
+// The sources being mixed, each carrying its own ramp so swaps that overlap a running
+// crossfade don't reset anyone else's level.
+struct Sources<S> {
+    playing: Vec<Faded<S>>,
+    outgoing: Vec<Faded<S>>,
+    fade_len: usize,
+}
+
+impl<S> Sources<S> {
+    fn new(fade_len: usize) -> Self {
+        Self {
+            playing: Vec::new(),
+            outgoing: Vec::new(),
+            fade_len,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.playing.is_empty() && self.outgoing.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.playing.clear();
+        self.outgoing.clear();
+    }
+
+    fn push(&mut self, source: S, fade: Fade) {
+        self.playing.push(Faded { source, fade });
+    }
+
+    fn all_mut(&mut self) -> impl Iterator<Item = &mut Faded<S>> {
+        self.playing.iter_mut().chain(self.outgoing.iter_mut())
+    }
+
+    fn retire(&mut self, leaving: Vec<Faded<S>>) {
+        let len = self.fade_len;
+        self.outgoing.extend(leaving.into_iter().map(|mut f| {
+            f.fade = f.fade.fading_out(len);
+            f
+        }));
+    }
+
+    fn replace(&mut self, added: impl IntoIterator<Item = S>) {
+        let leaving = std::mem::take(&mut self.playing);
+        self.retire(leaving);
+        for source in added {
+            self.push(source, Fade::In(0));
+        }
+    }
+
+    fn reconcile(&mut self, keep: impl Fn(&S) -> bool, added: impl IntoIterator<Item = S>) {
+        let (kept, removed): (Vec<_>, Vec<_>) = std::mem::take(&mut self.playing)
+            .into_iter()
+            .partition(|f| keep(&f.source));
+        self.playing = kept;
+        self.retire(removed);
+        for source in added {
+            self.push(source, Fade::In(0));
+        }
+    }
+
+    fn drop_finished(&mut self) {
+        let len = self.fade_len;
+        self.outgoing.retain(|f| !f.fade.finished(len));
+    }
+}
+
 pub struct AudioMixer {
     pub output: Arc<Mutex<AudioProducer>>,
-    sources: Vec<AudioSource>,
+    sources: Sources<AudioSource>,
     mix_buf: Vec<f32>,
+    master_gain: f32,
+    track_gains: HashMap<TrackId, f32>,
+    source_ring_len: usize,
+    samples_per_second: f64,
 }
 
-const SOURCE_RING_SIZE: usize = 65536;
+// Each source buffers several output buffers' worth so decode hiccups don't starve the mix.
+const SOURCE_RING_OUTPUT_MULTIPLE: usize = 8;
+const SOURCE_RING_MIN: usize = 16384;
 const MIX_BUF_MAX: usize = 4096;
+const CROSSFADE_S: f64 = 0.010;
 
 impl AudioMixer {
-    pub fn new(output: Arc<Mutex<AudioProducer>>) -> Self {
+    pub fn new(
+        output: Arc<Mutex<AudioProducer>>,
+        sample_rate: u32,
+        channels: u16,
+        output_buffer_len: usize,
+    ) -> Self {
+        let crossfade_len = (sample_rate as f64 * CROSSFADE_S) as usize * channels as usize;
         Self {
             output,
-            sources: Vec::new(),
+            sources: Sources::new(crossfade_len),
             mix_buf: vec![0.0f32; MIX_BUF_MAX],
+            master_gain: 1.0,
+            track_gains: HashMap::new(),
+            source_ring_len: (output_buffer_len * SOURCE_RING_OUTPUT_MULTIPLE).max(SOURCE_RING_MIN),
+            samples_per_second: (sample_rate.max(1) as f64) * channels.max(1) as f64,
         }
     }
 
-    pub fn create_source_producer() -> (AudioProducer, AudioConsumer) {
-        let rb = HeapRb::<f32>::new(SOURCE_RING_SIZE);
+    pub fn set_master_gain_db(&mut self, gain_db: f32) {
+        self.master_gain = db_to_linear(gain_db);
+    }
+
+    pub fn set_track_gain_db(&mut self, track_id: TrackId, gain_db: f32) {
+        self.track_gains.insert(track_id, db_to_linear(gain_db));
+    }
+
+    pub fn source_ring_len(&self) -> usize {
+        self.source_ring_len
+    }
+
+    pub fn create_source_producer(ring_len: usize) -> (AudioProducer, AudioConsumer) {
+        let rb = HeapRb::<f32>::new(ring_len);
         rb.split()
     }
 
-    pub fn add_source(&mut self, handle: GstAudioOnlyHandle, consumer: AudioConsumer) {
-        self.sources.push(AudioSource {
-            consumer,
-            _handle: handle,
-        });
+    pub fn add_source(&mut self, source: ShadowAudioSource) {
+        self.sources.push(AudioSource::new(source), Fade::Full);
     }
 
     pub fn mix_tick(&mut self) {
@@ -47,8 +213,8 @@ impl AudioMixer {
 
         let max_available = self
             .sources
-            .iter()
-            .map(|s| s.consumer.occupied_len())
+            .all_mut()
+            .map(|f| f.source.consumer.occupied_len())
             .max()
             .unwrap_or(0);
 
@@ -60,18 +226,28 @@ impl AudioMixer {
         let buf = &mut self.mix_buf[..mix_len];
         buf.fill(0.0);
 
-        for source in &mut self.sources {
+        let track_gains = &self.track_gains;
+        let fade_len = self.sources.fade_len;
+        let samples_per_second = self.samples_per_second;
+
+        for faded in self.sources.all_mut() {
+            let source = &mut faded.source;
+            let gain = track_gains.get(&source.track_id).copied().unwrap_or(1.0);
             let avail = source.consumer.occupied_len();
             for slot in buf.iter_mut().take(avail.min(mix_len)) {
                 if let Some(sample) = source.consumer.try_pop() {
-                    *slot += sample;
+                    let fade = faded.fade.gain(fade_len);
+                    faded.fade = faded.fade.advance(fade_len);
+                    let envelope = source.envelope_gain(samples_per_second);
+                    source.consumed += 1;
+                    *slot += sample * gain * fade * envelope;
                 }
             }
         }
 
-        for sample in buf.iter_mut() {
-            *sample = sample.clamp(-1.0, 1.0);
-        }
+        self.sources.drop_finished();
+
+        finish_mix(buf, self.master_gain);
 
         if let Ok(mut producer) = self.output.lock() {
             producer.push_slice(buf);
@@ -79,20 +255,110 @@ impl AudioMixer {
     }
 
     pub fn source_count(&self) -> usize {
-        self.sources.len()
+        self.sources.playing.len()
     }
 
     pub fn clear(&mut self) {
         self.sources.clear();
     }
 
-    pub fn replace_sources(&mut self, new_sources: Vec<(GstAudioOnlyHandle, AudioConsumer)>) {
-        self.sources.clear();
-        for (handle, consumer) in new_sources {
-            self.sources.push(AudioSource {
-                consumer,
-                _handle: handle,
-            });
+    // Keeps the previous sources alive just long enough to ramp them out under the new ones,
+    // so clip joins don't click. Sources still fading out from an earlier join keep going.
+    pub fn replace_sources(&mut self, new_sources: Vec<ShadowAudioSource>) {
+        self.sources
+            .replace(new_sources.into_iter().map(AudioSource::new));
+    }
+
+    pub fn timeline_clips(&self) -> impl Iterator<Item = TimelineClipId> + '_ {
+        self.sources.playing.iter().map(|f| f.source.timeline_clip)
+    }
+
+    // Swaps only the sources that changed: removed ones ramp out and added ones ramp in,
+    // while the rest keep playing where their own ramp is.
+    pub fn reconcile_sources(
+        &mut self,
+        keep: impl Fn(TimelineClipId) -> bool,
+        added: Vec<ShadowAudioSource>,
+    ) {
+        self.sources.reconcile(
+            |s| keep(s.timeline_clip),
+            added.into_iter().map(AudioSource::new),
+        );
+    }
+}
+
+fn crossfade_gains(pos: usize, len: usize) -> (f32, f32) {
+    if len == 0 {
+        return (1.0, 0.0);
+    }
+    let x = (pos as f32 / len as f32).min(1.0) * std::f32::consts::FRAC_PI_2;
+    (x.sin(), x.cos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEN: usize = 100;
+
+    fn step(sources: &mut Sources<u32>, samples: usize) {
+        for _ in 0..samples {
+            for faded in sources.all_mut() {
+                faded.fade = faded.fade.advance(LEN);
+            }
         }
+        sources.drop_finished();
+    }
+
+    fn gain_of(sources: &Sources<u32>, id: u32) -> Option<f32> {
+        sources
+            .playing
+            .iter()
+            .chain(sources.outgoing.iter())
+            .find(|f| f.source == id)
+            .map(|f| f.fade.gain(LEN))
+    }
+
+    #[test]
+    fn reconciling_twice_during_one_fade_keeps_every_level_continuous() {
+        let mut sources = Sources::new(LEN);
+        sources.push(1, Fade::Full);
+
+        sources.reconcile(|_| false, [2]);
+        step(&mut sources, 40);
+        let one_before = gain_of(&sources, 1).unwrap();
+        let two_before = gain_of(&sources, 2).unwrap();
+        assert!(one_before < 1.0 && two_before > 0.0);
+
+        sources.reconcile(|&id| id != 2, [3]);
+        let one_after = gain_of(&sources, 1).unwrap();
+        let two_after = gain_of(&sources, 2).unwrap();
+        assert!((one_after - one_before).abs() < 1e-6);
+        assert!((two_after - two_before).abs() < 1e-6);
+        assert_eq!(gain_of(&sources, 3), Some(0.0));
+
+        // Each outgoing source finishes on its own schedule rather than the latest swap's.
+        step(&mut sources, 40);
+        assert_eq!(gain_of(&sources, 2), None);
+        assert!(gain_of(&sources, 1).is_some());
+        step(&mut sources, LEN - 40);
+        assert_eq!(gain_of(&sources, 1), None);
+        step(&mut sources, 40);
+        assert_eq!(sources.playing[0].fade, Fade::Full);
+    }
+
+    #[test]
+    fn replacing_during_a_fade_keeps_the_earlier_outgoing_sources() {
+        let mut sources = Sources::new(LEN);
+        sources.push(1, Fade::Full);
+
+        sources.replace([2]);
+        step(&mut sources, 30);
+        let one_before = gain_of(&sources, 1).unwrap();
+        sources.replace([3]);
+
+        assert!((gain_of(&sources, 1).unwrap() - one_before).abs() < 1e-6);
+        assert_eq!(sources.outgoing.len(), 2);
+        assert_eq!(sources.playing.len(), 1);
     }
 }
@@ -57,21 +57,44 @@ impl EditorApp {
             received = true;
         }
 
+        // Stands in until the full metadata, so clips dropped straight after import get
+        // their real length rather than the fallback.
+        while within_budget() {
+            let Ok((id, duration)) = self.duration_rx.try_recv() else {
+                break;
+            };
+            if let Some(clip) = self.state.project.clips.get_mut(&id) {
+                if !clip.metadata_loaded {
+                    clip.duration = Some(duration);
+                }
+            }
+            received = true;
+        }
+
         while within_budget() {
             let Ok((id, meta)) = self.meta_rx.try_recv() else {
                 break;
             };
             let tag_mask = self.state.project.clip_tag_mask(id);
             if let Some(clip) = self.state.project.clips.get_mut(&id) {
-                clip.duration = meta.duration;
+                clip.duration = meta.duration.or(clip.duration);
                 clip.resolution = meta.resolution;
+                clip.fps = meta.fps;
                 clip.codec = meta.codec;
                 clip.audio_only = !meta.has_video;
-                clip.rebuild_search_haystack(tag_mask);
+                clip.has_audio = meta.has_audio;
+                clip.audio_channels = meta.audio_channels;
+                clip.metadata_loaded = true;
+                clip.rebuild_search_haystack(&self.state.project.tags, tag_mask);
+                self.state.project.settings.adopt_clip_format(clip);
                 if !meta.has_video {
                     self.textures.pending_thumbnails.remove(&id);
                 }
             }
+            if let Some(duration) = meta.duration {
+                let now = ctx.input(|i| i.time);
+                self.state.clamp_source_range(id, duration, now);
+            }
             received = true;
         }
 
@@ -79,6 +102,10 @@ impl EditorApp {
             let Ok(pf) = self.preview.result_rx.try_recv() else {
                 break;
             };
+            // Frames still arriving for a strip that was evicted or cancelled are dropped.
+            if !self.textures.preview_requested.contains(&pf.clip_id) {
+                continue;
+            }
             let texture = ctx.load_texture(
                 format!("preview_{:?}_{}", pf.clip_id, pf.index),
                 egui::ColorImage::from_rgba_unmultiplied(
@@ -123,10 +150,24 @@ impl EditorApp {
         }
 
         while within_budget() {
-            let Ok((id, peaks)) = self.waveform_rx.try_recv() else {
+            let Ok((id, chunk)) = self.waveform_rx.try_recv() else {
+                break;
+            };
+            let finished_coarsest = chunk.level == 0 && chunk.done;
+            self.textures.apply_waveform_chunk(id, chunk);
+            if finished_coarsest && self.state.ui.browser.quality_checks {
+                self.update_silence_flag(id);
+            }
+            received = true;
+        }
+
+        while within_budget() {
+            let Ok((id, black)) = self.black_rx.try_recv() else {
                 break;
             };
-            self.textures.waveform_peaks.insert(id, peaks);
+            if let Some(clip) = self.state.project.clips.get_mut(&id) {
+                clip.black_opening = black;
+            }
             received = true;
         }
 
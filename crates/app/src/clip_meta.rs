@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use wizard_state::project::ProjectState;
+
+use crate::settings::config_dir;
+
+// Stars and tags keyed by file path, so re-importing a folder brings back how its clips
+// were organised. Tags are stored by name because tag bits are per project.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClipMeta {
+    pub starred: bool,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct ClipMetaStore {
+    entries: HashMap<PathBuf, ClipMeta>,
+}
+
+impl ClipMetaStore {
+    pub fn load() -> Self {
+        let mut store = Self::default();
+        let Some(path) = store_path() else {
+            return store;
+        };
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return store;
+        };
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) else {
+            return store;
+        };
+        let Some(clips) = value["clips"].as_array() else {
+            return store;
+        };
+
+        for clip in clips {
+            let Some(path) = clip["path"].as_str() else {
+                continue;
+            };
+            let tags = clip["tags"]
+                .as_array()
+                .map(|tags| {
+                    tags.iter()
+                        .filter_map(|t| t.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            store.entries.insert(
+                PathBuf::from(path),
+                ClipMeta {
+                    starred: clip["starred"].as_bool().unwrap_or(false),
+                    tags,
+                },
+            );
+        }
+        store
+    }
+
+    pub fn save(&self) {
+        let Some(path) = store_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            if std::fs::create_dir_all(dir).is_err() {
+                return;
+            }
+        }
+        let clips: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(path, meta)| {
+                json!({
+                    "path": path.to_string_lossy(),
+                    "starred": meta.starred,
+                    "tags": meta.tags,
+                })
+            })
+            .collect();
+        if let Ok(text) = serde_json::to_string_pretty(&json!({ "clips": clips })) {
+            let _ = std::fs::write(path, text);
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&ClipMeta> {
+        self.entries.get(path)
+    }
+
+    // Only clips in the current project are rewritten; entries for folders that aren't
+    // imported this session are kept as they were.
+    pub fn update_from(&mut self, project: &ProjectState) {
+        for (id, clip) in &project.clips {
+            let meta = ClipMeta {
+                starred: project.starred.contains(id),
+                tags: project
+                    .tags
+                    .names(project.clip_tag_mask(*id))
+                    .map(str::to_string)
+                    .collect(),
+            };
+            if meta == ClipMeta::default() {
+                self.entries.remove(&clip.path);
+            } else {
+                self.entries.insert(clip.path.clone(), meta);
+            }
+        }
+    }
+}
+
+fn store_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("clip-meta.json"))
+}
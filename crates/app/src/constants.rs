@@ -1,16 +1,23 @@
 pub const SHADOW_LOOKAHEAD_S: f64 = 3.0;
+pub const SHADOW_LOOKAHEAD_MAX_S: f64 = 8.0;
+pub const SHADOW_LOOKAHEAD_CLIP_FRACTION: f64 = 0.25;
+pub const SHADOW_BUFFER_FRAMES: usize = 4;
+pub const PREWARM_DEBOUNCE_S: f64 = 0.15;
 pub const STALE_PIPELINE_THRESHOLD_S: f64 = 0.75;
 pub const PIPELINE_STALL_THRESHOLD_S: f64 = 0.08;
 pub const FRAME_GAP_STALL_S: f64 = 0.12;
 pub const FRAME_GAP_LONG_STALL_S: f64 = 0.25;
 pub const REVERSE_FORCE_PLAYING_AFTER_S: f64 = 0.075;
 pub const REVERSE_STARTUP_TIMEOUT_S: f64 = 0.35;
+pub const REVERSE_WATCHDOG_S: f64 = 4.0;
+pub const SOURCE_MONITOR_END_SLACK_S: f64 = 0.05;
+pub const SOURCE_MONITOR_EOS_S: f64 = 0.5;
 pub const HOVER_AUDIO_BUCKET_RATE: f64 = 2.0;
 pub const SCRUB_AUDIO_BUCKET_RATE: f64 = 10.0;
+pub const TRIM_AUDIO_PREROLL_S: f64 = 0.5;
+pub const AUDIO_RECONCILE_INTERVAL_S: f64 = 0.25;
 pub const VIDEO_DECODE_BUCKET_RATE: f64 = 60.0;
 pub const FPS_WINDOW_S: f64 = 0.25;
-pub const PLAYHEAD_ADVANCE_MAX_DT_S: f64 = 0.033;
-pub const PLAYHEAD_ADVANCE_DEBT_MAX_S: f64 = 0.25;
 pub const SCRUB_DECODE_WIDTH: u32 = 1920;
 pub const SCRUB_DECODE_HEIGHT: u32 = 1080;
 pub const SCRUB_CACHE_WIDTH: u32 = 960;
@@ -22,3 +29,13 @@ pub const SCRUB_MAX_DECODE_FRAMES: u32 = 20;
 pub const PLAYBACK_MAX_DECODE_FRAMES: u32 = 180;
 pub const REWIND_CACHE_MAX_FRAMES: usize = 45;
 pub const REWIND_CACHE_MAX_BYTES: usize = 500_000_000;
+pub const AUDIO_DEVICE_REFRESH_S: f64 = 2.0;
+pub const FALLBACK_AUDIO_BUFFER_LEN: usize = 4096;
+pub const TEXTURE_IDLE_EVICT_S: f64 = 30.0;
+pub const TEXTURE_EVICT_INTERVAL_S: f64 = 1.0;
+pub const TEXTURE_MEMORY_BUDGET_BYTES: usize = 512_000_000;
+pub const PREVIEW_STRIP_MAX_CLIPS: usize = 48;
+pub const EXPORT_AUDIO_SAMPLE_RATE: u32 = 48_000;
+pub const EXPORT_AUDIO_CHANNELS: u16 = 2;
+pub const TASK_REPAINT_INTERVAL_S: f64 = 0.1;
+pub const SHUTDOWN_JOIN_TIMEOUT_S: f64 = 1.0;
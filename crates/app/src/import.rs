@@ -1,40 +1,172 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::SystemTime;
 
 use notify::{RecursiveMode, Watcher};
+use wizard_state::clip::ClipId;
 
+use crate::workers::import_worker::ImportRequest;
 use crate::EditorApp;
 
 impl EditorApp {
+    // Importing a path that's already in the project never adds a second clip. If the file
+    // has changed on disk since it was read, as when it's been replaced under the same name,
+    // the existing clip re-reads it in place and stays wherever it is on the timeline.
     pub fn import_file(&mut self, p: PathBuf) {
-        if self.known_paths.contains(&p) {
-            return;
+        let mtime = modified_time(&p);
+        if let Some(&(clip_id, known_mtime)) = self.known_paths.get(&p) {
+            if self.state.project.clips.contains_key(&clip_id) {
+                if mtime != known_mtime {
+                    self.known_paths.insert(p.clone(), (clip_id, mtime));
+                    self.reload_clip(clip_id, p);
+                }
+                return;
+            }
         }
-        self.known_paths.insert(p.clone());
 
         let clip = wizard_state::clip::Clip::from_path(p.clone());
         let clip_id = clip.id;
+        self.known_paths.insert(p.clone(), (clip_id, mtime));
         self.state.project.add_clip(clip);
+        if let Some(meta) = self.clip_meta.get(&p) {
+            self.state
+                .project
+                .restore_organization(clip_id, meta.starred, &meta.tags);
+        }
         self.textures.pending_thumbnails.insert(clip_id);
 
-        let ttx = self.thumb_tx.clone();
-        let mtx = self.meta_tx.clone();
-        let wtx = self.waveform_tx.clone();
-        std::thread::spawn(move || {
-            let meta = wizard_media::metadata::extract_metadata(&p);
-            let has_video = meta.has_video;
-            let _ = mtx.send((clip_id, meta));
-
-            if has_video {
-                if let Some(img) = wizard_media::thumbnail::extract_thumbnail(&p) {
-                    let _ = ttx.send((clip_id, img));
-                }
+        let _ = self.import.probe_tx.send((clip_id, p.clone()));
+        let _ = self
+            .import
+            .req_tx
+            .send(ImportRequest::Enqueue { clip_id, path: p });
+    }
+
+    // Points an existing clip at a new file. Timeline instances keep their positions and are
+    // clamped to the new duration once its metadata comes back.
+    pub fn replace_source(&mut self, clip_id: ClipId, path: PathBuf) {
+        let Some(clip) = self.state.project.clips.get(&clip_id) else {
+            return;
+        };
+        if clip.path == path {
+            return;
+        }
+        self.known_paths.remove(&clip.path);
+        self.known_paths
+            .insert(path.clone(), (clip_id, modified_time(&path)));
+        self.reload_clip(clip_id, path);
+    }
+
+    // Rebuilds a clip from the file at `path`, keeping its id and name, and sends it back
+    // through import for fresh metadata, thumbnails and waveforms.
+    fn reload_clip(&mut self, clip_id: ClipId, path: PathBuf) {
+        let Some(clip) = self.state.project.clips.get_mut(&clip_id) else {
+            return;
+        };
+        let mut replacement = wizard_state::clip::Clip::from_path(path.clone());
+        replacement.id = clip_id;
+        replacement.display_name = clip.display_name.take();
+        *clip = replacement;
+        let tag_mask = self.state.project.clip_tag_mask(clip_id);
+        if let Some(clip) = self.state.project.clips.get_mut(&clip_id) {
+            clip.rebuild_search_haystack(&self.state.project.tags, tag_mask);
+        }
+
+        self.textures.clear_clip(clip_id);
+        self.textures.pending_thumbnails.insert(clip_id);
+        let _ = self.import.probe_tx.send((clip_id, path.clone()));
+        let _ = self
+            .import
+            .req_tx
+            .send(ImportRequest::Enqueue { clip_id, path });
+    }
+
+    // Clips the browser is showing jump the import queue so a big folder drop fills in
+    // what the user is looking at first.
+    pub fn prioritize_visible_imports(&mut self) {
+        let visible: Vec<ClipId> = self
+            .state
+            .ui
+            .browser
+            .visible_clips
+            .iter()
+            .copied()
+            .filter(|id| self.textures.pending_thumbnails.contains(id))
+            .collect();
+        if visible.is_empty() || visible == self.last_import_priority {
+            return;
+        }
+        self.last_import_priority = visible.clone();
+        let _ = self
+            .import
+            .req_tx
+            .send(ImportRequest::Prioritize { clip_ids: visible });
+    }
+
+    // Switching checks on analyses what is already imported: silence comes from finished
+    // waveforms, black openings need another decode. Switching off only hides the badges.
+    pub fn sync_quality_checks(&mut self) {
+        let enabled = self.state.ui.browser.quality_checks;
+        if self.import.quality_checks.swap(enabled, Ordering::Relaxed) == enabled || !enabled {
+            return;
+        }
+        let ids: Vec<ClipId> = self.state.project.clips.keys().copied().collect();
+        for id in ids {
+            self.update_silence_flag(id);
+            let Some(clip) = self.state.project.clips.get(&id) else {
+                continue;
+            };
+            if clip.metadata_loaded && !clip.audio_only {
+                let _ = self.import.req_tx.send(ImportRequest::Analyze {
+                    clip_id: id,
+                    path: clip.path.clone(),
+                });
             }
+        }
+    }
 
-            let peaks = wizard_media::audio::extract_waveform_peaks(&p, 512);
-            if !peaks.is_empty() {
-                let _ = wtx.send((clip_id, peaks));
+    // Split waveforms double the peak data, so they're only decoded for clips on audio tracks
+    // while the option is on, and dropped again when it's switched off.
+    pub fn enqueue_stereo_waveforms(&mut self) {
+        if !self.state.ui.timeline.stereo_waveforms {
+            if !self.textures.stereo_requested.is_empty() {
+                self.textures.stereo_requested.clear();
+                self.textures.stereo_waveform_peaks.clear();
+            }
+            return;
+        }
+        for track in &self.state.project.timeline.audio_tracks {
+            for tc in &track.clips {
+                if self.textures.stereo_requested.contains(&tc.source_id) {
+                    continue;
+                }
+                let Some(clip) = self.state.project.clips.get(&tc.source_id) else {
+                    continue;
+                };
+                if !clip.metadata_loaded || !clip.has_audio {
+                    continue;
+                }
+                self.textures.stereo_requested.insert(tc.source_id);
+                let _ = self.import.req_tx.send(ImportRequest::StereoWaveform {
+                    clip_id: tc.source_id,
+                    path: clip.path.clone(),
+                });
             }
-        });
+        }
+    }
+
+    pub fn update_silence_flag(&mut self, id: ClipId) {
+        if self.textures.waveform_progress.contains_key(&id) {
+            return;
+        }
+        let levels = self.textures.waveform_peaks.get(&id);
+        let Some(coarsest) = levels.and_then(|l| l.first()) else {
+            return;
+        };
+        let silent = wizard_media::audio::peaks_are_silent(coarsest);
+        if let Some(clip) = self.state.project.clips.get_mut(&id) {
+            clip.silent = silent;
+        }
     }
 
     pub fn import_folder(&mut self, path: PathBuf) {
@@ -48,7 +180,13 @@ impl EditorApp {
             notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
                 let Ok(event) = res else { return };
                 use notify::EventKind;
-                if !matches!(event.kind, EventKind::Create(_)) {
+                // Files moved into place count as new too; import_file sorts out the ones
+                // that are already known.
+                let arrived = matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(notify::event::ModifyKind::Name(_))
+                );
+                if !arrived {
                     return;
                 }
                 for p in event.paths {
@@ -73,3 +211,7 @@ impl EditorApp {
         }
     }
 }
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
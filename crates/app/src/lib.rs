@@ -1,28 +1,40 @@
 mod audio_mixer;
 mod channel_polling;
+mod clip_meta;
 mod constants;
 mod import;
 pub mod pipeline;
 mod playback;
 mod playback_engine;
+mod settings;
 pub mod texture_cache;
 pub mod workers;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 
 use notify::RecommendedWatcher;
 use wizard_audio::output::AudioOutput;
+use wizard_media::audio::WaveformChunk;
 use wizard_media::metadata::MediaMetadata;
 use wizard_state::clip::ClipId;
-use wizard_state::playback::PlaybackState;
-use wizard_state::project::AppState;
+use wizard_state::playback::{PlaybackState, ReverseBudget, ScrubQuality};
+use wizard_state::project::{AppState, AudioLatency, ZoomAnchor};
+use wizard_state::task::BackgroundTasks;
+use wizard_state::timeline::{OverlapPolicy, MAX_GAIN_DB, MIN_GAIN_DB};
 
-use crate::constants::{PLAYHEAD_ADVANCE_DEBT_MAX_S, PLAYHEAD_ADVANCE_MAX_DT_S};
+use crate::constants::{
+    AUDIO_DEVICE_REFRESH_S, FALLBACK_AUDIO_BUFFER_LEN, SHUTDOWN_JOIN_TIMEOUT_S,
+    TASK_REPAINT_INTERVAL_S,
+};
+use clip_meta::ClipMetaStore;
 use playback_engine::PlaybackEngine;
+use settings::PersistedSettings;
 use texture_cache::TextureCache;
+use workers::import_worker::{ImportSenders, ImportWorkerChannels};
 use workers::preview_worker::PreviewWorkerChannels;
 use workers::scrub_cache_worker::ScrubCacheWorkerChannels;
 
@@ -31,22 +43,29 @@ pub struct EditorApp {
     textures: TextureCache,
     playback: PlaybackEngine,
 
-    thumb_tx: mpsc::Sender<(ClipId, image::RgbaImage)>,
     thumb_rx: mpsc::Receiver<(ClipId, image::RgbaImage)>,
-    meta_tx: mpsc::Sender<(ClipId, MediaMetadata)>,
     meta_rx: mpsc::Receiver<(ClipId, MediaMetadata)>,
+    duration_rx: mpsc::Receiver<(ClipId, f64)>,
+    import: ImportWorkerChannels,
+    last_import_priority: Vec<ClipId>,
     preview: PreviewWorkerChannels,
+    preview_visible: HashSet<ClipId>,
     scrub_cache: ScrubCacheWorkerChannels,
-    waveform_tx: mpsc::Sender<(ClipId, Vec<(f32, f32)>)>,
-    waveform_rx: mpsc::Receiver<(ClipId, Vec<(f32, f32)>)>,
+    waveform_rx: mpsc::Receiver<(ClipId, WaveformChunk)>,
+    black_rx: mpsc::Receiver<(ClipId, bool)>,
 
     folder_watcher: Option<RecommendedWatcher>,
     watch_rx: mpsc::Receiver<PathBuf>,
     watch_tx: mpsc::Sender<PathBuf>,
-    known_paths: HashSet<PathBuf>,
+    // Each imported file's clip and its modification time when it was last read.
+    known_paths: HashMap<PathBuf, (ClipId, Option<SystemTime>)>,
 
     last_frame_time: Option<f64>,
-    playhead_advance_debt_s: f64,
+    saved_settings: PersistedSettings,
+    clip_meta: ClipMetaStore,
+    saved_organize_revision: u64,
+    audio_devices: Vec<String>,
+    audio_devices_listed_at: Option<f64>,
 }
 
 impl EditorApp {
@@ -69,20 +88,37 @@ impl EditorApp {
 
         let (thumb_tx, thumb_rx) = mpsc::channel();
         let (meta_tx, meta_rx) = mpsc::channel();
-        let (waveform_tx, waveform_rx) = mpsc::channel::<(ClipId, Vec<(f32, f32)>)>();
+        let (duration_tx, duration_rx) = mpsc::channel();
+        let (waveform_tx, waveform_rx) = mpsc::channel::<(ClipId, WaveformChunk)>();
+        let (black_tx, black_rx) = mpsc::channel();
+        let tasks = BackgroundTasks::default();
 
+        let import = workers::import_worker::spawn_import_worker(ImportSenders {
+            meta_tx,
+            duration_tx,
+            thumb_tx,
+            waveform_tx,
+            black_tx,
+            tasks: tasks.clone(),
+        });
         let preview = workers::preview_worker::spawn_preview_worker();
         let scrub_cache = workers::scrub_cache_worker::spawn_scrub_cache_worker();
 
+        let saved_settings = PersistedSettings::load();
+        let mut audio_error = None;
         let (audio_output, audio_producer, audio_sample_rate, audio_channels) =
-            match AudioOutput::new() {
+            match AudioOutput::open(
+                saved_settings.audio.output_device.as_deref(),
+                saved_settings.audio.latency.buffer_ms(),
+            ) {
                 Ok((output, producer)) => {
                     let sr = output.sample_rate_hz();
                     let ch = output.channels();
                     (Some(output), producer, sr, ch)
                 }
-                Err(_) => {
-                    let rb = ringbuf::HeapRb::<f32>::new(4096);
+                Err(e) => {
+                    audio_error = Some(e);
+                    let rb = ringbuf::HeapRb::<f32>::new(FALLBACK_AUDIO_BUFFER_LEN);
                     let (producer, _consumer) = ringbuf::traits::Split::split(rb);
                     (None, producer, 48000, 2)
                 }
@@ -93,34 +129,91 @@ impl EditorApp {
 
         let audio_producer = Arc::new(Mutex::new(audio_producer));
 
-        let playback = PlaybackEngine::new(
+        let mut playback = PlaybackEngine::new(
             audio_output,
+            saved_settings.audio.output_device.clone(),
+            saved_settings.audio.latency,
             audio_producer,
             audio_sample_rate,
             audio_channels,
             no_audio_paths,
         );
+        playback.audio_error = audio_error;
+
+        let mut state = AppState {
+            tasks,
+            ..Default::default()
+        };
+        state.ui.audio = saved_settings.audio.clone();
+        state.ui.playback = saved_settings.playback.clone();
+        state.ui.timeline.overlap = saved_settings.overlap;
+        state.ui.timeline.link_audio_on_drop = saved_settings.link_audio_on_drop;
+        state.ui.timeline.stereo_waveforms = saved_settings.stereo_waveforms;
+        state.ui.timeline.snapping_enabled = saved_settings.snapping_enabled;
 
         Self {
-            state: AppState::default(),
+            state,
             textures: TextureCache::default(),
             playback,
-            thumb_tx,
             thumb_rx,
-            meta_tx,
             meta_rx,
+            duration_rx,
+            import,
+            last_import_priority: Vec::new(),
             preview,
+            preview_visible: HashSet::new(),
             scrub_cache,
-            waveform_tx,
             waveform_rx,
+            black_rx,
             folder_watcher: None,
             watch_rx,
             watch_tx,
-            known_paths: HashSet::new(),
+            known_paths: HashMap::new(),
             last_frame_time: None,
-            playhead_advance_debt_s: 0.0,
+            saved_settings,
+            clip_meta: ClipMetaStore::load(),
+            saved_organize_revision: 0,
+            audio_devices: Vec::new(),
+            audio_devices_listed_at: None,
         }
     }
+
+    fn export_audio(&self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("WAV audio", &["wav"])
+            .set_file_name("mix.wav")
+            .save_file()
+        else {
+            return;
+        };
+        workers::export_worker::spawn_audio_export(
+            self.state.project.timeline.clone(),
+            self.state.project.clips.clone(),
+            path,
+            self.state.ui.timeline.range_selection,
+            &self.state.tasks,
+        );
+    }
+
+    fn audio_device_picker(&mut self, ui: &mut egui::Ui, now: f64) {
+        let selected = &mut self.state.ui.audio.output_device;
+        let devices = &mut self.audio_devices;
+        let listed_at = &mut self.audio_devices_listed_at;
+        egui::ComboBox::from_label("Output")
+            .selected_text(selected.as_deref().unwrap_or("System default"))
+            .show_ui(ui, |ui| {
+                // Enumerating devices is slow on some hosts, so only refresh while the list
+                // is open and not every frame.
+                if listed_at.is_none_or(|t| now - t > AUDIO_DEVICE_REFRESH_S) {
+                    *devices = wizard_audio::output::output_device_names();
+                    *listed_at = Some(now);
+                }
+                ui.selectable_value(selected, None, "System default");
+                for name in devices.iter() {
+                    ui.selectable_value(selected, Some(name.clone()), name);
+                }
+            });
+    }
 }
 
 impl eframe::App for EditorApp {
@@ -130,7 +223,7 @@ impl eframe::App for EditorApp {
         let now = ctx.input(|i| i.time);
         if let Some(last) = self.last_frame_time {
             let dt = now - last;
-            let duration = self.state.project.timeline.timeline_duration();
+            let end = self.state.project.playback_end();
             let has_pending = self.playback.pending_forward.is_some();
             let should_advance = match self.state.project.playback.state {
                 PlaybackState::Stopped => false,
@@ -161,16 +254,11 @@ impl eframe::App for EditorApp {
                 }
             };
             if should_advance {
-                let total_advance = dt + self.playhead_advance_debt_s;
-                let dt_for_advance = total_advance.min(PLAYHEAD_ADVANCE_MAX_DT_S);
-                self.playhead_advance_debt_s =
-                    (total_advance - dt_for_advance).min(PLAYHEAD_ADVANCE_DEBT_MAX_S);
-                self.state
-                    .project
-                    .playback
-                    .advance(dt_for_advance, duration);
+                self.playback
+                    .clock
+                    .advance(&mut self.state.project.playback, dt, end);
             } else {
-                self.playhead_advance_debt_s = 0.0;
+                self.playback.clock.reset();
             }
             if dt > 0.0 {
                 let inst_fps = (1.0 / dt) as f32;
@@ -198,8 +286,12 @@ impl eframe::App for EditorApp {
         self.playback.manage_shadow_pipeline(&mut self.state, now);
         self.playback
             .manage_reverse_shadow_pipeline(&mut self.state, now);
+        self.sync_quality_checks();
         self.poll_import_tasks(ctx);
         self.poll_folder_watcher();
+        for failure in self.state.tasks.prune_finished() {
+            self.state.ui.toasts.push(failure, now);
+        }
         egui::TopBottomPanel::top("top_panel")
             .exact_height(28.0)
             .show(ctx, |ui| {
@@ -211,9 +303,152 @@ impl eframe::App for EditorApp {
                             self.state.ui.debug.ui_fps, self.state.ui.debug.video_fps
                         ));
                     }
+                    ui.separator();
+                    ui.checkbox(&mut self.state.ui.audio.scrub_audio, "Scrub audio");
+                    ui.add(
+                        egui::Slider::new(&mut self.state.ui.audio.preview_volume, 0.0..=1.0)
+                            .text("Preview vol")
+                            .show_value(false),
+                    );
+                    self.audio_device_picker(ui, now);
+                    let latency = &mut self.state.ui.audio.latency;
+                    egui::ComboBox::from_label("Latency")
+                        .selected_text(latency.label())
+                        .show_ui(ui, |ui| {
+                            for option in AudioLatency::ALL {
+                                ui.selectable_value(latency, option, option.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Lower is more responsive when scrubbing; higher avoids dropouts \
+                             on slow machines",
+                        );
+                    if !self.playback.audio_available() {
+                        let reason = self
+                            .playback
+                            .audio_error
+                            .as_deref()
+                            .unwrap_or("No audio output device was found");
+                        ui.colored_label(wizard_ui::theme::TEXT_DIM, "\u{1F507} No audio")
+                            .on_hover_text(format!(
+                                "{reason}. Playback continues silently until an output device \
+                                 is available."
+                            ));
+                        if ui.button("Retry audio").clicked() {
+                            self.state.project.playback.state = PlaybackState::Stopped;
+                            self.playback.reopen_audio_output();
+                        }
+                    }
+                    ui.separator();
+                    ui.add(
+                        egui::Slider::new(
+                            &mut self.playback.master_gain_db,
+                            MIN_GAIN_DB..=MAX_GAIN_DB,
+                        )
+                        .text("Master")
+                        .suffix(" dB")
+                        .fixed_decimals(1),
+                    );
+                    ui.separator();
+                    let (w, h) = self.state.project.settings.resolution();
+                    let fps = self.state.project.settings.fps;
+                    if ui
+                        .selectable_label(
+                            self.state.ui.project_settings_open,
+                            format!("{w}x{h} @ {fps:.2}"),
+                        )
+                        .on_hover_text("Project settings")
+                        .clicked()
+                    {
+                        self.state.ui.project_settings_open = !self.state.ui.project_settings_open;
+                    }
+                    let has_timeline = self.state.project.timeline.timeline_duration() > 0.0;
+                    let export_hint = if self.state.ui.timeline.range_selection.is_some() {
+                        "Mix unmuted audio tracks within the in/out range to a WAV file"
+                    } else {
+                        "Mix unmuted audio tracks to a WAV file"
+                    };
+                    if ui
+                        .add_enabled(has_timeline, egui::Button::new("Export audio"))
+                        .on_hover_text(export_hint)
+                        .clicked()
+                    {
+                        self.export_audio();
+                    }
+                    ui.separator();
+                    let budget = &mut self.state.ui.playback.reverse_budget;
+                    egui::ComboBox::from_label("Reverse quality")
+                        .selected_text(budget.label())
+                        .show_ui(ui, |ui| {
+                            for option in ReverseBudget::ALL {
+                                ui.selectable_value(budget, option, option.label());
+                            }
+                        });
+                    let scrub_quality = &mut self.state.ui.playback.scrub_quality;
+                    egui::ComboBox::from_label("Scrub quality")
+                        .selected_text(scrub_quality.label())
+                        .show_ui(ui, |ui| {
+                            for option in ScrubQuality::ALL {
+                                ui.selectable_value(scrub_quality, option, option.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Fast keeps scrubbing snappy for rough cuts; Accurate shows the \
+                             exact frame at full size for precise trims",
+                        );
+                    ui.checkbox(
+                        &mut self.state.ui.playback.snap_playhead_to_frames,
+                        "Frame snap",
+                    )
+                    .on_hover_text("Keep the playhead on whole project frames");
+                    ui.separator();
+                    let zoom_anchor = &mut self.state.ui.timeline.zoom_anchor;
+                    egui::ComboBox::from_id_salt("zoom_anchor")
+                        .selected_text(zoom_anchor.label())
+                        .show_ui(ui, |ui| {
+                            for option in ZoomAnchor::ALL {
+                                ui.selectable_value(zoom_anchor, option, option.label());
+                            }
+                        });
+                    ui.separator();
+                    let overlap = &mut self.state.ui.timeline.overlap;
+                    for (label, policy) in [
+                        ("Video overlap", &mut overlap.video),
+                        ("Audio overlap", &mut overlap.audio),
+                    ] {
+                        egui::ComboBox::from_label(label)
+                            .selected_text(policy.label())
+                            .show_ui(ui, |ui| {
+                                for option in OverlapPolicy::ALL {
+                                    ui.selectable_value(policy, option, option.label());
+                                }
+                            });
+                    }
+                    ui.checkbox(&mut self.state.ui.timeline.link_audio_on_drop, "Link audio")
+                        .on_hover_text(
+                            "Dropping a clip with sound also places its audio on the paired \
+                             track",
+                        );
+                    ui.checkbox(&mut self.state.ui.timeline.trim_mode, "Trim mode")
+                        .on_hover_text("Wider trim handles on timeline clips (T)");
+                    ui.checkbox(&mut self.state.ui.timeline.stereo_waveforms, "Stereo waves")
+                        .on_hover_text("Draw left and right channels as separate waveforms");
+                    ui.separator();
+                    wizard_ui::task_list::task_list(ui, &self.state.tasks);
                 });
             });
 
+        wizard_ui::project_settings::project_settings_window(ctx, &mut self.state);
+
+        let mismatches = self.state.project.format_mismatches();
+        if !mismatches.is_empty() {
+            egui::TopBottomPanel::top("format_warning_panel").show(ctx, |ui| {
+                wizard_ui::format_warning::format_warning_banner(ui, &mut self.state, &mismatches);
+            });
+        }
+
         let mut action = wizard_ui::browser::BrowserAction::None;
         egui::SidePanel::left("browser_panel")
             .width_range(200.0..=1200.0)
@@ -226,22 +461,29 @@ impl eframe::App for EditorApp {
             wizard_ui::browser::BrowserAction::ImportFolder(path) => {
                 self.import_folder(path);
             }
+            wizard_ui::browser::BrowserAction::ReplaceSource(clip_id, path) => {
+                self.replace_source(clip_id, path);
+            }
         }
 
-        //FOURTH PANEL
-
-       /* egui::SidePanel::right("inspector_panel")
+        egui::SidePanel::right("inspector_panel")
             .width_range(220.0..=520.0)
             .default_width(300.0)
             .show(ctx, |ui| {
-                wizard_ui::inspector::inspector_panel(ui, &mut self.state);
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    wizard_ui::inspector::inspector_panel(ui, &mut self.state);
+                });
             });
-*/
-        self.enqueue_visible_previews();
+
+        self.prioritize_visible_imports();
+        self.manage_texture_budget(now);
+        self.enqueue_visible_previews(now);
         self.enqueue_scrub_cache_for_timeline_clips();
+        self.enqueue_stereo_waveforms();
         self.playback
             .update_hover_audio(&self.state, &self.textures);
         self.playback.update_timeline_scrub_audio(&self.state);
+        self.playback.update_trim_scrub_audio(&self.state);
         self.playback
             .update_playback_frame(&mut self.state, &mut self.textures, now);
 
@@ -292,18 +534,70 @@ impl eframe::App for EditorApp {
                         ui.label("Clip: none");
                     }
 
-                    if self.playback.shadow.is_some() {
-                        ui.label("Shadow: active");
+                    if let Some(ref shadow) = self.playback.shadow {
+                        ui.label(format!(
+                            "Shadow: active ({} buffered)",
+                            shadow.buffered_frames.len()
+                        ));
+                    }
+                    if let Some((latency, prewarmed)) = self.playback.last_startup_latency {
+                        ui.label(format!(
+                            "Startup: {:.0} ms ({})",
+                            latency * 1000.0,
+                            if prewarmed { "prewarmed" } else { "cold" }
+                        ));
+                    }
+                    if let Some(count) = self.playback.last_shadow_buffered_frames {
+                        ui.label(format!("Shadow at promotion: {count} frames"));
                     }
                     if self.playback.reverse_shadow.is_some() {
                         ui.label("RevShadow: active");
                     }
-                    let cache_len = self.playback.rewind_cache.len();
-                    if cache_len > 0 {
-                        ui.label(format!("RewindCache: {cache_len}"));
+                    let cache = &self.playback.rewind_cache;
+                    if let Some((start, end)) = cache.time_span() {
+                        ui.label(format!(
+                            "RewindCache: {} frames [{start:.3}..{end:.3}] {:.1} MB",
+                            cache.len(),
+                            cache.byte_size() as f64 / 1_000_000.0
+                        ));
                     }
                 });
         }
+        if self.state.ui.audio != self.saved_settings.audio
+            || self.state.ui.playback != self.saved_settings.playback
+            || self.state.ui.timeline.overlap != self.saved_settings.overlap
+            || self.state.ui.timeline.link_audio_on_drop != self.saved_settings.link_audio_on_drop
+            || self.state.ui.timeline.stereo_waveforms != self.saved_settings.stereo_waveforms
+            || self.state.ui.timeline.snapping_enabled != self.saved_settings.snapping_enabled
+        {
+            self.saved_settings.audio = self.state.ui.audio.clone();
+            self.saved_settings.playback = self.state.ui.playback.clone();
+            self.saved_settings.overlap = self.state.ui.timeline.overlap;
+            self.saved_settings.link_audio_on_drop = self.state.ui.timeline.link_audio_on_drop;
+            self.saved_settings.stereo_waveforms = self.state.ui.timeline.stereo_waveforms;
+            self.saved_settings.snapping_enabled = self.state.ui.timeline.snapping_enabled;
+            self.saved_settings.save();
+        }
+        if self.state.project.organize_revision != self.saved_organize_revision {
+            self.saved_organize_revision = self.state.project.organize_revision;
+            self.clip_meta.update_from(&self.state.project);
+            self.clip_meta.save();
+        }
+
+        if self.state.ui.audio.output_device != self.playback.output_device
+            || self.state.ui.audio.latency != self.playback.audio_latency
+        {
+            self.playback.output_device = self.state.ui.audio.output_device.clone();
+            self.playback.audio_latency = self.state.ui.audio.latency;
+            if self.playback.is_playing(&self.state) {
+                // Running pipelines were built for the old device's format; stopping lets the
+                // stop transition reopen the output cleanly.
+                self.state.project.playback.state = PlaybackState::Stopped;
+            } else {
+                self.playback.refresh_audio_output();
+            }
+        }
+
         let is_playing = self.playback.is_playing(&self.state);
         if was_playing && !is_playing {
             self.playback.handle_playback_stop_transition();
@@ -316,12 +610,43 @@ impl eframe::App for EditorApp {
         let received =
             self.playback
                 .poll_pipeline_frames(&mut self.state, &mut self.textures, ctx, now);
-        if received {
+        let monitor_received =
+            self.playback
+                .manage_source_monitor(&mut self.state, &mut self.textures, ctx, now);
+        if received || monitor_received {
             ctx.request_repaint();
         }
 
-        if self.state.project.playback.state != PlaybackState::Stopped {
+        for message in self.playback.pipeline_errors.drain(..) {
+            self.state.ui.toasts.push(message, now);
+        }
+        self.state.ui.toasts.expire(now);
+        wizard_ui::toasts::toasts(ctx, &mut self.state.ui.toasts);
+        if let Some(remaining) = self.state.ui.toasts.next_expiry(now) {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(remaining));
+        }
+
+        if self.state.project.playback.state != PlaybackState::Stopped
+            || self.state.ui.preview.source_monitor.is_some()
+        {
             ctx.request_repaint();
         }
+
+        // Workers don't wake the UI, so poll while anything is reporting progress.
+        if !self.state.tasks.active().is_empty() {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(TASK_REPAINT_INTERVAL_S));
+        }
+    }
+
+    // Everything is signalled to stop before anything is waited on, so the workers wind
+    // down side by side and the wait is bounded by the slowest rather than their sum.
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.folder_watcher = None;
+        let mut threads = self.playback.shutdown();
+        threads.extend(self.import.shutdown());
+        threads.extend(self.preview.shutdown());
+        threads.extend(self.scrub_cache.shutdown());
+        let deadline = Instant::now() + Duration::from_secs_f64(SHUTDOWN_JOIN_TIMEOUT_S);
+        workers::join_until(threads, deadline);
     }
 }
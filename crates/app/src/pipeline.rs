@@ -1,13 +1,18 @@
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc, Mutex};
 
-use wizard_media::gst_pipeline::{GstAudioOnlyHandle, GstPipelineHandle, GstReversePipelineHandle};
-use wizard_media::pipeline::DecodedFrame;
+use wizard_media::gst_pipeline::{
+    AudioPacing, GstAudioOnlyHandle, GstPipelineHandle, GstReversePipelineHandle,
+};
+use wizard_media::pipeline::{AudioProducer, DecodedFrame};
 use wizard_state::clip::ClipId;
-use wizard_state::timeline::TimelineClipId;
+use wizard_state::playback::ReverseBudget;
+use wizard_state::timeline::{SourceEnvelope, TimelineClipId, TrackId};
 
 use crate::audio_mixer::AudioMixer;
 use crate::constants::*;
+use crate::workers::video_decode_worker::{PLAYBACK_DECODE_HEIGHT, PLAYBACK_DECODE_WIDTH};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PipelineStatus {
@@ -34,6 +39,7 @@ pub struct ForwardPipelineState {
     pub started_at: f64,
     pub last_frame_time: Option<f64>,
     pub age: u32,
+    pub prebuffered: VecDeque<DecodedFrame>,
 }
 
 impl ForwardPipelineState {
@@ -59,13 +65,21 @@ impl ForwardPipelineState {
     }
 }
 
+pub type ShadowAudioSource = (
+    GstAudioOnlyHandle,
+    wizard_audio::output::AudioConsumer,
+    TrackId,
+    TimelineClipId,
+    SourceEnvelope,
+);
+
 pub struct ShadowPipelineState {
     pub handle: GstPipelineHandle,
     pub clip: (ClipId, PathBuf),
     pub timeline_clip: TimelineClipId,
     pub first_frame_ready: bool,
-    pub buffered_frame: Option<DecodedFrame>,
-    pub audio_sources: Vec<(GstAudioOnlyHandle, wizard_audio::output::AudioConsumer)>,
+    pub buffered_frames: VecDeque<DecodedFrame>,
+    pub audio_sources: Vec<ShadowAudioSource>,
 }
 
 pub struct ReversePipelineState {
@@ -119,13 +133,14 @@ impl PendingReversePipeline {
     pub fn spawn(
         path: &Path,
         source_time: f64,
-        target_w: u32,
-        target_h: u32,
+        budget: ReverseBudget,
         speed: f64,
         clip_id: ClipId,
         timeline_clip_id: TimelineClipId,
         now: f64,
     ) -> Self {
+        let (target_w, target_h) =
+            budget.decode_size(PLAYBACK_DECODE_WIDTH, PLAYBACK_DECODE_HEIGHT);
         let (tx, rx) = mpsc::channel();
         let path_buf = path.to_path_buf();
         std::thread::Builder::new()
@@ -137,6 +152,7 @@ impl PendingReversePipeline {
                     speed,
                     target_w,
                     target_h,
+                    budget.buffered_frames(),
                 );
                 let _ = tx.send(result);
             })
@@ -155,6 +171,62 @@ impl PendingReversePipeline {
     }
 }
 
+// The browser's source monitor: a plain forward pipeline over the whole source, with its
+// sound going straight to the output while the timeline is stopped.
+pub struct SourceMonitorPipeline {
+    pub handle: GstPipelineHandle,
+    pub clip: (ClipId, PathBuf),
+    pub start: f64,
+    pub pts_offset: Option<f64>,
+    pub activated: bool,
+    pub last_frame_time: Option<f64>,
+}
+
+pub struct PendingSourceMonitorPipeline {
+    pub rx: mpsc::Receiver<Result<GstPipelineHandle, String>>,
+    pub clip: (ClipId, PathBuf),
+    pub start: f64,
+}
+
+impl PendingSourceMonitorPipeline {
+    pub fn spawn(
+        path: &Path,
+        source_time: f64,
+        audio_producer: Arc<Mutex<AudioProducer>>,
+        audio_sample_rate: u32,
+        audio_channels: u16,
+        clip_id: ClipId,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let path_buf = path.to_path_buf();
+        std::thread::Builder::new()
+            .name("source-monitor-spawn".into())
+            .spawn(move || {
+                let result = GstPipelineHandle::start(
+                    &path_buf,
+                    source_time,
+                    PLAYBACK_DECODE_WIDTH,
+                    PLAYBACK_DECODE_HEIGHT,
+                    Some(audio_producer),
+                    audio_sample_rate,
+                    audio_channels,
+                    1.0,
+                );
+                let _ = tx.send(result);
+            })
+            .expect("failed to spawn source-monitor-spawn thread");
+        Self {
+            rx,
+            clip: (clip_id, path.to_path_buf()),
+            start: source_time,
+        }
+    }
+
+    pub fn try_recv(&self) -> Option<Result<GstPipelineHandle, String>> {
+        self.rx.try_recv().ok()
+    }
+}
+
 pub struct ReverseShadowState {
     pub handle: GstReversePipelineHandle,
     pub clip: (ClipId, PathBuf),
@@ -166,11 +238,14 @@ pub struct ReverseShadowState {
 pub struct ShadowAudioSourceRequest {
     pub path: PathBuf,
     pub source_time: f64,
+    pub track_id: TrackId,
+    pub timeline_clip: TimelineClipId,
+    pub envelope: SourceEnvelope,
 }
 
 pub struct ShadowPipelineBuild {
     pub handle: GstPipelineHandle,
-    pub audio_sources: Vec<(GstAudioOnlyHandle, wizard_audio::output::AudioConsumer)>,
+    pub audio_sources: Vec<ShadowAudioSource>,
 }
 
 pub struct PendingShadowPipeline {
@@ -241,6 +316,7 @@ impl PendingShadowPipeline {
         clip_id: ClipId,
         timeline_clip_id: TimelineClipId,
         audio_requests: Vec<ShadowAudioSourceRequest>,
+        source_ring_len: usize,
         now: f64,
     ) -> Self {
         let (tx, rx) = mpsc::channel();
@@ -267,7 +343,7 @@ impl PendingShadowPipeline {
 
                 let mut audio_sources = Vec::new();
                 for req in audio_requests {
-                    let (producer, consumer) = AudioMixer::create_source_producer();
+                    let (producer, consumer) = AudioMixer::create_source_producer(source_ring_len);
                     let source_producer = std::sync::Arc::new(std::sync::Mutex::new(producer));
                     if let Ok(audio_handle) = GstAudioOnlyHandle::start(
                         &req.path,
@@ -276,8 +352,15 @@ impl PendingShadowPipeline {
                         audio_sample_rate,
                         audio_channels,
                         speed,
+                        AudioPacing::Realtime,
                     ) {
-                        audio_sources.push((audio_handle, consumer));
+                        audio_sources.push((
+                            audio_handle,
+                            consumer,
+                            req.track_id,
+                            req.timeline_clip,
+                            req.envelope,
+                        ));
                     }
                 }
 
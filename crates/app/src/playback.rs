@@ -1,25 +1,61 @@
 use std::collections::HashSet;
 
+use crate::constants::PREVIEW_STRIP_MAX_CLIPS;
+use crate::workers::import_worker::ImportRequest;
 use crate::workers::preview_worker::PreviewRequest;
 use crate::workers::scrub_cache_worker::ScrubCacheRequest;
 use crate::EditorApp;
 
 impl EditorApp {
-    pub fn enqueue_visible_previews(&mut self) {
+    // Strips for the hovered and selected clips are always fetched; the rest are prefetched
+    // for what's on screen only while there's room under the strip cap, so nothing is
+    // requested just to be evicted again.
+    pub fn enqueue_visible_previews(&mut self, now: f64) {
         const PREFETCH_PER_FRAME: usize = 2;
-        let mut remaining = PREFETCH_PER_FRAME;
+        const PREFETCH_MAX_PER_FRAME: usize = 8;
 
-        if let Some(clip_id) = self.state.ui.selection.hovered_clip {
-            let _ = self.enqueue_preview_request(clip_id, true);
-        }
+        let hovered = self.state.ui.selection.hovered_clip;
+        let primary = self.state.ui.selection.primary_clip();
 
-        if let Some(clip_id) = self.state.ui.selection.primary_clip() {
+        for clip_id in hovered.into_iter().chain(primary) {
+            self.textures.preview_hovered_at.insert(clip_id, now);
             let _ = self.enqueue_preview_request(clip_id, true);
         }
+        let keep: Vec<wizard_state::clip::ClipId> = hovered.into_iter().chain(primary).collect();
+        for clip_id in self.textures.evict_preview_strips(&keep) {
+            let _ = self.preview.req_tx.send(PreviewRequest::Cancel { clip_id });
+        }
 
         let visible: Vec<wizard_state::clip::ClipId> = self.state.ui.browser.visible_clips.clone();
+        let visible_set: HashSet<wizard_state::clip::ClipId> = visible.iter().copied().collect();
+
+        // Clips that scrolled in since last frame stand in for scroll velocity.
+        let newly_visible = visible
+            .iter()
+            .filter(|id| !self.preview_visible.contains(id))
+            .count();
+        let mut remaining = (PREFETCH_PER_FRAME + newly_visible).min(PREFETCH_MAX_PER_FRAME);
+
+        let stale: Vec<wizard_state::clip::ClipId> = self
+            .textures
+            .preview_requested
+            .iter()
+            .filter(|id| {
+                !visible_set.contains(id)
+                    && Some(**id) != hovered
+                    && Some(**id) != primary
+                    && !self.textures.preview_frames.contains_key(id)
+            })
+            .copied()
+            .collect();
+        for clip_id in stale {
+            self.textures.preview_requested.remove(&clip_id);
+            let _ = self.preview.req_tx.send(PreviewRequest::Cancel { clip_id });
+        }
+        self.preview_visible = visible_set;
+
         for clip_id in visible {
-            if remaining == 0 {
+            if remaining == 0 || self.textures.preview_strip_count() >= PREVIEW_STRIP_MAX_CLIPS {
                 break;
             }
             if self.enqueue_preview_request(clip_id, false) {
@@ -52,6 +88,48 @@ impl EditorApp {
         true
     }
 
+    pub fn manage_texture_budget(&mut self, now: f64) {
+        let mut in_use: HashSet<wizard_state::clip::ClipId> = self
+            .state
+            .ui
+            .browser
+            .visible_clips
+            .iter()
+            .copied()
+            .collect();
+        in_use.extend(self.state.ui.selection.hovered_clip);
+        in_use.extend(self.state.ui.selection.selected_clips.iter().copied());
+        for track in &self.state.project.timeline.video_tracks {
+            in_use.extend(track.clips.iter().map(|c| c.source_id));
+        }
+        for &clip_id in &in_use {
+            self.textures.touch(clip_id, now);
+        }
+
+        self.textures.evict_stale_textures(now);
+
+        // Preview strips come back through `enqueue_visible_previews`; thumbnails only
+        // arrive with the import, so ask for them again once the clip is needed.
+        let wanted: Vec<wizard_state::clip::ClipId> = self
+            .textures
+            .evicted_thumbnails
+            .iter()
+            .filter(|id| in_use.contains(id))
+            .copied()
+            .collect();
+        for clip_id in wanted {
+            let Some(clip) = self.state.project.clips.get(&clip_id) else {
+                continue;
+            };
+            self.textures.evicted_thumbnails.remove(&clip_id);
+            self.textures.pending_thumbnails.insert(clip_id);
+            let _ = self.import.req_tx.send(ImportRequest::Thumbnail {
+                clip_id,
+                path: clip.path.clone(),
+            });
+        }
+    }
+
     pub fn enqueue_scrub_cache_for_timeline_clips(&mut self) {
         let mut on_timeline: HashSet<wizard_state::clip::ClipId> = HashSet::new();
         for track in &self.state.project.timeline.video_tracks {
@@ -1,8 +1,10 @@
+use std::collections::VecDeque;
+
 use wizard_media::pipeline::DecodedFrame;
 use wizard_state::clip::ClipId;
 use wizard_state::playback::PlaybackState;
 use wizard_state::project::AppState;
-use wizard_state::timeline::TimelineClipId;
+use wizard_state::timeline::{SourceEnvelope, TimelineClipId};
 
 use crate::constants::*;
 use crate::pipeline::{
@@ -32,22 +34,14 @@ impl PlaybackEngine {
         let current_timeline_clip = fwd.timeline_clip;
 
         if let Some(ref shadow) = self.shadow {
-            if let Some(next_hit) = state
-                .project
-                .timeline
-                .next_clip_after(current_timeline_clip)
-            {
+            if let Some(next_hit) = state.project.next_clip_after(current_timeline_clip) {
                 if shadow.timeline_clip == next_hit.clip.id {
                     return;
                 }
             }
         }
         if let Some(ref shadow) = self.pending_shadow {
-            if let Some(next_hit) = state
-                .project
-                .timeline
-                .next_clip_after(current_timeline_clip)
-            {
+            if let Some(next_hit) = state.project.next_clip_after(current_timeline_clip) {
                 if shadow.timeline_clip == next_hit.clip.id {
                     return;
                 }
@@ -63,15 +57,11 @@ impl PlaybackEngine {
         };
 
         let speed = state.project.playback.speed;
-        if remaining > SHADOW_LOOKAHEAD_S / speed {
+        if remaining > self.shadow_lookahead_s(state, current_timeline_clip) {
             return;
         }
 
-        let Some(next_hit) = state
-            .project
-            .timeline
-            .next_clip_after(current_timeline_clip)
-        else {
+        let Some(next_hit) = state.project.next_clip_after(current_timeline_clip) else {
             return;
         };
 
@@ -93,7 +83,7 @@ impl PlaybackEngine {
             .unwrap_or(state.project.playback.playhead);
 
         let mut audio_requests = Vec::new();
-        let audio_hits = state.project.timeline.audio_clips_at_time(next_time);
+        let audio_hits = state.project.audio_clips_at_time(next_time);
         for hit in audio_hits {
             let Some(aclip) = state.project.clips.get(&hit.clip.source_id) else {
                 continue;
@@ -104,6 +94,9 @@ impl PlaybackEngine {
             audio_requests.push(ShadowAudioSourceRequest {
                 path: aclip.path.clone(),
                 source_time: hit.source_time,
+                track_id: hit.track_id,
+                timeline_clip: hit.clip.id,
+                envelope: SourceEnvelope::for_clip(&hit.clip, hit.source_time, speed),
             });
         }
 
@@ -118,10 +111,33 @@ impl PlaybackEngine {
             next_clip_id,
             next_timeline_clip_id,
             audio_requests,
+            self.mixer.source_ring_len(),
             now,
         ));
     }
 
+    // Long clips and a decoder falling behind the project rate both need the next
+    // pipeline spun up earlier to have frames ready by the join.
+    fn shadow_lookahead_s(&self, state: &AppState, timeline_clip_id: TimelineClipId) -> f64 {
+        let clip_duration = state
+            .project
+            .timeline
+            .find_clip(timeline_clip_id)
+            .map(|(_, _, tc)| tc.duration)
+            .unwrap_or(0.0);
+        let base = SHADOW_LOOKAHEAD_S.max(clip_duration * SHADOW_LOOKAHEAD_CLIP_FRACTION);
+
+        let video_fps = state.ui.debug.video_fps as f64;
+        let target_fps = state.project.settings.fps;
+        let headroom = if video_fps > 0.0 && target_fps > 0.0 {
+            (video_fps / target_fps).clamp(0.25, 1.0)
+        } else {
+            1.0
+        };
+
+        (base / headroom).min(SHADOW_LOOKAHEAD_MAX_S) / state.project.playback.speed
+    }
+
     pub fn poll_pending_shadow_pipeline(&mut self, _now: f64) {
         let pending = match self.pending_shadow.as_ref() {
             Some(p) => p,
@@ -135,15 +151,18 @@ impl PlaybackEngine {
 
         let pending = self.pending_shadow.take().expect("checked above");
 
-        if let Ok(build) = result {
-            self.shadow = Some(ShadowPipelineState {
-                handle: build.handle,
-                clip: pending.clip,
-                timeline_clip: pending.timeline_clip,
-                first_frame_ready: false,
-                buffered_frame: None,
-                audio_sources: build.audio_sources,
-            });
+        match result {
+            Ok(build) => {
+                self.shadow = Some(ShadowPipelineState {
+                    handle: build.handle,
+                    clip: pending.clip,
+                    timeline_clip: pending.timeline_clip,
+                    first_frame_ready: false,
+                    buffered_frames: VecDeque::new(),
+                    audio_sources: build.audio_sources,
+                });
+            }
+            Err(e) => self.report_pipeline_error(&pending.clip.1, &e),
         }
     }
 
@@ -167,30 +186,34 @@ impl PlaybackEngine {
             return false;
         }
 
-        let shadow = self.shadow.take().expect("shadow checked above");
+        let mut shadow = self.shadow.take().expect("shadow checked above");
 
         let _ = shadow.handle.begin_playing();
 
+        self.last_shadow_buffered_frames = Some(shadow.buffered_frames.len());
+        let first_frame = shadow.buffered_frames.pop_front();
+
         let mut fwd = ForwardPipelineState {
             handle: shadow.handle,
             clip: shadow.clip,
             timeline_clip: shadow.timeline_clip,
             pts_offset: None,
             speed: state.project.playback.speed,
-            frame_delivered: shadow.buffered_frame.is_some(),
+            frame_delivered: first_frame.is_some(),
             activated: true,
             started_at: now,
-            last_frame_time: if shadow.buffered_frame.is_some() {
+            last_frame_time: if first_frame.is_some() {
                 Some(now)
             } else {
                 None
             },
             age: 0,
+            prebuffered: shadow.buffered_frames,
         };
 
         state.project.playback.playhead = next_time;
 
-        if let Some(frame) = shadow.buffered_frame {
+        if let Some(frame) = first_frame {
             textures.update_playback_texture(
                 ctx,
                 frame.width as usize,
@@ -212,13 +235,10 @@ impl PlaybackEngine {
         self.forward = Some(fwd);
 
         if !shadow.audio_sources.is_empty() {
-            for (ref audio_handle, _) in &shadow.audio_sources {
+            for (ref audio_handle, ..) in &shadow.audio_sources {
                 let _ = audio_handle.begin_playing();
             }
             self.mixer.replace_sources(shadow.audio_sources);
-            if let Some(ref output) = self.audio_output {
-                output.clear_buffer();
-            }
         } else {
             self.reset_audio_sources();
             self.start_audio_sources(state);
@@ -240,21 +260,25 @@ impl PlaybackEngine {
 
         let pending = self.pending_forward.take().expect("checked above");
 
-        if let Ok(handle) = result {
-            self.runtime_log_frames = 0;
-            self.forward = Some(ForwardPipelineState {
-                handle,
-                clip: pending.clip,
-                timeline_clip: pending.timeline_clip,
-                pts_offset: None,
-                speed: pending.speed,
-                frame_delivered: false,
-                activated: false,
-                started_at: pending.started_at,
-                last_frame_time: None,
-                age: 0,
-            });
-            self.try_activate_pipeline(now);
+        match result {
+            Ok(handle) => {
+                self.runtime_log_frames = 0;
+                self.forward = Some(ForwardPipelineState {
+                    handle,
+                    clip: pending.clip,
+                    timeline_clip: pending.timeline_clip,
+                    pts_offset: None,
+                    speed: pending.speed,
+                    frame_delivered: false,
+                    activated: false,
+                    started_at: pending.started_at,
+                    last_frame_time: None,
+                    age: 0,
+                    prebuffered: VecDeque::new(),
+                });
+                self.try_activate_pipeline(now);
+            }
+            Err(e) => self.report_pipeline_error(&pending.clip.1, &e),
         }
     }
 
@@ -293,7 +317,7 @@ impl PlaybackEngine {
 
         if let Some((_, _, tc)) = state.project.timeline.find_clip(timeline_clip_id) {
             let remaining = (tc.timeline_start + tc.duration) - state.project.playback.playhead;
-            if remaining < SHADOW_LOOKAHEAD_S / state.project.playback.speed {
+            if remaining < self.shadow_lookahead_s(state, timeline_clip_id) {
                 self.manage_shadow_pipeline(state, now);
             }
         }
@@ -343,7 +367,7 @@ impl PlaybackEngine {
                     state.project.playback.playhead = next_time;
                     self.forward = None;
 
-                    if let Some(next_hit) = state.project.timeline.video_clip_at_time(next_time) {
+                    if let Some(next_hit) = state.project.video_clip_at_time(next_time) {
                         let next_hit_clone = next_hit.clone();
                         if self.promote_shadow_pipeline(
                             state,
@@ -382,13 +406,11 @@ impl PlaybackEngine {
             }
 
             if mapped_source_pts >= source_in && mapped_source_pts < source_out {
-                if !fwd.frame_delivered {
-                    let new_playhead = timeline_start + (mapped_source_pts - source_in);
-                    state.project.playback.playhead = new_playhead;
-                }
                 fwd.frame_delivered = true;
 
                 let timeline_pos = timeline_start + (mapped_source_pts - source_in);
+                self.clock
+                    .sync_to_decoded(&mut state.project.playback, timeline_pos);
                 let cache_clip_changed = self
                     .rewind_cache
                     .last_timeline_clip_id()
@@ -412,7 +434,8 @@ impl PlaybackEngine {
             if let Some(ref mut fwd) = self.forward {
                 fwd.frame_delivered = true;
             }
-            state.project.playback.playhead = timeline_pos;
+            self.clock
+                .sync_to_decoded(&mut state.project.playback, timeline_pos);
         }
         if let Some(ref mut fwd) = self.forward {
             fwd.last_frame_time = Some(now);
@@ -468,7 +491,7 @@ impl PlaybackEngine {
         let playhead = state.project.playback.playhead;
         let speed = state.project.playback.speed;
 
-        let Some(next_hit) = state.project.timeline.next_video_clip_after_time(playhead) else {
+        let Some(next_hit) = state.project.next_video_clip_after_time(playhead) else {
             return;
         };
 
@@ -505,7 +528,6 @@ impl PlaybackEngine {
         let mut audio_requests = Vec::new();
         let audio_hits = state
             .project
-            .timeline
             .audio_clips_at_time(next_hit.clip.timeline_start);
         for hit in audio_hits {
             let Some(aclip) = state.project.clips.get(&hit.clip.source_id) else {
@@ -517,6 +539,9 @@ impl PlaybackEngine {
             audio_requests.push(ShadowAudioSourceRequest {
                 path: aclip.path.clone(),
                 source_time: hit.source_time,
+                track_id: hit.track_id,
+                timeline_clip: hit.clip.id,
+                envelope: SourceEnvelope::for_clip(&hit.clip, hit.source_time, speed),
             });
         }
 
@@ -531,6 +556,7 @@ impl PlaybackEngine {
             next_clip_id,
             next_timeline_clip_id,
             audio_requests,
+            self.mixer.source_ring_len(),
             now,
         ));
     }
@@ -540,26 +566,51 @@ impl PlaybackEngine {
 
         let target_hit = state
             .project
-            .timeline
             .video_clip_at_time(playhead)
-            .or_else(|| state.project.timeline.next_video_clip_after_time(playhead));
+            .or_else(|| state.project.next_video_clip_after_time(playhead));
 
         let Some(hit) = target_hit else {
             return;
         };
 
+        // Wait for the playhead to settle before building a decoder, so clicking around the
+        // timeline doesn't start one per seek. One warmed for a different clip is useless, so
+        // it goes straight away; one for this clip is kept until its replacement is due.
+        let target = (hit.clip.id, playhead);
+        if self.prewarm_target.map(|(t, _)| t) != Some(target) {
+            self.prewarm_target = Some((target, now));
+            if self
+                .shadow
+                .as_ref()
+                .is_some_and(|s| s.timeline_clip != hit.clip.id)
+            {
+                self.shadow = None;
+            }
+            if self
+                .pending_shadow
+                .as_ref()
+                .is_some_and(|s| s.timeline_clip != hit.clip.id)
+            {
+                self.pending_shadow = None;
+            }
+            return;
+        }
         if self
-            .shadow
-            .as_ref()
-            .is_some_and(|s| s.timeline_clip == hit.clip.id)
+            .prewarm_target
+            .is_some_and(|(_, since)| now - since < PREWARM_DEBOUNCE_S)
         {
             return;
         }
-        if self
-            .pending_shadow
+
+        let warm_for_clip = self
+            .shadow
             .as_ref()
             .is_some_and(|s| s.timeline_clip == hit.clip.id)
-        {
+            || self
+                .pending_shadow
+                .as_ref()
+                .is_some_and(|s| s.timeline_clip == hit.clip.id);
+        if warm_for_clip && self.prewarmed == Some(target) {
             return;
         }
 
@@ -577,7 +628,7 @@ impl PlaybackEngine {
 
         let mut audio_requests = Vec::new();
         let clip_time = hit.clip.timeline_start.max(playhead);
-        let audio_hits = state.project.timeline.audio_clips_at_time(clip_time);
+        let audio_hits = state.project.audio_clips_at_time(clip_time);
         for ahit in audio_hits {
             let Some(aclip) = state.project.clips.get(&ahit.clip.source_id) else {
                 continue;
@@ -588,6 +639,9 @@ impl PlaybackEngine {
             audio_requests.push(ShadowAudioSourceRequest {
                 path: aclip.path.clone(),
                 source_time: ahit.source_time,
+                track_id: ahit.track_id,
+                timeline_clip: ahit.clip.id,
+                envelope: SourceEnvelope::for_clip(&ahit.clip, ahit.source_time, speed),
             });
         }
 
@@ -602,7 +656,9 @@ impl PlaybackEngine {
             clip_id,
             timeline_clip_id,
             audio_requests,
+            self.mixer.source_ring_len(),
             now,
         ));
+        self.prewarmed = Some(target);
     }
 }
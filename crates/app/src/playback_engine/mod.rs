@@ -2,23 +2,27 @@ mod forward;
 pub mod rewind_cache;
 mod reverse;
 mod scrub;
+mod source_monitor;
 
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 
 use wizard_audio::output::{AudioOutput, AudioProducer};
-use wizard_media::gst_pipeline::GstAudioOnlyHandle;
+use wizard_media::gst_pipeline::{AudioPacing, GstAudioOnlyHandle};
 use wizard_media::pipeline::DecodedFrame;
 use wizard_state::clip::ClipId;
-use wizard_state::playback::PlaybackState;
-use wizard_state::project::AppState;
+use wizard_state::playback::{PlaybackClock, PlaybackState};
+use wizard_state::project::{AppState, AudioLatency, PipelineHealth};
+use wizard_state::timeline::{PlayheadHit, SourceEnvelope, TimelineClipId};
 
 use crate::audio_mixer::AudioMixer;
 use crate::constants::*;
 use crate::pipeline::{
     ForwardPipelineState, PendingPipeline, PendingReversePipeline, PendingShadowPipeline,
-    PipelineStatus, ReversePipelineState, ReverseShadowState, ShadowPipelineState,
+    PendingSourceMonitorPipeline, PipelineStatus, ReversePipelineState, ReverseShadowState,
+    ShadowAudioSource, ShadowPipelineState, SourceMonitorPipeline,
 };
 use crate::texture_cache::TextureCache;
 use crate::workers;
@@ -35,12 +39,19 @@ pub struct PlaybackEngine {
     pub pending_reverse: Option<PendingReversePipeline>,
     pub reverse_shadow: Option<ReverseShadowState>,
     pub pending_reverse_shadow: Option<PendingReversePipeline>,
+    pub source_monitor: Option<SourceMonitorPipeline>,
+    pub pending_source_monitor: Option<PendingSourceMonitorPipeline>,
 
     pub audio_output: Option<AudioOutput>,
+    pub output_device: Option<String>,
+    pub audio_latency: AudioLatency,
+    pub audio_error: Option<String>,
+    pub pipeline_errors: Vec<String>,
     pub audio_producer: Arc<Mutex<AudioProducer>>,
     pub mixer: AudioMixer,
     pub audio_sample_rate: u32,
     pub audio_channels: u16,
+    pub master_gain_db: f32,
     pub no_audio_paths: Arc<Mutex<HashSet<PathBuf>>>,
 
     pub video_decode: VideoDecodeWorkerChannels,
@@ -50,11 +61,22 @@ pub struct PlaybackEngine {
     pub last_hover_audio_request: Option<(ClipId, i64)>,
     pub last_scrub_audio_request: Option<(ClipId, i64)>,
     pub rewind_cache: RewindCache,
+    // When reverse playback of a clip started waiting on a pipeline that has yet to deliver,
+    // across respawns, and the clip the watchdog gave up on.
+    pub reverse_starved_since: Option<(TimelineClipId, f64)>,
+    pub reverse_abandoned: Option<TimelineClipId>,
+    pub clock: PlaybackClock,
     pub was_scrubbing: bool,
     pub last_is_playing: bool,
     pub last_playback_state: PlaybackState,
     pub last_decoded_frame: Option<(f64, &'static str)>,
+    pub last_shadow_buffered_frames: Option<usize>,
+    pub prewarm_target: Option<((TimelineClipId, f64), f64)>,
+    pub prewarmed: Option<(TimelineClipId, f64)>,
+    pub startup_requested_at: Option<(f64, bool)>,
+    pub last_startup_latency: Option<(f64, bool)>,
     pub last_playhead_observed: f64,
+    pub last_audio_reconcile: Option<f64>,
     pub video_fps_window_start: Option<f64>,
     pub video_fps_window_frames: u32,
     pub runtime_log_frames: u32,
@@ -63,12 +85,22 @@ pub struct PlaybackEngine {
 impl PlaybackEngine {
     pub fn new(
         audio_output: Option<AudioOutput>,
+        output_device: Option<String>,
+        audio_latency: AudioLatency,
         audio_producer: Arc<Mutex<AudioProducer>>,
         audio_sample_rate: u32,
         audio_channels: u16,
         no_audio_paths: Arc<Mutex<HashSet<PathBuf>>>,
     ) -> Self {
-        let mixer = AudioMixer::new(audio_producer.clone());
+        let output_buffer_len = audio_output
+            .as_ref()
+            .map_or(FALLBACK_AUDIO_BUFFER_LEN, AudioOutput::buffer_len);
+        let mixer = AudioMixer::new(
+            audio_producer.clone(),
+            audio_sample_rate,
+            audio_channels,
+            output_buffer_len,
+        );
         let video_decode = workers::video_decode_worker::spawn_video_decode_worker();
         let audio = workers::audio_worker::spawn_audio_worker(no_audio_paths.clone());
 
@@ -81,11 +113,18 @@ impl PlaybackEngine {
             pending_reverse: None,
             reverse_shadow: None,
             pending_reverse_shadow: None,
+            source_monitor: None,
+            pending_source_monitor: None,
             audio_output,
+            output_device,
+            audio_latency,
+            audio_error: None,
+            pipeline_errors: Vec::new(),
             audio_producer,
             mixer,
             audio_sample_rate,
             audio_channels,
+            master_gain_db: 0.0,
             no_audio_paths,
             video_decode,
             audio,
@@ -93,11 +132,20 @@ impl PlaybackEngine {
             last_hover_audio_request: None,
             last_scrub_audio_request: None,
             rewind_cache: RewindCache::new(),
+            reverse_starved_since: None,
+            reverse_abandoned: None,
+            clock: PlaybackClock::default(),
             was_scrubbing: false,
             last_is_playing: false,
             last_playback_state: PlaybackState::Stopped,
             last_decoded_frame: None,
+            last_shadow_buffered_frames: None,
+            prewarm_target: None,
+            prewarmed: None,
+            startup_requested_at: None,
+            last_startup_latency: None,
             last_playhead_observed: 0.0,
+            last_audio_reconcile: None,
             video_fps_window_start: None,
             video_fps_window_frames: 0,
             runtime_log_frames: 0,
@@ -118,6 +166,7 @@ impl PlaybackEngine {
             .unwrap_or(false)
     }
 
+    // The light path: drops queued audio and mixer sources but keeps the device open.
     pub fn reset_audio_sources(&mut self) {
         self.mixer.clear();
         if let Some(ref output) = self.audio_output {
@@ -125,6 +174,84 @@ impl PlaybackEngine {
         }
     }
 
+    // Reopening re-queries the device and can glitch, so it only happens when the selection,
+    // the latency setting or the device behind the stream actually changed.
+    pub fn refresh_audio_output(&mut self) {
+        let needs_reopen = match self.audio_output.as_ref() {
+            Some(output) => {
+                output.requested_device() != self.output_device.as_deref()
+                    || output.requested_buffer_ms() != self.audio_latency.buffer_ms()
+                    || output.device_changed()
+            }
+            None => self.output_device.is_some(),
+        };
+        if needs_reopen {
+            self.reopen_audio_output();
+        }
+    }
+
+    // Collected here and handed to the toast queue by the app, since the pipeline pollers
+    // don't have the UI state.
+    pub fn report_pipeline_error(&mut self, path: &Path, reason: &str) {
+        let name = path
+            .file_name()
+            .map_or_else(|| path.to_string_lossy(), |n| n.to_string_lossy());
+        self.pipeline_errors
+            .push(format!("Couldn't decode {name}: {reason}"));
+    }
+
+    pub fn audio_available(&self) -> bool {
+        self.audio_output.is_some()
+    }
+
+    pub fn reopen_audio_output(&mut self) {
+        // The old stream has to release the device before it can be opened again.
+        self.audio_output = None;
+        let (output, producer) = match AudioOutput::open(
+            self.output_device.as_deref(),
+            self.audio_latency.buffer_ms(),
+        ) {
+            Ok(opened) => opened,
+            Err(e) => {
+                self.audio_error = Some(e);
+                return;
+            }
+        };
+        self.audio_error = None;
+        self.audio_sample_rate = output.sample_rate_hz();
+        self.audio_channels = output.channels();
+        if let Ok(mut slot) = self.audio_producer.lock() {
+            *slot = producer;
+        }
+        self.mixer = AudioMixer::new(
+            self.audio_producer.clone(),
+            self.audio_sample_rate,
+            self.audio_channels,
+            output.buffer_len(),
+        );
+        self.audio_output = Some(output);
+    }
+
+    // Stops every pipeline, lets go of the audio device and winds the decode workers down,
+    // handing back their threads to wait on.
+    pub fn shutdown(&mut self) -> Vec<JoinHandle<()>> {
+        self.forward = None;
+        self.pending_forward = None;
+        self.shadow = None;
+        self.pending_shadow = None;
+        self.reverse = None;
+        self.pending_reverse = None;
+        self.reverse_shadow = None;
+        self.pending_reverse_shadow = None;
+        self.source_monitor = None;
+        self.pending_source_monitor = None;
+        self.mixer.clear();
+        self.audio_output = None;
+        let mut threads = self.video_decode.shutdown();
+        threads.extend(self.audio.shutdown());
+        threads
+    }
+
     pub fn last_pipeline_frame_time(&self) -> Option<f64> {
         self.forward.as_ref().and_then(|f| f.last_frame_time)
     }
@@ -150,6 +277,7 @@ impl PlaybackEngine {
         self.pending_reverse = None;
         self.rewind_cache.clear();
         self.reset_audio_sources();
+        self.refresh_audio_output();
     }
 
     pub fn handle_playback_state_transition(
@@ -188,12 +316,17 @@ impl PlaybackEngine {
 
     pub fn poll_shadow_frame(&mut self) {
         if let Some(ref mut shadow) = self.shadow {
-            if shadow.first_frame_ready {
-                return;
-            }
-            if let Some(frame) = shadow.handle.try_recv_frame() {
-                shadow.first_frame_ready = true;
-                shadow.buffered_frame = Some(frame);
+            while shadow.buffered_frames.len() < SHADOW_BUFFER_FRAMES {
+                let Some(frame) = shadow.handle.try_recv_frame() else {
+                    break;
+                };
+                if !shadow.first_frame_ready {
+                    shadow.first_frame_ready = true;
+                    shadow
+                        .handle
+                        .request_preroll_frames(SHADOW_BUFFER_FRAMES as u32 - 1);
+                }
+                shadow.buffered_frames.push_back(frame);
             }
         }
     }
@@ -232,6 +365,21 @@ impl PlaybackEngine {
         let is_playing = self.is_playing(state);
         let is_scrubbing = state.ui.timeline.scrubbing.is_some();
 
+        // Timed from the press of play to the first frame on screen, noting whether the
+        // decoder was already warm from sitting stopped on the clip.
+        if is_forward && self.last_playback_state == PlaybackState::Stopped {
+            let playhead = state.project.playback.playhead;
+            let prewarmed = state
+                .project
+                .video_clip_at_time(playhead)
+                .is_some_and(|hit| {
+                    self.shadow
+                        .as_ref()
+                        .is_some_and(|s| s.timeline_clip == hit.clip.id)
+                });
+            self.startup_requested_at = Some((now, prewarmed));
+        }
+
         if !is_forward && self.forward.is_some() {
             self.forward = None;
             self.shadow = None;
@@ -249,6 +397,10 @@ impl PlaybackEngine {
             self.reverse_shadow = None;
             self.pending_reverse_shadow = None;
         }
+        if !is_reverse {
+            self.reverse_starved_since = None;
+            self.reverse_abandoned = None;
+        }
 
         if !is_playing {
             self.poll_pending_shadow_pipeline(now);
@@ -269,7 +421,7 @@ impl PlaybackEngine {
             state.project.playback.playhead
         };
 
-        let hit = state.project.timeline.video_clip_at_time(playhead);
+        let hit = state.project.video_clip_at_time(playhead);
         let Some(hit) = hit else {
             let had_pipeline = self.forward.is_some() || self.reverse.is_some();
             self.forward = None;
@@ -287,6 +439,8 @@ impl PlaybackEngine {
                 } else if !has_audio && self.mixer.source_count() > 0 {
                     self.mixer.clear();
                     self.reset_audio_sources();
+                } else {
+                    self.reconcile_audio_sources(state, now);
                 }
                 self.manage_shadow_pipeline(state, now);
             }
@@ -404,11 +558,16 @@ impl PlaybackEngine {
                     }
                 }
             }
+
+            if self.forward.is_some() && self.pending_forward.is_none() {
+                self.reconcile_audio_sources(state, now);
+            }
         }
 
         if is_reverse {
             if self.reverse.is_none()
                 && self.pending_reverse.is_none()
+                && self.reverse_abandoned != Some(timeline_clip_id)
                 && self.promote_reverse_shadow(
                     state,
                     textures,
@@ -438,33 +597,78 @@ impl PlaybackEngine {
         self.mixer.clear();
 
         let playhead = state.project.playback.playhead;
+        for hit in state.project.audio_clips_at_time(playhead) {
+            if let Some(source) = self.start_audio_source(state, &hit) {
+                let _ = source.0.begin_playing();
+                self.mixer.add_source(source);
+            }
+        }
+    }
+
+    fn start_audio_source(&self, state: &AppState, hit: &PlayheadHit) -> Option<ShadowAudioSource> {
+        let clip = state.project.clips.get(&hit.clip.source_id)?;
+        if self.path_has_no_audio(&clip.path) {
+            return None;
+        }
+
+        let (producer, consumer) = AudioMixer::create_source_producer(self.mixer.source_ring_len());
+        let source_producer = Arc::new(Mutex::new(producer));
         let speed = state.project.playback.speed;
-        let hits = state.project.timeline.audio_clips_at_time(playhead);
+        let handle = GstAudioOnlyHandle::start(
+            &clip.path,
+            hit.source_time,
+            source_producer,
+            self.audio_sample_rate,
+            self.audio_channels,
+            speed,
+            AudioPacing::Realtime,
+        )
+        .ok()?;
+        let envelope = SourceEnvelope::for_clip(&hit.clip, hit.source_time, speed);
+        Some((handle, consumer, hit.track_id, hit.clip.id, envelope))
+    }
 
-        for hit in hits {
-            let Some(clip) = state.project.clips.get(&hit.clip.source_id) else {
-                continue;
-            };
-            let path = clip.path.clone();
-            if self.path_has_no_audio(&path) {
-                continue;
-            }
+    // Sources are otherwise only rebuilt at clip transitions, so a track muted or a clip
+    // trimmed away mid-playback would keep sounding. Every so often the mixing set is
+    // compared against the clips under the playhead and only the difference is swapped.
+    fn reconcile_audio_sources(&mut self, state: &AppState, now: f64) {
+        if self
+            .last_audio_reconcile
+            .is_some_and(|t| now - t < AUDIO_RECONCILE_INTERVAL_S)
+        {
+            return;
+        }
+        self.last_audio_reconcile = Some(now);
 
-            let (producer, consumer) = AudioMixer::create_source_producer();
-            let source_producer = Arc::new(Mutex::new(producer));
-
-            if let Ok(handle) = GstAudioOnlyHandle::start(
-                &path,
-                hit.source_time,
-                source_producer,
-                self.audio_sample_rate,
-                self.audio_channels,
-                speed,
-            ) {
-                let _ = handle.begin_playing();
-                self.mixer.add_source(handle, consumer);
-            }
+        let playhead = state.project.playback.playhead;
+        let hits: Vec<_> = state
+            .project
+            .audio_clips_at_time(playhead)
+            .into_iter()
+            .filter(|hit| {
+                state
+                    .project
+                    .clips
+                    .get(&hit.clip.source_id)
+                    .is_some_and(|c| !self.path_has_no_audio(&c.path))
+            })
+            .collect();
+        let mixing: HashSet<TimelineClipId> = self.mixer.timeline_clips().collect();
+        let wanted: HashSet<TimelineClipId> = hits.iter().map(|hit| hit.clip.id).collect();
+        if mixing == wanted {
+            return;
         }
+
+        let added: Vec<_> = hits
+            .iter()
+            .filter(|hit| !mixing.contains(&hit.clip.id))
+            .filter_map(|hit| self.start_audio_source(state, hit))
+            .collect();
+        for (handle, ..) in &added {
+            let _ = handle.begin_playing();
+        }
+        self.mixer
+            .reconcile_sources(|id| wanted.contains(&id), added);
     }
 
     pub(crate) fn show_scrub_cache_bridge_frame(
@@ -576,10 +780,16 @@ impl PlaybackEngine {
         self.poll_shadow_frame();
 
         let mut pipeline_frames: Vec<DecodedFrame> = Vec::new();
-        if let Some(ref fwd) = self.forward {
+        if let Some(ref mut fwd) = self.forward {
             while let Some(frame) = fwd.handle.try_recv_frame() {
                 pipeline_frames.push(frame);
             }
+            // Frames carried over from the shadow bridge the gap until the pipeline delivers.
+            if pipeline_frames.is_empty() {
+                pipeline_frames.extend(fwd.prebuffered.pop_front());
+            } else {
+                pipeline_frames.extend(fwd.prebuffered.drain(..));
+            }
         }
         if !pipeline_frames.is_empty() {
             received = true;
@@ -613,18 +823,18 @@ impl PlaybackEngine {
                 self.reverse = None;
                 self.pending_reverse = None;
 
-                if let Some(hit) = state.project.timeline.video_clip_at_time(playhead) {
+                if let Some(hit) = state.project.video_clip_at_time(playhead) {
                     let clip_id = hit.clip.source_id;
                     let timeline_clip_id = hit.clip.id;
                     if let Some(clip) = state.project.clips.get(&clip_id) {
                         let path = clip.path.clone();
                         let speed = state.project.playback.speed;
+                        self.reverse_starved_since = Some((timeline_clip_id, now));
                         self.pending_reverse =
                             Some(crate::pipeline::PendingReversePipeline::spawn(
                                 &path,
                                 hit.source_time,
-                                workers::video_decode_worker::PLAYBACK_DECODE_WIDTH,
-                                workers::video_decode_worker::PLAYBACK_DECODE_HEIGHT,
+                                state.ui.playback.reverse_budget,
                                 speed,
                                 clip_id,
                                 timeline_clip_id,
@@ -661,24 +871,89 @@ impl PlaybackEngine {
                 self.apply_reverse_pipeline_frame(state, textures, ctx, frame, now);
             }
         }
+        self.check_reverse_watchdog(state, now);
 
         let mut last_snippet: Option<crate::workers::audio_worker::AudioSnippet> = None;
         while let Ok(snippet) = self.audio.snippet_rx.try_recv() {
             last_snippet = Some(snippet);
         }
         if let Some(snippet) = last_snippet {
-            if self.is_playing(state) {
+            // The source monitor's sound is going to the output too, so hover snippets wait.
+            if self.is_playing(state) || state.ui.preview.source_monitor.is_some() {
             } else {
                 self.reset_audio_sources();
+                let volume = state.ui.audio.preview_volume.clamp(0.0, 1.0);
+                let samples: Vec<f32> = snippet.samples_mono.iter().map(|s| s * volume).collect();
                 if let Ok(mut producer) = self.audio_producer.lock() {
                     let ch = self.audio_channels;
-                    wizard_audio::output::enqueue_samples(&mut producer, &snippet.samples_mono, ch);
+                    wizard_audio::output::enqueue_samples(&mut producer, &samples, ch);
                 }
             }
         }
 
+        self.mixer.set_master_gain_db(self.master_gain_db);
+        for track in &state.project.timeline.audio_tracks {
+            self.mixer.set_track_gain_db(track.id, track.gain_db);
+        }
         self.mixer.mix_tick();
 
+        state.ui.preview.buffering = self.pipeline_buffering(state, now);
+        state.ui.preview.pipeline_health = self.pipeline_health(state, now);
+        state.ui.timeline.rewind_cached_span = self.rewind_cache.time_span();
+        self.record_startup_latency(state, now);
+
         received
     }
+
+    fn record_startup_latency(&mut self, state: &AppState, now: f64) {
+        let Some((requested_at, prewarmed)) = self.startup_requested_at else {
+            return;
+        };
+        if state.project.playback.state != PlaybackState::Playing {
+            self.startup_requested_at = None;
+        } else if self.pipeline_frame_delivered() {
+            self.last_startup_latency = Some((now - requested_at, prewarmed));
+            self.startup_requested_at = None;
+        }
+    }
+
+    // None while stopped or playing over a gap, where no pipeline is wanted.
+    fn pipeline_health(&self, state: &AppState, now: f64) -> Option<PipelineHealth> {
+        let (status, pending) = match state.project.playback.state {
+            PlaybackState::Playing => (
+                self.forward.as_ref().map(|f| f.status(now)),
+                self.pending_forward.is_some(),
+            ),
+            PlaybackState::PlayingReverse => (
+                self.reverse.as_ref().map(|r| r.status(now)),
+                self.pending_reverse.is_some(),
+            ),
+            PlaybackState::Stopped => return None,
+        };
+        let status = match status {
+            Some(status) => status,
+            None if pending => PipelineStatus::StartingUp,
+            None => return None,
+        };
+        Some(match status {
+            PipelineStatus::StartingUp => PipelineHealth::StartingUp,
+            PipelineStatus::Delivering => PipelineHealth::Delivering,
+            PipelineStatus::Stalled => PipelineHealth::Stalled,
+            PipelineStatus::LongStall => PipelineHealth::LongStall,
+        })
+    }
+
+    fn pipeline_buffering(&self, state: &AppState, now: f64) -> bool {
+        match state.project.playback.state {
+            PlaybackState::Playing => self
+                .forward
+                .as_ref()
+                .is_some_and(|f| f.stall_status(now).is_stalled()),
+            PlaybackState::PlayingReverse => self
+                .reverse
+                .as_ref()
+                .is_some_and(|r| r.stall_status(now).is_stalled()),
+            PlaybackState::Stopped => false,
+        }
+    }
 }
@@ -9,7 +9,6 @@ use wizard_state::timeline::TimelineClipId;
 use crate::constants::*;
 use crate::pipeline::{PendingReversePipeline, ReversePipelineState, ReverseShadowState};
 use crate::texture_cache::TextureCache;
-use crate::workers;
 
 use super::PlaybackEngine;
 
@@ -38,6 +37,11 @@ impl PlaybackEngine {
         source_time: f64,
         now: f64,
     ) {
+        if self.reverse_abandoned == Some(timeline_clip_id) {
+            return;
+        }
+        self.reverse_abandoned = None;
+
         let speed = state.project.playback.speed;
 
         if let Some(ref mut rev) = self.reverse {
@@ -85,11 +89,16 @@ impl PlaybackEngine {
             }
             self.reverse = None;
             self.pending_reverse = None;
+            if self
+                .reverse_starved_since
+                .is_none_or(|(id, _)| id != timeline_clip_id)
+            {
+                self.reverse_starved_since = Some((timeline_clip_id, now));
+            }
             self.pending_reverse = Some(PendingReversePipeline::spawn(
                 path,
                 source_time,
-                workers::video_decode_worker::PLAYBACK_DECODE_WIDTH,
-                workers::video_decode_worker::PLAYBACK_DECODE_HEIGHT,
+                state.ui.playback.reverse_budget,
                 speed,
                 clip_id,
                 timeline_clip_id,
@@ -111,18 +120,50 @@ impl PlaybackEngine {
         };
 
         let pending = self.pending_reverse.take().expect("checked above");
-        if let Ok(handle) = result {
-            self.reverse = Some(ReversePipelineState {
-                handle,
-                clip: pending.clip,
-                timeline_clip: pending.timeline_clip,
-                pts_offset: None,
-                speed: pending.speed,
-                activated: false,
-                started_at: pending.started_at,
-                last_frame_time: None,
-            });
-            self.try_activate_pipeline(now);
+        match result {
+            Ok(handle) => {
+                self.reverse = Some(ReversePipelineState {
+                    handle,
+                    clip: pending.clip,
+                    timeline_clip: pending.timeline_clip,
+                    pts_offset: None,
+                    speed: pending.speed,
+                    activated: false,
+                    started_at: pending.started_at,
+                    last_frame_time: None,
+                });
+                self.try_activate_pipeline(now);
+            }
+            Err(e) => self.report_pipeline_error(&pending.clip.1, &e),
+        }
+    }
+
+    // The startup timeouts respawn a silent reverse pipeline, but some files never deliver
+    // however often they're restarted. Past the watchdog the clip is given up on until
+    // reverse playback leaves it, and the rewind cache or the decode worker's stepped frames
+    // stand in for the pipeline.
+    pub(super) fn check_reverse_watchdog(&mut self, state: &AppState, now: f64) {
+        if state.project.playback.state != PlaybackState::PlayingReverse {
+            return;
+        }
+        let Some((timeline_clip, since)) = self.reverse_starved_since else {
+            return;
+        };
+        if now - since < REVERSE_WATCHDOG_S {
+            return;
+        }
+        let path = self
+            .reverse
+            .as_ref()
+            .map(|r| r.clip.1.clone())
+            .or_else(|| self.pending_reverse.as_ref().map(|p| p.clip.1.clone()));
+        self.reverse_starved_since = None;
+        self.reverse_abandoned = Some(timeline_clip);
+        self.reverse = None;
+        self.pending_reverse = None;
+        self.last_video_decode_request = None;
+        if let Some(path) = path {
+            self.report_pipeline_error(&path, "reverse playback stalled, stepping frames instead");
         }
     }
 
@@ -134,6 +175,7 @@ impl PlaybackEngine {
         frame: &DecodedFrame,
         now: f64,
     ) -> bool {
+        self.reverse_starved_since = None;
         let previous_rev_pts = self
             .last_decoded_frame
             .and_then(|(pts, source)| (source == "rev").then_some(pts));
@@ -166,7 +208,8 @@ impl PlaybackEngine {
                     let should_apply = previous_rev_pts.is_none()
                         || timeline_pos <= state.project.playback.playhead;
                     if should_apply {
-                        state.project.playback.playhead = timeline_pos;
+                        self.clock
+                            .sync_to_decoded(&mut state.project.playback, timeline_pos);
                     }
                     if distance_to_clip_start <= boundary_threshold && previous_rev_pts.is_some() {
                         let from_timeline_clip = rev.timeline_clip;
@@ -178,10 +221,8 @@ impl PlaybackEngine {
                         if prev_time <= 0.0 {
                             state.project.playback.playhead = 0.0;
                             state.project.playback.state = PlaybackState::Stopped;
-                        } else if let Some(prev_hit) = state
-                            .project
-                            .timeline
-                            .previous_clip_before(from_timeline_clip)
+                        } else if let Some(prev_hit) =
+                            state.project.previous_clip_before(from_timeline_clip)
                         {
                             let prev_timeline_clip_id = prev_hit.clip.id;
                             if self.promote_reverse_shadow(
@@ -200,8 +241,7 @@ impl PlaybackEngine {
                                     self.pending_reverse = Some(PendingReversePipeline::spawn(
                                         &path,
                                         prev_hit.source_time,
-                                        workers::video_decode_worker::PLAYBACK_DECODE_WIDTH,
-                                        workers::video_decode_worker::PLAYBACK_DECODE_HEIGHT,
+                                        state.ui.playback.reverse_budget,
                                         speed,
                                         prev_clip_id,
                                         prev_timeline_clip_id,
@@ -227,10 +267,8 @@ impl PlaybackEngine {
                     if prev_time <= 0.0 {
                         state.project.playback.playhead = 0.0;
                         state.project.playback.state = PlaybackState::Stopped;
-                    } else if let Some(prev_hit) = state
-                        .project
-                        .timeline
-                        .previous_clip_before(from_timeline_clip)
+                    } else if let Some(prev_hit) =
+                        state.project.previous_clip_before(from_timeline_clip)
                     {
                         let prev_timeline_clip_id = prev_hit.clip.id;
                         if self.promote_reverse_shadow(
@@ -246,11 +284,11 @@ impl PlaybackEngine {
                             if let Some(clip) = state.project.clips.get(&prev_clip_id) {
                                 let path = clip.path.clone();
                                 let speed = state.project.playback.speed;
+                                self.reverse_starved_since = Some((prev_timeline_clip_id, now));
                                 self.pending_reverse = Some(PendingReversePipeline::spawn(
                                     &path,
                                     prev_hit.source_time,
-                                    workers::video_decode_worker::PLAYBACK_DECODE_WIDTH,
-                                    workers::video_decode_worker::PLAYBACK_DECODE_HEIGHT,
+                                    state.ui.playback.reverse_budget,
                                     speed,
                                     prev_clip_id,
                                     prev_timeline_clip_id,
@@ -319,14 +357,17 @@ impl PlaybackEngine {
 
         let pending = self.pending_reverse_shadow.take().expect("checked above");
 
-        if let Ok(handle) = result {
-            self.reverse_shadow = Some(ReverseShadowState {
-                handle,
-                clip: pending.clip,
-                timeline_clip: pending.timeline_clip,
-                first_frame_ready: false,
-                buffered_frame: None,
-            });
+        match result {
+            Ok(handle) => {
+                self.reverse_shadow = Some(ReverseShadowState {
+                    handle,
+                    clip: pending.clip,
+                    timeline_clip: pending.timeline_clip,
+                    first_frame_ready: false,
+                    buffered_frame: None,
+                });
+            }
+            Err(e) => self.report_pipeline_error(&pending.clip.1, &e),
         }
     }
 
@@ -355,22 +396,14 @@ impl PlaybackEngine {
         let current_timeline_clip = rev.timeline_clip;
 
         if let Some(ref shadow) = self.reverse_shadow {
-            if let Some(prev_hit) = state
-                .project
-                .timeline
-                .previous_clip_before(current_timeline_clip)
-            {
+            if let Some(prev_hit) = state.project.previous_clip_before(current_timeline_clip) {
                 if shadow.timeline_clip == prev_hit.clip.id {
                     return;
                 }
             }
         }
         if let Some(ref shadow) = self.pending_reverse_shadow {
-            if let Some(prev_hit) = state
-                .project
-                .timeline
-                .previous_clip_before(current_timeline_clip)
-            {
+            if let Some(prev_hit) = state.project.previous_clip_before(current_timeline_clip) {
                 if shadow.timeline_clip == prev_hit.clip.id {
                     return;
                 }
@@ -388,11 +421,7 @@ impl PlaybackEngine {
             return;
         }
 
-        let Some(prev_hit) = state
-            .project
-            .timeline
-            .previous_clip_before(current_timeline_clip)
-        else {
+        let Some(prev_hit) = state.project.previous_clip_before(current_timeline_clip) else {
             return;
         };
 
@@ -409,8 +438,7 @@ impl PlaybackEngine {
         self.pending_reverse_shadow = Some(PendingReversePipeline::spawn(
             &path,
             prev_hit.source_time,
-            workers::video_decode_worker::PLAYBACK_DECODE_WIDTH,
-            workers::video_decode_worker::PLAYBACK_DECODE_HEIGHT,
+            state.ui.playback.reverse_budget,
             speed,
             prev_clip_id,
             prev_timeline_clip_id,
@@ -421,7 +449,7 @@ impl PlaybackEngine {
     pub fn manage_reverse_shadow_for_stopped(&mut self, state: &mut AppState, now: f64) {
         let playhead = state.project.playback.playhead;
 
-        let target_hit = state.project.timeline.video_clip_at_time(playhead);
+        let target_hit = state.project.video_clip_at_time(playhead);
 
         let Some(hit) = target_hit else {
             return;
@@ -456,8 +484,7 @@ impl PlaybackEngine {
         self.pending_reverse_shadow = Some(PendingReversePipeline::spawn(
             &path,
             hit.source_time,
-            workers::video_decode_worker::PLAYBACK_DECODE_WIDTH,
-            workers::video_decode_worker::PLAYBACK_DECODE_HEIGHT,
+            state.ui.playback.reverse_budget,
             speed,
             clip_id,
             timeline_clip_id,
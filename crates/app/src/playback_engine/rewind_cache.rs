@@ -64,6 +64,18 @@ impl RewindCache {
         self.entries.len()
     }
 
+    pub fn byte_size(&self) -> usize {
+        self.total_bytes
+    }
+
+    // Earliest and latest cached timeline positions.
+    pub fn time_span(&self) -> Option<(f64, f64)> {
+        self.entries.iter().fold(None, |span, e| {
+            let (lo, hi) = span.unwrap_or((e.timeline_pos, e.timeline_pos));
+            Some((lo.min(e.timeline_pos), hi.max(e.timeline_pos)))
+        })
+    }
+
     pub fn best_entry_for_playhead(&self, playhead: f64) -> Option<&RewindCacheEntry> {
         let mut best: Option<&RewindCacheEntry> = None;
         for entry in self.entries.iter().rev() {
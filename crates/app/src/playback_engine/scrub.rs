@@ -1,4 +1,5 @@
-use wizard_state::project::AppState;
+use wizard_state::playback::ScrubQuality;
+use wizard_state::project::{AppState, TrimEdge};
 
 use crate::constants::*;
 use crate::texture_cache::TextureCache;
@@ -13,6 +14,12 @@ impl PlaybackEngine {
         if self.audio_output.is_none() {
             return;
         }
+        if !state.ui.audio.scrub_audio {
+            if self.last_hover_audio_request.take().is_some() {
+                let _ = self.audio.req_tx.send(AudioPreviewRequest::Stop);
+            }
+            return;
+        }
 
         let is_playing = self.is_playing(state);
 
@@ -72,13 +79,17 @@ impl PlaybackEngine {
         if self.audio_output.is_none() {
             return;
         }
+        if !state.ui.audio.scrub_audio {
+            self.last_scrub_audio_request = None;
+            return;
+        }
         let Some(time) = state.ui.timeline.scrubbing else {
             return;
         };
         if state.ui.browser.hovered_scrub_t.is_some() {
             return;
         }
-        let Some(hit) = state.project.timeline.audio_clip_at_time(time) else {
+        let Some(hit) = state.project.audio_clip_at_time(time) else {
             return;
         };
         let Some(clip) = state.project.clips.get(&hit.clip.source_id) else {
@@ -101,6 +112,50 @@ impl PlaybackEngine {
         });
     }
 
+    // Plays the source under the edge being dragged. Out points preroll a little so the
+    // snippet runs up to the cut rather than past it.
+    pub fn update_trim_scrub_audio(&mut self, state: &AppState) {
+        if self.audio_output.is_none() {
+            return;
+        }
+        if !state.ui.audio.scrub_audio {
+            self.last_scrub_audio_request = None;
+            return;
+        }
+        let Some(trim) = state.ui.timeline.trimming_clip.as_ref() else {
+            return;
+        };
+        let Some((_, _, tc)) = state.project.timeline.find_clip(trim.clip_id) else {
+            return;
+        };
+        let Some(clip) = state.project.clips.get(&tc.source_id) else {
+            return;
+        };
+        if self.path_has_no_audio(&clip.path) {
+            return;
+        }
+
+        let edge_time = match trim.edge {
+            TrimEdge::Left => tc.source_in,
+            TrimEdge::Right => tc.source_out,
+        };
+        let bucket = (edge_time * SCRUB_AUDIO_BUCKET_RATE).round() as i64;
+        if self.last_scrub_audio_request == Some((tc.source_id, bucket)) {
+            return;
+        }
+        self.last_scrub_audio_request = Some((tc.source_id, bucket));
+
+        let time_seconds = match trim.edge {
+            TrimEdge::Left => edge_time,
+            TrimEdge::Right => (edge_time - TRIM_AUDIO_PREROLL_S).max(tc.source_in),
+        };
+        let _ = self.audio.req_tx.send(AudioPreviewRequest::Preview {
+            path: clip.path.clone(),
+            time_seconds,
+            sample_rate_hz: self.audio_sample_rate,
+        });
+    }
+
     pub fn update_playback_frame(
         &mut self,
         state: &mut AppState,
@@ -149,8 +204,9 @@ impl PlaybackEngine {
             playhead
         };
 
-        if let Some(hit) = state.project.timeline.video_clip_at_time(time) {
-            if is_scrubbing {
+        let quality = state.ui.playback.scrub_quality;
+        if let Some(hit) = state.project.video_clip_at_time(time) {
+            if is_scrubbing && quality.uses_scrub_cache() {
                 if let Some(tex) = textures
                     .scrub_frames
                     .get(&hit.clip.source_id)
@@ -161,14 +217,15 @@ impl PlaybackEngine {
                 }
             }
 
+            let source_time = hit.source_time;
             if let Some(clip) = state.project.clips.get(&hit.clip.source_id) {
-                let bucket = (hit.source_time * VIDEO_DECODE_BUCKET_RATE).round() as i64;
+                let bucket = (source_time * VIDEO_DECODE_BUCKET_RATE).round() as i64;
                 if self.last_video_decode_request == Some((hit.clip.source_id, bucket)) {
                     return;
                 }
 
                 let (tw, th) = if is_scrubbing {
-                    (SCRUB_DECODE_WIDTH, SCRUB_DECODE_HEIGHT)
+                    quality.decode_size(SCRUB_DECODE_WIDTH, SCRUB_DECODE_HEIGHT)
                 } else {
                     (
                         workers::video_decode_worker::PLAYBACK_DECODE_WIDTH,
@@ -178,10 +235,10 @@ impl PlaybackEngine {
                 let _ = self.video_decode.req_tx.send(VideoDecodeRequest {
                     clip_id: hit.clip.source_id,
                     path: clip.path.clone(),
-                    time_seconds: hit.source_time,
+                    time_seconds: source_time,
                     target_width: tw,
                     target_height: th,
-                    max_decode_frames: if is_scrubbing {
+                    max_decode_frames: if is_scrubbing && quality == ScrubQuality::Fast {
                         SCRUB_MAX_DECODE_FRAMES
                     } else {
                         PLAYBACK_MAX_DECODE_FRAMES
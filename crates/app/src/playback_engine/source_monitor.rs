@@ -0,0 +1,162 @@
+use std::path::Path;
+
+use wizard_state::clip::ClipId;
+use wizard_state::playback::PlaybackState;
+use wizard_state::project::AppState;
+
+use crate::constants::*;
+use crate::pipeline::{PendingSourceMonitorPipeline, SourceMonitorPipeline};
+use crate::texture_cache::TextureCache;
+
+use super::PlaybackEngine;
+
+impl PlaybackEngine {
+    // Drives the browser's source monitor alongside, never together with, timeline playback.
+    // Pausing and seeking respawn the pipeline paused at the new spot so its preroll frame
+    // stands in the picture; playing starts it, and running off the end of the source
+    // starts it over from the top.
+    pub fn manage_source_monitor(
+        &mut self,
+        state: &mut AppState,
+        textures: &mut TextureCache,
+        ctx: &egui::Context,
+        now: f64,
+    ) -> bool {
+        // Going back to working the timeline, by playing or scrubbing it, closes the monitor.
+        if state.project.playback.state != PlaybackState::Stopped
+            || state.ui.timeline.scrubbing.is_some()
+        {
+            state.close_source_monitor();
+        }
+        let Some(clip_id) = state.ui.preview.source_monitor.as_ref().map(|m| m.clip) else {
+            if self.source_monitor.is_some() || self.pending_source_monitor.is_some() {
+                self.close_source_monitor(textures);
+            }
+            return false;
+        };
+        let Some(clip) = state.project.clips.get(&clip_id) else {
+            state.close_source_monitor();
+            return false;
+        };
+        let path = clip.path.clone();
+        let duration = clip.duration;
+
+        if let Some(result) = self
+            .pending_source_monitor
+            .as_ref()
+            .and_then(|p| p.try_recv())
+        {
+            let pending = self.pending_source_monitor.take().expect("checked above");
+            match result {
+                Ok(handle) => {
+                    self.source_monitor = Some(SourceMonitorPipeline {
+                        handle,
+                        clip: pending.clip,
+                        start: pending.start,
+                        pts_offset: None,
+                        activated: false,
+                        last_frame_time: None,
+                    });
+                }
+                Err(e) => {
+                    self.report_pipeline_error(&pending.clip.1, &e);
+                    state.close_source_monitor();
+                    self.close_source_monitor(textures);
+                    return false;
+                }
+            }
+        }
+
+        let Some(monitor) = state.ui.preview.source_monitor.as_mut() else {
+            return false;
+        };
+        let current = self
+            .source_monitor
+            .as_ref()
+            .map(|m| &m.clip)
+            .or(self.pending_source_monitor.as_ref().map(|p| &p.clip));
+        let clip_changed = current.is_none_or(|(id, p)| *id != clip_id || *p != path);
+        // A seek waits for the last spawn to land, which also paces a dragged scrub bar.
+        let seek = if self.pending_source_monitor.is_none() || clip_changed {
+            monitor.seek_to.take()
+        } else {
+            None
+        };
+        let paused_while_running =
+            !monitor.playing && self.source_monitor.as_ref().is_some_and(|m| m.activated);
+        if clip_changed {
+            textures.source_monitor_texture = None;
+        }
+        if clip_changed || seek.is_some() || paused_while_running {
+            let start = seek
+                .unwrap_or(monitor.position)
+                .clamp(0.0, duration.unwrap_or(f64::MAX));
+            monitor.position = start;
+            self.spawn_source_monitor(clip_id, &path, start);
+        }
+
+        let mut received = false;
+        if let Some(ref mut pipeline) = self.source_monitor {
+            if monitor.playing && !pipeline.activated && pipeline.handle.is_first_frame_ready() {
+                pipeline.activated = true;
+                pipeline.last_frame_time = Some(now);
+                let _ = pipeline.handle.begin_playing();
+            }
+
+            let mut latest = None;
+            while let Some(frame) = pipeline.handle.try_recv_frame() {
+                if let Some(older) = latest.replace(frame) {
+                    pipeline.handle.return_buffer(older.rgba_data);
+                }
+            }
+            if let Some(mut frame) = latest {
+                textures.update_source_monitor_texture(
+                    ctx,
+                    frame.width as usize,
+                    frame.height as usize,
+                    &frame.rgba_data,
+                );
+                let start = pipeline.start;
+                let offset = *pipeline.pts_offset.get_or_insert(frame.pts_seconds - start);
+                monitor.position = (frame.pts_seconds - offset).max(0.0);
+                pipeline.last_frame_time = Some(now);
+                pipeline
+                    .handle
+                    .return_buffer(std::mem::take(&mut frame.rgba_data));
+                received = true;
+            }
+
+            // Without a known duration, the pipeline going quiet is the only sign of the end.
+            let at_end =
+                duration.is_some_and(|d| monitor.position >= d - SOURCE_MONITOR_END_SLACK_S);
+            let dried_up = pipeline
+                .last_frame_time
+                .is_some_and(|t| now - t > SOURCE_MONITOR_EOS_S);
+            if monitor.playing && pipeline.activated && (at_end || dried_up) {
+                monitor.position = 0.0;
+                self.spawn_source_monitor(clip_id, &path, 0.0);
+            }
+        }
+        received
+    }
+
+    fn spawn_source_monitor(&mut self, clip_id: ClipId, path: &Path, source_time: f64) {
+        self.source_monitor = None;
+        self.reset_audio_sources();
+        self.pending_source_monitor = Some(PendingSourceMonitorPipeline::spawn(
+            path,
+            source_time,
+            self.audio_producer.clone(),
+            self.audio_sample_rate,
+            self.audio_channels,
+            clip_id,
+        ));
+    }
+
+    fn close_source_monitor(&mut self, textures: &mut TextureCache) {
+        self.source_monitor = None;
+        self.pending_source_monitor = None;
+        textures.source_monitor_texture = None;
+        self.reset_audio_sources();
+    }
+}
@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use wizard_state::playback::{ReverseBudget, ScrubQuality};
+use wizard_state::project::{AudioLatency, AudioUiState, PlaybackUiState};
+use wizard_state::timeline::{OverlapPolicy, OverlapSettings};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistedSettings {
+    pub audio: AudioUiState,
+    pub playback: PlaybackUiState,
+    pub overlap: OverlapSettings,
+    pub link_audio_on_drop: bool,
+    pub stereo_waveforms: bool,
+    pub snapping_enabled: bool,
+}
+
+impl Default for PersistedSettings {
+    fn default() -> Self {
+        Self {
+            audio: AudioUiState::default(),
+            playback: PlaybackUiState::default(),
+            overlap: OverlapSettings::default(),
+            link_audio_on_drop: true,
+            stereo_waveforms: false,
+            snapping_enabled: true,
+        }
+    }
+}
+
+impl PersistedSettings {
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+        let Some(path) = settings_path() else {
+            return settings;
+        };
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return settings;
+        };
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) else {
+            return settings;
+        };
+
+        let audio = &value["audio"];
+        if let Some(v) = audio["scrub_audio"].as_bool() {
+            settings.audio.scrub_audio = v;
+        }
+        if let Some(v) = audio["preview_volume"].as_f64() {
+            settings.audio.preview_volume = (v as f32).clamp(0.0, 1.0);
+        }
+        if let Some(v) = audio["output_device"].as_str() {
+            settings.audio.output_device = Some(v.to_string());
+        }
+        if let Some(latency) = audio["latency"].as_str().and_then(AudioLatency::from_label) {
+            settings.audio.latency = latency;
+        }
+
+        let playback = &value["playback"];
+        if let Some(budget) = playback["reverse_budget"]
+            .as_str()
+            .and_then(ReverseBudget::from_label)
+        {
+            settings.playback.reverse_budget = budget;
+        }
+        if let Some(quality) = playback["scrub_quality"]
+            .as_str()
+            .and_then(ScrubQuality::from_label)
+        {
+            settings.playback.scrub_quality = quality;
+        }
+        if let Some(v) = playback["snap_playhead_to_frames"].as_bool() {
+            settings.playback.snap_playhead_to_frames = v;
+        }
+
+        let timeline = &value["timeline"];
+        if let Some(policy) = timeline["video_overlap"]
+            .as_str()
+            .and_then(OverlapPolicy::from_label)
+        {
+            settings.overlap.video = policy;
+        }
+        if let Some(policy) = timeline["audio_overlap"]
+            .as_str()
+            .and_then(OverlapPolicy::from_label)
+        {
+            settings.overlap.audio = policy;
+        }
+        if let Some(v) = timeline["link_audio_on_drop"].as_bool() {
+            settings.link_audio_on_drop = v;
+        }
+        if let Some(v) = timeline["stereo_waveforms"].as_bool() {
+            settings.stereo_waveforms = v;
+        }
+        if let Some(v) = timeline["snapping_enabled"].as_bool() {
+            settings.snapping_enabled = v;
+        }
+        settings
+    }
+
+    pub fn save(&self) {
+        let Some(path) = settings_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            if std::fs::create_dir_all(dir).is_err() {
+                return;
+            }
+        }
+        let value = json!({
+            "audio": {
+                "scrub_audio": self.audio.scrub_audio,
+                "preview_volume": self.audio.preview_volume,
+                "output_device": self.audio.output_device,
+                "latency": self.audio.latency.label(),
+            },
+            "playback": {
+                "reverse_budget": self.playback.reverse_budget.label(),
+                "scrub_quality": self.playback.scrub_quality.label(),
+                "snap_playhead_to_frames": self.playback.snap_playhead_to_frames,
+            },
+            "timeline": {
+                "video_overlap": self.overlap.video.label(),
+                "audio_overlap": self.overlap.audio.label(),
+                "link_audio_on_drop": self.link_audio_on_drop,
+                "stereo_waveforms": self.stereo_waveforms,
+                "snapping_enabled": self.snapping_enabled,
+            },
+        });
+        if let Ok(text) = serde_json::to_string_pretty(&value) {
+            let _ = std::fs::write(path, text);
+        }
+    }
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("wizard-editor"))
+}
+
+fn settings_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("settings.json"))
+}
@@ -1,7 +1,13 @@
 use std::collections::{HashMap, HashSet};
 
+use wizard_media::audio::WaveformChunk;
 use wizard_state::clip::ClipId;
 
+use crate::constants::{
+    PREVIEW_STRIP_MAX_CLIPS, TEXTURE_EVICT_INTERVAL_S, TEXTURE_IDLE_EVICT_S,
+    TEXTURE_MEMORY_BUDGET_BYTES,
+};
+
 pub struct ScrubCacheEntry {
     pub frames: Vec<egui::TextureHandle>,
     pub pts: Vec<f64>,
@@ -37,13 +43,179 @@ pub struct TextureCache {
     pub preview_frames: HashMap<ClipId, Vec<egui::TextureHandle>>,
     pub pending_thumbnails: HashSet<ClipId>,
     pub preview_requested: HashSet<ClipId>,
-    pub waveform_peaks: HashMap<ClipId, Vec<(f32, f32)>>,
+    // When each clip's strip was last scrubbed in the browser; prefetched ones have no entry.
+    pub preview_hovered_at: HashMap<ClipId, f64>,
+    pub waveform_peaks: HashMap<ClipId, Vec<Vec<(f32, f32)>>>,
+    pub waveform_progress: HashMap<ClipId, f32>,
+    pub stereo_waveform_peaks: HashMap<ClipId, [Vec<Vec<(f32, f32)>>; 2]>,
+    pub stereo_requested: HashSet<ClipId>,
     pub playback_texture: Option<egui::TextureHandle>,
+    pub source_monitor_texture: Option<egui::TextureHandle>,
     pub scrub_frames: HashMap<ClipId, ScrubCacheEntry>,
     pub scrub_requested: HashSet<ClipId>,
+    pub last_used: HashMap<ClipId, f64>,
+    pub evicted_thumbnails: HashSet<ClipId>,
+    pub last_eviction_check: f64,
 }
 
 impl TextureCache {
+    pub fn apply_waveform_chunk(&mut self, id: ClipId, chunk: WaveformChunk) {
+        let levels = match chunk.channel {
+            None => self.waveform_peaks.entry(id).or_default(),
+            // Late chunks for a split waveform that has since been switched off are dropped.
+            Some(_) if !self.stereo_requested.contains(&id) => return,
+            Some(channel) => {
+                let channels = self.stereo_waveform_peaks.entry(id).or_default();
+                let Some(levels) = channels.get_mut(channel) else {
+                    return;
+                };
+                levels
+            }
+        };
+        if levels.len() <= chunk.level {
+            levels.resize_with(chunk.level + 1, Vec::new);
+        }
+        let level = &mut levels[chunk.level];
+        let end = chunk.range.end.max(chunk.total);
+        if level.len() < end {
+            level.resize(end, (0.0, 0.0));
+        }
+        level[chunk.range.clone()].copy_from_slice(&chunk.peaks);
+        if chunk.done {
+            level.truncate(chunk.total);
+        }
+
+        // Progress follows the coarsest level; the finer ones land in the same batch.
+        if chunk.level == 0 && chunk.channel.is_none() {
+            if chunk.done {
+                self.waveform_progress.remove(&id);
+            } else if chunk.total > 0 {
+                let progress = chunk.range.end as f32 / chunk.total as f32;
+                self.waveform_progress.insert(id, progress.min(1.0));
+            }
+        }
+    }
+
+    // Forgets everything derived from a clip's source so it can be rebuilt from a new file.
+    pub fn clear_clip(&mut self, id: ClipId) {
+        self.thumbnails.remove(&id);
+        self.preview_frames.remove(&id);
+        self.preview_requested.remove(&id);
+        self.preview_hovered_at.remove(&id);
+        self.waveform_peaks.remove(&id);
+        self.waveform_progress.remove(&id);
+        self.stereo_waveform_peaks.remove(&id);
+        self.stereo_requested.remove(&id);
+        self.scrub_frames.remove(&id);
+        self.scrub_requested.remove(&id);
+        self.last_used.remove(&id);
+        self.evicted_thumbnails.remove(&id);
+    }
+
+    pub fn touch(&mut self, id: ClipId, now: f64) {
+        self.last_used.insert(id, now);
+    }
+
+    fn clip_texture_bytes(&self, id: &ClipId) -> usize {
+        let thumb = self.thumbnails.get(id).map(texture_bytes).unwrap_or(0);
+        let previews: usize = self
+            .preview_frames
+            .get(id)
+            .map(|frames| frames.iter().map(texture_bytes).sum())
+            .unwrap_or(0);
+        thumb + previews
+    }
+
+    fn evict_clip_textures(&mut self, id: ClipId) {
+        if self.thumbnails.remove(&id).is_some() {
+            self.evicted_thumbnails.insert(id);
+        }
+        self.preview_frames.remove(&id);
+        self.preview_requested.remove(&id);
+        self.preview_hovered_at.remove(&id);
+        self.last_used.remove(&id);
+    }
+
+    // Clips holding a preview strip or waiting on one.
+    pub fn preview_strip_count(&self) -> usize {
+        self.preview_requested
+            .iter()
+            .chain(self.preview_frames.keys())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    // Keeps at most PREVIEW_STRIP_MAX_CLIPS strips, dropping prefetched ones that were never
+    // scrubbed first, then the least recently scrubbed. Clips in `keep` always stay. Returns
+    // what was dropped so queued extractions can be cancelled too.
+    pub fn evict_preview_strips(&mut self, keep: &[ClipId]) -> Vec<ClipId> {
+        let held: HashSet<ClipId> = self
+            .preview_requested
+            .iter()
+            .chain(self.preview_frames.keys())
+            .copied()
+            .collect();
+        let Some(excess) = held.len().checked_sub(PREVIEW_STRIP_MAX_CLIPS) else {
+            return Vec::new();
+        };
+        let mut candidates: Vec<(ClipId, f64)> = held
+            .into_iter()
+            .filter(|id| !keep.contains(id))
+            .map(|id| {
+                let hovered_at = self.preview_hovered_at.get(&id).copied();
+                (id, hovered_at.unwrap_or(f64::NEG_INFINITY))
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let evicted: Vec<ClipId> = candidates
+            .into_iter()
+            .take(excess)
+            .map(|(id, _)| id)
+            .collect();
+        for id in &evicted {
+            self.preview_frames.remove(id);
+            self.preview_requested.remove(id);
+            self.preview_hovered_at.remove(id);
+        }
+        evicted
+    }
+
+    // Drops thumbnails and preview strips for clips that haven't been on screen for a while,
+    // then keeps dropping the least recently used ones until the total fits the budget.
+    // Anything touched this frame is left alone.
+    pub fn evict_stale_textures(&mut self, now: f64) {
+        if now - self.last_eviction_check < TEXTURE_EVICT_INTERVAL_S {
+            return;
+        }
+        self.last_eviction_check = now;
+
+        let ids: HashSet<ClipId> = self
+            .thumbnails
+            .keys()
+            .chain(self.preview_frames.keys())
+            .copied()
+            .collect();
+        let mut entries: Vec<(ClipId, f64)> = ids
+            .into_iter()
+            .map(|id| (id, *self.last_used.entry(id).or_insert(now)))
+            .collect();
+        entries.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let mut total: usize = entries
+            .iter()
+            .map(|(id, _)| self.clip_texture_bytes(id))
+            .sum();
+        for (id, last_used) in entries {
+            let idle = now - last_used;
+            let over_budget = total > TEXTURE_MEMORY_BUDGET_BYTES && last_used < now;
+            if idle < TEXTURE_IDLE_EVICT_S && !over_budget {
+                continue;
+            }
+            total = total.saturating_sub(self.clip_texture_bytes(&id));
+            self.evict_clip_textures(id);
+        }
+    }
+
     pub fn update_playback_texture(
         &mut self,
         ctx: &egui::Context,
@@ -51,17 +223,45 @@ impl TextureCache {
         height: usize,
         rgba_data: &[u8],
     ) {
-        let image = egui::ColorImage::from_rgba_unmultiplied([width, height], rgba_data);
-        if let Some(ref mut handle) = self.playback_texture {
-            let [tw, th] = handle.size();
-            if tw == width && th == height {
-                handle.set(image, egui::TextureOptions::LINEAR);
-                return;
-            }
+        let slot = &mut self.playback_texture;
+        upload_frame(slot, ctx, "playback_frame", width, height, rgba_data);
+    }
+
+    pub fn update_source_monitor_texture(
+        &mut self,
+        ctx: &egui::Context,
+        width: usize,
+        height: usize,
+        rgba_data: &[u8],
+    ) {
+        let slot = &mut self.source_monitor_texture;
+        upload_frame(slot, ctx, "source_monitor_frame", width, height, rgba_data);
+    }
+}
+
+// Frames of the same size are written into the existing texture rather than reallocating.
+fn upload_frame(
+    slot: &mut Option<egui::TextureHandle>,
+    ctx: &egui::Context,
+    name: &str,
+    width: usize,
+    height: usize,
+    rgba_data: &[u8],
+) {
+    let image = egui::ColorImage::from_rgba_unmultiplied([width, height], rgba_data);
+    if let Some(handle) = slot {
+        let [tw, th] = handle.size();
+        if tw == width && th == height {
+            handle.set(image, egui::TextureOptions::LINEAR);
+            return;
         }
-        let texture = ctx.load_texture("playback_frame", image, egui::TextureOptions::LINEAR);
-        self.playback_texture = Some(texture);
     }
+    *slot = Some(ctx.load_texture(name, image, egui::TextureOptions::LINEAR));
+}
+
+fn texture_bytes(tex: &egui::TextureHandle) -> usize {
+    let [w, h] = tex.size();
+    w * h * 4
 }
 
 impl wizard_ui::TextureLookup for TextureCache {
@@ -81,14 +281,30 @@ impl wizard_ui::TextureLookup for TextureCache {
         self.preview_requested.contains(id)
     }
 
-    fn waveform_peaks(&self, id: &ClipId) -> Option<&Vec<(f32, f32)>> {
-        self.waveform_peaks.get(id)
+    fn waveform_peaks(&self, id: &ClipId) -> Option<&[Vec<(f32, f32)>]> {
+        self.waveform_peaks.get(id).map(|levels| levels.as_slice())
+    }
+
+    fn stereo_waveform_peaks(&self, id: &ClipId) -> Option<[&[Vec<(f32, f32)>]; 2]> {
+        let [left, right] = self.stereo_waveform_peaks.get(id)?;
+        if left.is_empty() || right.is_empty() {
+            return None;
+        }
+        Some([left.as_slice(), right.as_slice()])
+    }
+
+    fn waveform_progress(&self, id: &ClipId) -> Option<f32> {
+        self.waveform_progress.get(id).copied()
     }
 
     fn playback_frame(&self) -> Option<&egui::TextureHandle> {
         self.playback_texture.as_ref()
     }
 
+    fn source_monitor_frame(&self) -> Option<&egui::TextureHandle> {
+        self.source_monitor_texture.as_ref()
+    }
+
     fn scrub_frame_at_time(&self, id: &ClipId, source_time: f64) -> Option<&egui::TextureHandle> {
         self.scrub_frames
             .get(id)
@@ -2,9 +2,12 @@ use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 
 use wizard_media::gst_pipeline::GstAudioDecoder;
 
+use super::WorkerThreads;
+
 pub enum AudioPreviewRequest {
     Stop,
     Preview {
@@ -21,13 +24,23 @@ pub struct AudioSnippet {
 pub struct AudioWorkerChannels {
     pub req_tx: mpsc::Sender<AudioPreviewRequest>,
     pub snippet_rx: mpsc::Receiver<AudioSnippet>,
+    pub threads: WorkerThreads,
+}
+
+impl AudioWorkerChannels {
+    // Closing the request channel ends the worker's wait for the next request.
+    pub fn shutdown(&mut self) -> Vec<JoinHandle<()>> {
+        self.req_tx = mpsc::channel().0;
+        self.threads.stop()
+    }
 }
 
 pub fn spawn_audio_worker(no_audio_paths: Arc<Mutex<HashSet<PathBuf>>>) -> AudioWorkerChannels {
     let (req_tx, req_rx) = mpsc::channel();
     let (snippet_tx, snippet_rx) = mpsc::sync_channel(8);
+    let mut threads = WorkerThreads::default();
 
-    std::thread::spawn(move || {
+    threads.push(std::thread::spawn(move || {
         let mut cached_decoder: Option<(PathBuf, GstAudioDecoder)> = None;
 
         let open_decoder = |path: &std::path::Path,
@@ -92,9 +105,13 @@ pub fn spawn_audio_worker(no_audio_paths: Arc<Mutex<HashSet<PathBuf>>>) -> Audio
                 }
             }
         }
-    });
+    }));
 
-    AudioWorkerChannels { req_tx, snippet_rx }
+    AudioWorkerChannels {
+        req_tx,
+        snippet_rx,
+        threads,
+    }
 }
 
 fn apply_fade(samples: &mut [f32], sample_rate: u32) {
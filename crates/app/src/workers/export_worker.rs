@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use wizard_media::export::export_audio;
+use wizard_state::clip::{Clip, ClipId};
+use wizard_state::task::BackgroundTasks;
+use wizard_state::timeline::Timeline;
+
+use crate::constants::{EXPORT_AUDIO_CHANNELS, EXPORT_AUDIO_SAMPLE_RATE};
+
+// Works on a copy of the timeline so editing can carry on while the mix renders.
+pub fn spawn_audio_export(
+    timeline: Timeline,
+    clips: HashMap<ClipId, Clip>,
+    out_path: PathBuf,
+    range: Option<(f64, f64)>,
+    tasks: &BackgroundTasks,
+) {
+    let task = tasks.start("Export audio");
+    std::thread::spawn(move || {
+        let result = export_audio(
+            &timeline,
+            &clips,
+            &out_path,
+            EXPORT_AUDIO_SAMPLE_RATE,
+            EXPORT_AUDIO_CHANNELS,
+            range,
+            &task,
+        );
+        match result {
+            Err(e) if !task.is_cancelled() => task.fail(format!("Audio export failed: {e}")),
+            _ => task.finish(),
+        }
+    });
+}
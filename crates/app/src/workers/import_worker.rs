@@ -0,0 +1,241 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+
+use wizard_media::audio::WaveformChunk;
+use wizard_media::metadata::MediaMetadata;
+use wizard_state::clip::ClipId;
+use wizard_state::task::BackgroundTasks;
+
+use super::WorkerThreads;
+
+const WORKER_COUNT: usize = 2;
+
+pub enum ImportRequest {
+    Enqueue { clip_id: ClipId, path: PathBuf },
+    Thumbnail { clip_id: ClipId, path: PathBuf },
+    Analyze { clip_id: ClipId, path: PathBuf },
+    StereoWaveform { clip_id: ClipId, path: PathBuf },
+    Prioritize { clip_ids: Vec<ClipId> },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ImportJob {
+    Full,
+    ThumbnailOnly,
+    AnalyzeOnly,
+    StereoWaveform,
+}
+
+type ImportItem = (ClipId, PathBuf, ImportJob);
+
+pub struct ImportSenders {
+    pub meta_tx: mpsc::Sender<(ClipId, MediaMetadata)>,
+    pub duration_tx: mpsc::Sender<(ClipId, f64)>,
+    pub thumb_tx: mpsc::Sender<(ClipId, image::RgbaImage)>,
+    pub waveform_tx: mpsc::Sender<(ClipId, WaveformChunk)>,
+    pub black_tx: mpsc::Sender<(ClipId, bool)>,
+    pub tasks: BackgroundTasks,
+}
+
+fn apply_import_req(
+    req: ImportRequest,
+    queue: &mut VecDeque<ImportItem>,
+    queued: &mut HashSet<ClipId>,
+) {
+    match req {
+        ImportRequest::Enqueue { clip_id, path } => {
+            if queued.insert(clip_id) {
+                queue.push_back((clip_id, path, ImportJob::Full));
+            }
+        }
+        ImportRequest::Thumbnail { clip_id, path } => {
+            if queued.insert(clip_id) {
+                queue.push_front((clip_id, path, ImportJob::ThumbnailOnly));
+            }
+        }
+        ImportRequest::Analyze { clip_id, path } => {
+            if queued.insert(clip_id) {
+                queue.push_back((clip_id, path, ImportJob::AnalyzeOnly));
+            }
+        }
+        // Not deduplicated against the other jobs; the app only asks once per clip.
+        ImportRequest::StereoWaveform { clip_id, path } => {
+            queue.push_back((clip_id, path, ImportJob::StereoWaveform));
+        }
+        ImportRequest::Prioritize { clip_ids } => {
+            for clip_id in clip_ids.iter().rev() {
+                if let Some(pos) = queue.iter().position(|(id, _, _)| id == clip_id) {
+                    if let Some(item) = queue.remove(pos) {
+                        queue.push_front(item);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct ImportWorkerChannels {
+    pub req_tx: mpsc::Sender<ImportRequest>,
+    // Quick duration reads, kept off the import queue so they land long before metadata.
+    pub probe_tx: mpsc::Sender<(ClipId, PathBuf)>,
+    // Read per job, so switching it on affects everything not yet started.
+    pub quality_checks: Arc<AtomicBool>,
+    pub threads: WorkerThreads,
+}
+
+impl ImportWorkerChannels {
+    // Closing the request channels wakes the dispatcher and the prober, and the workers
+    // follow once the dispatcher has gone.
+    pub fn shutdown(&mut self) -> Vec<JoinHandle<()>> {
+        self.req_tx = mpsc::channel().0;
+        self.probe_tx = mpsc::channel().0;
+        self.threads.stop()
+    }
+}
+
+pub fn spawn_import_worker(senders: ImportSenders) -> ImportWorkerChannels {
+    let (req_tx, req_rx) = mpsc::channel();
+    // A rendezvous channel keeps work in the queue until a worker is free, so
+    // reprioritising still affects everything that hasn't started yet.
+    let (work_tx, work_rx) = mpsc::sync_channel::<ImportItem>(0);
+    let work_rx = Arc::new(Mutex::new(work_rx));
+    let quality_checks = Arc::new(AtomicBool::new(false));
+    let mut threads = WorkerThreads::default();
+
+    let dispatch = std::thread::Builder::new()
+        .name("import-dispatch".into())
+        .spawn(move || {
+            let mut queue: VecDeque<ImportItem> = VecDeque::new();
+            let mut queued: HashSet<ClipId> = HashSet::new();
+
+            loop {
+                while let Ok(req) = req_rx.try_recv() {
+                    apply_import_req(req, &mut queue, &mut queued);
+                }
+
+                let Some(item) = queue.pop_front() else {
+                    let Ok(req) = req_rx.recv() else {
+                        return;
+                    };
+                    apply_import_req(req, &mut queue, &mut queued);
+                    continue;
+                };
+                if item.2 != ImportJob::StereoWaveform {
+                    queued.remove(&item.0);
+                }
+
+                if work_tx.send(item).is_err() {
+                    return;
+                }
+            }
+        })
+        .expect("failed to spawn import-dispatch thread");
+    threads.push(dispatch);
+
+    let (probe_tx, probe_rx) = mpsc::channel::<(ClipId, PathBuf)>();
+    let duration_tx = senders.duration_tx.clone();
+    let probe = std::thread::Builder::new()
+        .name("import-probe".into())
+        .spawn(move || {
+            while let Ok((clip_id, path)) = probe_rx.recv() {
+                if let Some(duration) = wizard_media::metadata::probe_duration(&path) {
+                    if duration_tx.send((clip_id, duration)).is_err() {
+                        return;
+                    }
+                }
+            }
+        })
+        .expect("failed to spawn import-probe thread");
+    threads.push(probe);
+
+    for _ in 0..WORKER_COUNT {
+        let work_rx = Arc::clone(&work_rx);
+        let mtx = senders.meta_tx.clone();
+        let ttx = senders.thumb_tx.clone();
+        let wtx = senders.waveform_tx.clone();
+        let btx = senders.black_tx.clone();
+        let tasks = senders.tasks.clone();
+        let quality_checks = Arc::clone(&quality_checks);
+        let stop = threads.stop_flag();
+        let worker = std::thread::Builder::new()
+            .name("import-worker".into())
+            .spawn(move || loop {
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                let (clip_id, path, job) = {
+                    let rx = work_rx.lock().expect("work_rx lock poisoned");
+                    match rx.recv() {
+                        Ok(item) => item,
+                        Err(_) => return,
+                    }
+                };
+
+                if job == ImportJob::ThumbnailOnly {
+                    if let Some(img) = wizard_media::thumbnail::extract_thumbnail(&path) {
+                        let _ = ttx.send((clip_id, img));
+                    }
+                    continue;
+                }
+
+                if job == ImportJob::StereoWaveform {
+                    wizard_media::audio::stream_waveform_pyramid(&path, true, |chunk| {
+                        wtx.send((clip_id, chunk)).is_ok() && !stop.load(Ordering::Relaxed)
+                    });
+                    continue;
+                }
+
+                if job == ImportJob::AnalyzeOnly {
+                    let black = wizard_media::thumbnail::opening_frames_black(&path);
+                    let _ = btx.send((clip_id, black));
+                    continue;
+                }
+
+                let meta = wizard_media::metadata::extract_metadata(&path);
+                let has_video = meta.has_video;
+                if mtx.send((clip_id, meta)).is_err() {
+                    return;
+                }
+
+                if has_video {
+                    let img = if quality_checks.load(Ordering::Relaxed) {
+                        let (img, black) =
+                            wizard_media::thumbnail::extract_thumbnail_checking_black(&path);
+                        let _ = btx.send((clip_id, black));
+                        img
+                    } else {
+                        wizard_media::thumbnail::extract_thumbnail(&path)
+                    };
+                    if let Some(img) = img {
+                        let _ = ttx.send((clip_id, img));
+                    }
+                }
+
+                let name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let task = tasks.start(format!("Waveform: {name}"));
+                wizard_media::audio::stream_waveform_pyramid(&path, false, |chunk| {
+                    if chunk.total > 0 {
+                        task.set_progress(chunk.range.end as f32 / chunk.total as f32);
+                    }
+                    let _ = wtx.send((clip_id, chunk));
+                    !task.is_cancelled() && !stop.load(Ordering::Relaxed)
+                });
+                task.finish();
+            })
+            .expect("failed to spawn import-worker thread");
+        threads.push(worker);
+    }
+
+    ImportWorkerChannels {
+        req_tx,
+        probe_tx,
+        quality_checks,
+        threads,
+    }
+}
@@ -1,8 +1,35 @@
 use wizard_state::playback::PlaybackState;
-use wizard_state::project::AppState;
+use wizard_state::project::{AppState, TrimEdge};
 
 pub fn handle_keyboard(ctx: &egui::Context, state: &mut AppState) {
+    if ctx.wants_keyboard_input() {
+        return;
+    }
     ctx.input(|i| {
+        // Clips go through our own clipboard, never the system one. Depending on the platform
+        // and what the system clipboard holds, Cmd+V arrives as a Paste event, a V press, or
+        // both, so paste once per frame on whichever comes first.
+        let mut pasted = false;
+        for event in &i.events {
+            match event {
+                egui::Event::Copy => state.copy_selected_clips(),
+                egui::Event::Paste(_) if !pasted => {
+                    state.paste_clipboard();
+                    pasted = true;
+                }
+                egui::Event::Key {
+                    key: egui::Key::V,
+                    pressed: true,
+                    repeat: false,
+                    modifiers,
+                    ..
+                } if modifiers.command && !pasted => {
+                    state.paste_clipboard();
+                    pasted = true;
+                }
+                _ => {}
+            }
+        }
         if i.key_pressed(egui::Key::L) {
             match state.project.playback.state {
                 PlaybackState::Playing => {
@@ -29,24 +56,104 @@ pub fn handle_keyboard(ctx: &egui::Context, state: &mut AppState) {
             state.project.playback.speed = 1.0;
             state.project.playback.stop();
         }
+        // While the source monitor is up, Space plays it rather than the timeline.
         if i.key_pressed(egui::Key::Space) {
-            state.project.playback.speed = 1.0;
-            state.project.playback.toggle_play();
+            if let Some(monitor) = state.ui.preview.source_monitor.as_mut() {
+                monitor.playing = !monitor.playing;
+            } else {
+                let end = state.project.playback_end();
+                state.project.playback.toggle_play(end);
+            }
+        }
+        if i.key_pressed(egui::Key::Escape) {
+            state.close_source_monitor();
         }
+        // Shift ripples the gap closed; Alt also takes linked partners that aren't selected.
         if i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace) {
             if !state.ui.selection.selected_timeline_clips.is_empty() {
                 state.project.snapshot_for_undo();
                 let to_delete: Vec<_> =
                     state.ui.selection.selected_timeline_clips.drain().collect();
                 for clip_id in to_delete {
-                    state.project.timeline.remove_clip(clip_id);
+                    let timeline = &mut state.project.timeline;
+                    if i.modifiers.shift {
+                        timeline.ripple_delete(clip_id);
+                    } else if i.modifiers.alt {
+                        timeline.remove_clip(clip_id);
+                    } else {
+                        timeline.remove_clip_single(clip_id);
+                    }
+                }
+            }
+        }
+        let next_edit =
+            i.key_pressed(egui::Key::ArrowDown) || i.key_pressed(egui::Key::CloseBracket);
+        let previous_edit =
+            i.key_pressed(egui::Key::ArrowUp) || i.key_pressed(egui::Key::OpenBracket);
+        if next_edit || previous_edit {
+            let playhead = state.project.playback.playhead;
+            let target = if next_edit {
+                state.project.timeline.next_edit_point(playhead)
+            } else {
+                state.project.timeline.previous_edit_point(playhead)
+            };
+            if let Some(mut t) = target {
+                // Rounding outward keeps an off-grid cut from snapping back to where we were.
+                if state.ui.playback.snap_playhead_to_frames {
+                    let settings = &state.project.settings;
+                    t = if next_edit {
+                        settings.next_frame_boundary(t)
+                    } else {
+                        settings.frame_start(t)
+                    };
                 }
+                state.project.playback.playhead = t;
+                state.ui.timeline.reveal_playhead = true;
             }
         }
+        let trim_edge = if i.key_pressed(egui::Key::Q) {
+            Some(TrimEdge::Left)
+        } else if i.key_pressed(egui::Key::W) {
+            Some(TrimEdge::Right)
+        } else {
+            None
+        };
+        if let Some(edge) = trim_edge {
+            if let Some(clip_id) = state.project.clip_for_playhead_edit(&state.ui.selection) {
+                state.ui.selection.select_single_timeline_clip(clip_id);
+                state.project.trim_clip_to_playhead(clip_id, edge);
+            }
+        }
+        if i.key_pressed(egui::Key::S) && !i.modifiers.command {
+            state.project.split_all_at_playhead();
+        }
+        if i.key_pressed(egui::Key::F) {
+            state.match_frame();
+        }
+        if i.key_pressed(egui::Key::I) {
+            state.mark_source_in();
+        }
+        if i.key_pressed(egui::Key::O) {
+            state.mark_source_out();
+        }
+        if i.key_pressed(egui::Key::Comma) {
+            state.insert_source_at_playhead();
+        }
+        if i.key_pressed(egui::Key::N) {
+            state.ui.timeline.snapping_enabled = !state.ui.timeline.snapping_enabled;
+        }
+        if i.key_pressed(egui::Key::T) {
+            state.ui.timeline.trim_mode = !state.ui.timeline.trim_mode;
+        }
+        if i.key_pressed(egui::Key::Backslash) {
+            state.ui.preview.scale = state.ui.preview.scale.toggled();
+        }
         if i.modifiers.command && !i.modifiers.shift && i.key_pressed(egui::Key::Z) {
             state.project.undo();
         }
-        if i.modifiers.command && i.modifiers.shift && i.key_pressed(egui::Key::Z) {
+        let redo =
+            (i.modifiers.shift && i.key_pressed(egui::Key::Z)) || i.key_pressed(egui::Key::Y);
+        if i.modifiers.command && redo {
             state.project.redo();
         }
     });
@@ -1,5 +1,47 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
 pub mod audio_worker;
+pub mod export_worker;
+pub mod import_worker;
 pub mod keyboard;
 pub mod preview_worker;
 pub mod scrub_cache_worker;
 pub mod video_decode_worker;
+
+// A worker's threads and the flag asking them to stop. Threads check the flag between
+// jobs; one waiting on an empty queue is woken instead by its request channel closing.
+#[derive(Default)]
+pub struct WorkerThreads {
+    stop: Arc<AtomicBool>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl WorkerThreads {
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    pub fn push(&mut self, handle: JoinHandle<()>) {
+        self.handles.push(handle);
+    }
+
+    pub fn stop(&mut self) -> Vec<JoinHandle<()>> {
+        self.stop.store(true, Ordering::Relaxed);
+        std::mem::take(&mut self.handles)
+    }
+}
+
+// Waits for threads to wind down until `deadline`. One still busy in a decode is left for
+// the process to tear down rather than holding the window open.
+pub fn join_until(mut handles: Vec<JoinHandle<()>>, deadline: Instant) {
+    while Instant::now() < deadline {
+        handles.retain(|h| !h.is_finished());
+        if handles.is_empty() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
@@ -1,8 +1,12 @@
 use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::Ordering;
 use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
 
 use wizard_state::clip::ClipId;
 
+use super::WorkerThreads;
+
 const PREVIEW_FRAME_COUNT: usize = 32;
 const WORKER_COUNT: usize = 3;
 
@@ -12,6 +16,9 @@ pub enum PreviewRequest {
         path: std::path::PathBuf,
         priority: bool,
     },
+    Cancel {
+        clip_id: ClipId,
+    },
 }
 
 pub struct PreviewFrame {
@@ -42,25 +49,45 @@ fn apply_preview_req(
                 queue.push_back((clip_id, path));
             }
         }
+        PreviewRequest::Cancel { clip_id } => {
+            if queued.remove(&clip_id) {
+                queue.retain(|(id, _)| *id != clip_id);
+            }
+        }
     }
 }
 
 pub struct PreviewWorkerChannels {
     pub req_tx: mpsc::Sender<PreviewRequest>,
     pub result_rx: mpsc::Receiver<PreviewFrame>,
+    pub threads: WorkerThreads,
+}
+
+impl PreviewWorkerChannels {
+    // Closing the request channel wakes the dispatcher, which lets the workers run dry.
+    pub fn shutdown(&mut self) -> Vec<JoinHandle<()>> {
+        self.req_tx = mpsc::channel().0;
+        self.threads.stop()
+    }
 }
 
 pub fn spawn_preview_worker() -> PreviewWorkerChannels {
     let (req_tx, req_rx) = mpsc::channel();
     let (result_tx, result_rx) = mpsc::channel();
-    let (work_tx, work_rx) = mpsc::channel::<(ClipId, std::path::PathBuf)>();
+    // Only hand work over once a worker is free so cancels can still reach queued clips.
+    let (work_tx, work_rx) = mpsc::sync_channel::<(ClipId, std::path::PathBuf)>(0);
     let work_rx = Arc::new(Mutex::new(work_rx));
+    let mut threads = WorkerThreads::default();
 
-    std::thread::spawn(move || {
+    threads.push(std::thread::spawn(move || {
         let mut queue: VecDeque<(ClipId, std::path::PathBuf)> = VecDeque::new();
         let mut queued: HashSet<ClipId> = HashSet::new();
 
         loop {
+            while let Ok(req) = req_rx.try_recv() {
+                apply_preview_req(req, &mut queue, &mut queued);
+            }
+
             let item = if let Some(item) = queue.pop_front() {
                 queued.remove(&item.0);
                 item
@@ -72,20 +99,20 @@ pub fn spawn_preview_worker() -> PreviewWorkerChannels {
                 continue;
             };
 
-            while let Ok(req) = req_rx.try_recv() {
-                apply_preview_req(req, &mut queue, &mut queued);
-            }
-
             if work_tx.send(item).is_err() {
                 return;
             }
         }
-    });
+    }));
 
     for _ in 0..WORKER_COUNT {
         let work_rx = Arc::clone(&work_rx);
         let result_tx = result_tx.clone();
-        std::thread::spawn(move || loop {
+        let stop = threads.stop_flag();
+        threads.push(std::thread::spawn(move || loop {
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
             let (clip_id, path) = {
                 let rx = work_rx.lock().expect("work_rx lock poisoned");
                 match rx.recv() {
@@ -115,9 +142,13 @@ pub fn spawn_preview_worker() -> PreviewWorkerChannels {
                     return;
                 }
             }
-        });
+        }));
     }
     drop(result_tx);
 
-    PreviewWorkerChannels { req_tx, result_rx }
+    PreviewWorkerChannels {
+        req_tx,
+        result_rx,
+        threads,
+    }
 }
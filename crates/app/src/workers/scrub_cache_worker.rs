@@ -1,9 +1,13 @@
 use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::Ordering;
 use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
 
 use wizard_media::gst_pipeline::GstFrameDecoder;
 use wizard_state::clip::ClipId;
 
+use super::WorkerThreads;
+
 use crate::constants::{
     SCRUB_CACHE_FPS, SCRUB_CACHE_HEIGHT, SCRUB_CACHE_MAX_FRAMES, SCRUB_CACHE_WIDTH,
     SCRUB_CACHE_WORKER_COUNT,
@@ -30,6 +34,15 @@ pub struct ScrubCacheFrame {
 pub struct ScrubCacheWorkerChannels {
     pub req_tx: mpsc::Sender<ScrubCacheRequest>,
     pub result_rx: mpsc::Receiver<ScrubCacheFrame>,
+    pub threads: WorkerThreads,
+}
+
+impl ScrubCacheWorkerChannels {
+    // Closing the request channel wakes the dispatcher, which lets the workers run dry.
+    pub fn shutdown(&mut self) -> Vec<JoinHandle<()>> {
+        self.req_tx = mpsc::channel().0;
+        self.threads.stop()
+    }
 }
 
 fn apply_scrub_req(
@@ -57,8 +70,9 @@ pub fn spawn_scrub_cache_worker() -> ScrubCacheWorkerChannels {
     let (result_tx, result_rx) = mpsc::channel();
     let (work_tx, work_rx) = mpsc::channel::<(ClipId, std::path::PathBuf)>();
     let work_rx = Arc::new(Mutex::new(work_rx));
+    let mut threads = WorkerThreads::default();
 
-    std::thread::spawn(move || {
+    threads.push(std::thread::spawn(move || {
         let mut queue: VecDeque<(ClipId, std::path::PathBuf)> = VecDeque::new();
         let mut queued: HashSet<ClipId> = HashSet::new();
 
@@ -82,12 +96,17 @@ pub fn spawn_scrub_cache_worker() -> ScrubCacheWorkerChannels {
                 return;
             }
         }
-    });
+    }));
 
     for _ in 0..SCRUB_CACHE_WORKER_COUNT {
         let work_rx = Arc::clone(&work_rx);
         let result_tx = result_tx.clone();
-        std::thread::spawn(move || loop {
+        let stop = threads.stop_flag();
+        // The work queue isn't bounded, so the flag is what keeps queued clips from running.
+        threads.push(std::thread::spawn(move || loop {
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
             let (clip_id, path) = {
                 let rx = work_rx.lock().expect("work_rx lock poisoned");
                 match rx.recv() {
@@ -135,9 +154,13 @@ pub fn spawn_scrub_cache_worker() -> ScrubCacheWorkerChannels {
                     return;
                 }
             }
-        });
+        }));
     }
     drop(result_tx);
 
-    ScrubCacheWorkerChannels { req_tx, result_rx }
+    ScrubCacheWorkerChannels {
+        req_tx,
+        result_rx,
+        threads,
+    }
 }
@@ -3,10 +3,13 @@ use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::mpsc;
 use std::sync::Arc;
+use std::thread::JoinHandle;
 
 use wizard_media::gst_pipeline::GstFrameDecoder;
 use wizard_state::clip::ClipId;
 
+use super::WorkerThreads;
+
 pub const PLAYBACK_DECODE_WIDTH: u32 = 1920;
 pub const PLAYBACK_DECODE_HEIGHT: u32 = 1080;
 
@@ -31,8 +34,22 @@ pub struct VideoDecodeResult {
 pub struct VideoDecodeWorkerChannels {
     pub req_tx: mpsc::Sender<VideoDecodeRequest>,
     pub result_rx: mpsc::Receiver<VideoDecodeResult>,
+    pub threads: WorkerThreads,
+}
+
+impl VideoDecodeWorkerChannels {
+    // Closing the request channel ends the worker's wait for the next request.
+    pub fn shutdown(&mut self) -> Vec<JoinHandle<()>> {
+        self.req_tx = mpsc::channel().0;
+        self.threads.stop()
+    }
 }
 
+// Decoders scale to a fixed size when opened, so scrub-sized and full-size requests for
+// the same file each keep their own warm decoder instead of reopening the file.
+type DecoderKey = (PathBuf, u32, u32);
+type FrameKey = (ClipId, i64, u32, u32);
+
 struct FrameCacheEntry {
     image: Arc<image::RgbaImage>,
     order: u64,
@@ -41,12 +58,13 @@ struct FrameCacheEntry {
 pub fn spawn_video_decode_worker() -> VideoDecodeWorkerChannels {
     let (req_tx, req_rx) = mpsc::channel::<VideoDecodeRequest>();
     let (result_tx, result_rx) = mpsc::channel::<VideoDecodeResult>();
+    let mut threads = WorkerThreads::default();
 
-    std::thread::spawn(move || {
-        let mut decoder_lru: VecDeque<(PathBuf, GstFrameDecoder)> =
+    threads.push(std::thread::spawn(move || {
+        let mut decoder_lru: VecDeque<(DecoderKey, GstFrameDecoder)> =
             VecDeque::with_capacity(DECODER_LRU_CAPACITY);
-        let mut last_emitted: Option<(ClipId, i64)> = None;
-        let mut frame_cache: HashMap<(ClipId, i64), FrameCacheEntry> = HashMap::new();
+        let mut last_emitted: Option<FrameKey> = None;
+        let mut frame_cache: HashMap<FrameKey, FrameCacheEntry> = HashMap::new();
         let mut cache_order: u64 = 0;
 
         loop {
@@ -57,7 +75,8 @@ pub fn spawn_video_decode_worker() -> VideoDecodeWorkerChannels {
                 req = next;
             }
 
-            let lru_idx = decoder_lru.iter().position(|(p, _)| p == &req.path);
+            let decoder_key = (req.path.clone(), req.target_width, req.target_height);
+            let lru_idx = decoder_lru.iter().position(|(key, _)| key == &decoder_key);
             let decoder_idx = if let Some(idx) = lru_idx {
                 if idx != 0 {
                     let entry = decoder_lru.remove(idx).expect("index valid");
@@ -70,7 +89,7 @@ pub fn spawn_video_decode_worker() -> VideoDecodeWorkerChannels {
                         if decoder_lru.len() >= DECODER_LRU_CAPACITY {
                             decoder_lru.pop_back();
                         }
-                        decoder_lru.push_front((req.path.clone(), d));
+                        decoder_lru.push_front((decoder_key, d));
                         0
                     }
                     Err(_) => continue,
@@ -80,18 +99,18 @@ pub fn spawn_video_decode_worker() -> VideoDecodeWorkerChannels {
             let (_, ref mut decoder) = decoder_lru[decoder_idx];
 
             let bucket = (req.time_seconds * 60.0).round() as i64;
-            if last_emitted == Some((req.clip_id, bucket)) {
+            let cache_key = (req.clip_id, bucket, req.target_width, req.target_height);
+            if last_emitted == Some(cache_key) {
                 continue;
             }
 
-            let cache_key = (req.clip_id, bucket);
             if let Some(entry) = frame_cache.get(&cache_key) {
                 let _ = result_tx.send(VideoDecodeResult {
                     clip_id: req.clip_id,
                     time_seconds: req.time_seconds,
                     image: Arc::clone(&entry.image),
                 });
-                last_emitted = Some((req.clip_id, bucket));
+                last_emitted = Some(cache_key);
                 continue;
             }
 
@@ -101,9 +120,9 @@ pub fn spawn_video_decode_worker() -> VideoDecodeWorkerChannels {
             });
 
             let img = if can_sequential {
-                decoder.decode_next_frame()
+                decoder.decode_until(req.time_seconds, req.max_decode_frames)
             } else {
-                decoder.seek_and_decode(req.time_seconds)
+                decoder.seek_and_decode_exact(req.time_seconds, req.max_decode_frames)
             };
 
             if let Some(img) = img {
@@ -132,10 +151,14 @@ pub fn spawn_video_decode_worker() -> VideoDecodeWorkerChannels {
                     time_seconds: req.time_seconds,
                     image: img,
                 });
-                last_emitted = Some((req.clip_id, bucket));
+                last_emitted = Some(cache_key);
             }
         }
-    });
+    }));
 
-    VideoDecodeWorkerChannels { req_tx, result_rx }
+    VideoDecodeWorkerChannels {
+        req_tx,
+        result_rx,
+        threads,
+    }
 }
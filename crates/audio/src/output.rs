@@ -1,26 +1,46 @@
 use std::sync::{Arc, Mutex};
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{FromSample, Sample, SampleFormat};
+use cpal::{FromSample, Sample, SampleFormat, SupportedBufferSize, SupportedStreamConfig};
 use ringbuf::traits::{Consumer, Producer, Split};
 use ringbuf::HeapRb;
 
 pub type AudioProducer = ringbuf::HeapProd<f32>;
 pub type AudioConsumer = ringbuf::HeapCons<f32>;
 
+// With no requested size the ring holds a few device periods, but never less than the
+// floor, since some hosts report minimum periods far too small to feed from the UI thread.
+const DEVICE_PERIODS: usize = 4;
+const MIN_BUFFER_MS: usize = 50;
+const UNKNOWN_DEVICE_BUFFER_MS: usize = 125;
+
 pub struct AudioOutput {
     _stream: cpal::Stream,
+    device_name: String,
+    requested_device: Option<String>,
     sample_rate_hz: u32,
     channels: u16,
+    requested_buffer_ms: Option<u32>,
+    buffer_len: usize,
     consumer_slot: Arc<Mutex<AudioConsumer>>,
 }
 
 impl AudioOutput {
     pub fn new() -> Result<(Self, AudioProducer), String> {
-        let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .ok_or_else(|| "No default output device".to_string())?;
+        Self::open(None, None)
+    }
+
+    // Opens the named output device, falling back to the system default when it isn't
+    // connected. `buffer_ms` sizes the ring buffer feeding the device; None derives it from
+    // the device's own buffer size.
+    pub fn open(
+        device_name: Option<&str>,
+        buffer_ms: Option<u32>,
+    ) -> Result<(Self, AudioProducer), String> {
+        let device =
+            target_device(device_name).ok_or_else(|| "No default output device".to_string())?;
+        let requested_device = device_name.map(str::to_string);
+        let device_name = device.name().unwrap_or_default();
 
         let supported = device
             .default_output_config()
@@ -28,10 +48,15 @@ impl AudioOutput {
 
         let sample_rate_hz = supported.sample_rate().0;
         let sample_format = supported.sample_format();
+        let frames = match buffer_ms {
+            Some(ms) => sample_rate_hz as usize * ms as usize / 1000,
+            None => default_buffer_frames(&supported),
+        };
         let config: cpal::StreamConfig = supported.into();
         let channels = config.channels;
+        let buffer_len = frames.max(1) * channels as usize;
 
-        let rb = HeapRb::<f32>::new(sample_rate_hz as usize / 4);
+        let rb = HeapRb::<f32>::new(buffer_len);
         let (producer, consumer) = rb.split();
 
         let consumer_slot = Arc::new(Mutex::new(consumer));
@@ -60,14 +85,41 @@ impl AudioOutput {
         Ok((
             Self {
                 _stream: stream,
+                device_name,
+                requested_device,
                 sample_rate_hz,
                 channels,
+                requested_buffer_ms: buffer_ms,
+                buffer_len,
                 consumer_slot,
             },
             producer,
         ))
     }
 
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    pub fn requested_device(&self) -> Option<&str> {
+        self.requested_device.as_deref()
+    }
+
+    // True when the device this output should be on (the requested one if connected, else the
+    // default), or its preferred rate or channel count, no longer matches the open stream.
+    pub fn device_changed(&self) -> bool {
+        let Some(device) = target_device(self.requested_device.as_deref()) else {
+            return false;
+        };
+        if device.name().unwrap_or_default() != self.device_name {
+            return true;
+        }
+        device
+            .default_output_config()
+            .map(|c| c.sample_rate().0 != self.sample_rate_hz || c.channels() != self.channels)
+            .unwrap_or(false)
+    }
+
     pub fn sample_rate_hz(&self) -> u32 {
         self.sample_rate_hz
     }
@@ -76,8 +128,17 @@ impl AudioOutput {
         self.channels
     }
 
+    pub fn requested_buffer_ms(&self) -> Option<u32> {
+        self.requested_buffer_ms
+    }
+
+    // Capacity of the ring buffer in interleaved samples.
+    pub fn buffer_len(&self) -> usize {
+        self.buffer_len
+    }
+
     pub fn swap_buffer(&self) -> AudioProducer {
-        let rb = HeapRb::<f32>::new(self.sample_rate_hz as usize / 4);
+        let rb = HeapRb::<f32>::new(self.buffer_len);
         let (producer, consumer) = rb.split();
         if let Ok(mut slot) = self.consumer_slot.lock() {
             *slot = consumer;
@@ -98,6 +159,32 @@ impl AudioOutput {
     }
 }
 
+pub fn output_device_names() -> Vec<String> {
+    cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn default_buffer_frames(supported: &SupportedStreamConfig) -> usize {
+    let rate = supported.sample_rate().0 as usize;
+    let floor = rate * MIN_BUFFER_MS / 1000;
+    match supported.buffer_size() {
+        SupportedBufferSize::Range { min, .. } => (*min as usize * DEVICE_PERIODS).max(floor),
+        SupportedBufferSize::Unknown => rate * UNKNOWN_DEVICE_BUFFER_MS / 1000,
+    }
+}
+
+fn target_device(name: Option<&str>) -> Option<cpal::Device> {
+    let host = cpal::default_host();
+    name.and_then(|name| {
+        host.output_devices()
+            .ok()?
+            .find(|d| d.name().is_ok_and(|n| n == name))
+    })
+    .or_else(|| host.default_output_device())
+}
+
 pub fn enqueue_samples(producer: &mut AudioProducer, samples: &[f32], channels: u16) {
     if samples.is_empty() {
         return;
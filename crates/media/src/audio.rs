@@ -1,18 +1,379 @@
+use std::ops::Range;
 use std::path::Path;
+use std::sync::mpsc;
 
 use gstreamer as gst;
 use gstreamer::prelude::*;
 use gstreamer_app as gst_app;
 
+use crate::gst_init::{apply_downmix_matrix, wait_for_async_done};
 use crate::gst_pipeline::init_once;
+use crate::metadata::extract_metadata;
 
-pub fn extract_waveform_peaks(path: &Path, num_peaks: usize) -> Vec<(f32, f32)> {
-    if num_peaks == 0 {
-        return Vec::new();
+pub const WAVEFORM_MIP_LEVELS: [usize; 3] = [256, 1024, 4096];
+const WAVEFORM_SAMPLE_RATE: usize = 44100;
+const WAVEFORM_CHUNK_SAMPLES: usize = WAVEFORM_SAMPLE_RATE * 10;
+// Roughly -50 dBFS; anything quieter throughout is treated as a dead recording.
+const SILENCE_PEAK_THRESHOLD: f32 = 0.003;
+// Long files are split into ranges of at least this length, one decoder each.
+const WAVEFORM_MIN_RANGE_S: f64 = 30.0;
+const WAVEFORM_MAX_WORKERS: usize = 4;
+
+fn waveform_worker_count(duration: f64) -> usize {
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let ranges = (duration / WAVEFORM_MIN_RANGE_S) as usize;
+    cores.min(WAVEFORM_MAX_WORKERS).min(ranges).max(1)
+}
+
+pub fn peaks_are_silent(peaks: &[(f32, f32)]) -> bool {
+    !peaks.is_empty()
+        && peaks
+            .iter()
+            .all(|&(min, max)| min.abs().max(max.abs()) < SILENCE_PEAK_THRESHOLD)
+}
+
+// One slice of a waveform level. `total` is the expected length of the level; it is only
+// an estimate until `done`, when it is the exact peak count. `channel` is set when the
+// channels are split, and is None for the combined mono waveform.
+pub struct WaveformChunk {
+    pub level: usize,
+    pub channel: Option<usize>,
+    pub range: Range<usize>,
+    pub peaks: Vec<(f32, f32)>,
+    pub total: usize,
+    pub done: bool,
+}
+
+struct PeakAccumulator {
+    channel: Option<usize>,
+    total: usize,
+    samples_per_peak: usize,
+    next: usize,
+    current: (f32, f32),
+    count: usize,
+    pending: Vec<(f32, f32)>,
+}
+
+impl PeakAccumulator {
+    // `first_sample` is where in the file this accumulator's samples begin, so a range
+    // decoded on its own still lands its peaks at their index in the whole level.
+    fn new(
+        channel: Option<usize>,
+        num_peaks: usize,
+        total_samples: usize,
+        first_sample: usize,
+    ) -> Self {
+        let samples_per_peak = (total_samples / num_peaks).max(1);
+        Self {
+            channel,
+            total: num_peaks,
+            samples_per_peak,
+            next: first_sample / samples_per_peak,
+            current: (0.0, 0.0),
+            count: first_sample % samples_per_peak,
+            pending: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, sample: f32) {
+        if self.next >= self.total {
+            return;
+        }
+        self.current.0 = self.current.0.min(sample);
+        self.current.1 = self.current.1.max(sample);
+        self.count += 1;
+        if self.count == self.samples_per_peak {
+            self.flush_current();
+        }
+    }
+
+    fn flush_current(&mut self) {
+        self.pending.push(self.current);
+        self.current = (0.0, 0.0);
+        self.count = 0;
+        self.next += 1;
+    }
+
+    fn take_chunk(&mut self, level: usize, done: bool) -> Option<WaveformChunk> {
+        if done && self.count > 0 && self.next < self.total {
+            self.flush_current();
+        }
+        if self.pending.is_empty() && !done {
+            return None;
+        }
+        let peaks = std::mem::take(&mut self.pending);
+        let start = self.next - peaks.len();
+        Some(WaveformChunk {
+            level,
+            channel: self.channel,
+            range: start..self.next,
+            peaks,
+            total: if done { self.next } else { self.total },
+            done,
+        })
+    }
+}
+
+// Decodes once and streams every level in `WAVEFORM_MIP_LEVELS`, coarsest first, in chunks
+// as the decode progresses so long files can be drawn before they finish. Long files are
+// decoded as several ranges at once, so their chunks arrive out of order; each level
+// still ends with one final chunk. Files whose duration can't be queried are delivered as
+// a single final chunk per level. Returning false from `on_chunk` stops the decode and no
+// final chunks are sent. With `stereo` the left and right channels each get their own
+// pyramid instead of one mixed down to mono.
+pub fn stream_waveform_pyramid(
+    path: &Path,
+    stereo: bool,
+    on_chunk: impl FnMut(WaveformChunk) -> bool,
+) {
+    let duration = extract_metadata(path).duration.filter(|d| *d > 0.0);
+    let workers = duration.map(waveform_worker_count).unwrap_or(1);
+    match duration {
+        Some(duration) if workers > 1 => {
+            stream_waveform_pyramid_parallel(path, stereo, duration, workers, on_chunk)
+        }
+        _ => stream_waveform_pyramid_serial(path, stereo, on_chunk),
+    }
+}
+
+fn stream_waveform_pyramid_serial(
+    path: &Path,
+    stereo: bool,
+    mut on_chunk: impl FnMut(WaveformChunk) -> bool,
+) {
+    let channels = if stereo { 2 } else { 1 };
+    let mut levels: Option<Vec<Vec<PeakAccumulator>>> = None;
+    let mut unsized_samples = Vec::new();
+    let mut since_flush = 0usize;
+    let mut stopped = false;
+
+    decode_audio_streaming(path, channels, None, |samples, duration| {
+        if levels.is_none() {
+            match duration {
+                Some(d) if d > 0.0 => {
+                    let total_samples = (d * WAVEFORM_SAMPLE_RATE as f64).ceil() as usize;
+                    let mut accs = new_accumulators(stereo, total_samples, 0);
+                    push_frames(&mut accs, &unsized_samples);
+                    push_frames(&mut accs, samples);
+                    since_flush = (unsized_samples.len() + samples.len()) / channels;
+                    unsized_samples = Vec::new();
+                    levels = Some(accs);
+                }
+                _ => {
+                    unsized_samples.extend_from_slice(samples);
+                    return true;
+                }
+            }
+        } else if let Some(accs) = levels.as_mut() {
+            push_frames(accs, samples);
+            since_flush += samples.len() / channels;
+        }
+
+        if since_flush >= WAVEFORM_CHUNK_SAMPLES {
+            since_flush = 0;
+            if let Some(accs) = levels.as_mut() {
+                if !send_chunks(accs, false, &mut on_chunk) {
+                    stopped = true;
+                    return false;
+                }
+            }
+        }
+        true
+    });
+
+    if stopped {
+        return;
+    }
+
+    match levels {
+        Some(mut accs) => {
+            send_chunks(&mut accs, true, &mut on_chunk);
+        }
+        None if !unsized_samples.is_empty() => {
+            for channel in 0..channels {
+                let channel_samples: Vec<f32> = unsized_samples
+                    .iter()
+                    .skip(channel)
+                    .step_by(channels)
+                    .copied()
+                    .collect();
+                for (level, &num_peaks) in WAVEFORM_MIP_LEVELS.iter().enumerate() {
+                    let peaks = peaks_from_samples(&channel_samples, num_peaks);
+                    let _ = on_chunk(WaveformChunk {
+                        level,
+                        channel: stereo.then_some(channel),
+                        range: 0..peaks.len(),
+                        total: peaks.len(),
+                        peaks,
+                        done: true,
+                    });
+                }
+            }
+        }
+        None => {}
+    }
+}
+
+// Each worker decodes one range into accumulators indexed against the whole file. A peak
+// straddling two ranges is computed partly by each, so chunks are merged into a full copy
+// of every level here and forwarded from that, and the final chunk resends the level.
+fn stream_waveform_pyramid_parallel(
+    path: &Path,
+    stereo: bool,
+    duration: f64,
+    workers: usize,
+    mut on_chunk: impl FnMut(WaveformChunk) -> bool,
+) {
+    let channels = if stereo { 2 } else { 1 };
+    let total_samples = (duration * WAVEFORM_SAMPLE_RATE as f64).ceil() as usize;
+    let span = duration / workers as f64;
+    let empty_levels: Vec<Vec<(f32, f32)>> = WAVEFORM_MIP_LEVELS
+        .iter()
+        .map(|&n| vec![(0.0, 0.0); n])
+        .collect();
+    let mut merged = vec![empty_levels; channels];
+    let mut ends = vec![vec![0usize; WAVEFORM_MIP_LEVELS.len()]; channels];
+    let mut stopped = false;
+
+    std::thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel::<WaveformChunk>();
+        for i in 0..workers {
+            let range = span * i as f64..span * (i + 1) as f64;
+            let tx = tx.clone();
+            scope.spawn(move || {
+                stream_range_peaks(path, stereo, range, total_samples, |chunk| {
+                    tx.send(chunk).is_ok()
+                })
+            });
+        }
+        drop(tx);
+
+        // Dropping the receiver on a stop fails the workers' next send, which ends them.
+        for chunk in rx {
+            let channel = chunk.channel.unwrap_or(0);
+            let level = &mut merged[channel][chunk.level];
+            for (peak, &(min, max)) in level[chunk.range.clone()].iter_mut().zip(&chunk.peaks) {
+                peak.0 = peak.0.min(min);
+                peak.1 = peak.1.max(max);
+            }
+            let end = &mut ends[channel][chunk.level];
+            *end = (*end).max(chunk.range.end);
+            let forwarded = WaveformChunk {
+                peaks: level[chunk.range.clone()].to_vec(),
+                total: level.len(),
+                done: false,
+                ..chunk
+            };
+            if !on_chunk(forwarded) {
+                stopped = true;
+                break;
+            }
+        }
+    });
+
+    if stopped {
+        return;
+    }
+
+    for (channel, levels) in merged.iter().enumerate() {
+        for (level, peaks) in levels.iter().enumerate() {
+            let total = ends[channel][level];
+            let _ = on_chunk(WaveformChunk {
+                level,
+                channel: stereo.then_some(channel),
+                range: 0..total,
+                peaks: peaks[..total].to_vec(),
+                total,
+                done: true,
+            });
+        }
     }
+}
 
-    let samples = decode_all_audio_mono(path);
-    if samples.is_empty() {
+// One worker of the parallel pyramid: decodes `range` and sends its chunks as it goes,
+// then the partial peaks at both of its edges once the range is done.
+fn stream_range_peaks(
+    path: &Path,
+    stereo: bool,
+    range: Range<f64>,
+    total_samples: usize,
+    mut send: impl FnMut(WaveformChunk) -> bool,
+) {
+    let channels = if stereo { 2 } else { 1 };
+    let first_sample = (range.start * WAVEFORM_SAMPLE_RATE as f64).round() as usize;
+    let mut accs = new_accumulators(stereo, total_samples, first_sample);
+    let mut since_flush = 0usize;
+    let mut stopped = false;
+
+    decode_audio_streaming(path, channels, Some(range), |samples, _| {
+        push_frames(&mut accs, samples);
+        since_flush += samples.len() / channels;
+        if since_flush >= WAVEFORM_CHUNK_SAMPLES {
+            since_flush = 0;
+            if !send_chunks(&mut accs, false, &mut send) {
+                stopped = true;
+                return false;
+            }
+        }
+        true
+    });
+
+    if !stopped {
+        send_chunks(&mut accs, true, &mut send);
+    }
+}
+
+fn new_accumulators(
+    stereo: bool,
+    total_samples: usize,
+    first_sample: usize,
+) -> Vec<Vec<PeakAccumulator>> {
+    let channels = if stereo { 2 } else { 1 };
+    (0..channels)
+        .map(|channel| {
+            WAVEFORM_MIP_LEVELS
+                .iter()
+                .map(|&n| {
+                    PeakAccumulator::new(stereo.then_some(channel), n, total_samples, first_sample)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Hands every level's pending peaks to `on_chunk`, stopping as soon as it returns false.
+fn send_chunks(
+    accs: &mut [Vec<PeakAccumulator>],
+    done: bool,
+    on_chunk: &mut impl FnMut(WaveformChunk) -> bool,
+) -> bool {
+    for channel_accs in accs.iter_mut() {
+        for (level, acc) in channel_accs.iter_mut().enumerate() {
+            if let Some(chunk) = acc.take_chunk(level, done) {
+                if !on_chunk(chunk) {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+// `samples` are interleaved with one accumulator list per channel.
+fn push_frames(accs: &mut [Vec<PeakAccumulator>], samples: &[f32]) {
+    for frame in samples.chunks_exact(accs.len()) {
+        for (channel_accs, &sample) in accs.iter_mut().zip(frame) {
+            for acc in channel_accs.iter_mut() {
+                acc.push(sample);
+            }
+        }
+    }
+}
+
+fn peaks_from_samples(samples: &[f32], num_peaks: usize) -> Vec<(f32, f32)> {
+    if num_peaks == 0 || samples.is_empty() {
         return Vec::new();
     }
 
@@ -37,7 +398,16 @@ pub fn extract_waveform_peaks(path: &Path, num_peaks: usize) -> Vec<(f32, f32)>
     peaks
 }
 
-fn decode_all_audio_mono(path: &Path) -> Vec<f32> {
+// Hands decoded samples, interleaved over `channels`, to `on_samples` buffer by buffer,
+// along with the stream duration in seconds once the pipeline can report it. Returning
+// false ends the decode. With a `range`, the pipeline seeks to it first and samples
+// outside it are trimmed by buffer timestamp.
+fn decode_audio_streaming(
+    path: &Path,
+    channels: usize,
+    range: Option<Range<f64>>,
+    mut on_samples: impl FnMut(&[f32], Option<f64>) -> bool,
+) {
     init_once();
 
     let pipeline = gst::Pipeline::new();
@@ -47,29 +417,29 @@ fn decode_all_audio_mono(path: &Path) -> Vec<f32> {
         .build()
     {
         Ok(e) => e,
-        Err(_) => return Vec::new(),
+        Err(_) => return,
     };
 
     let decodebin = match gst::ElementFactory::make("decodebin").build() {
         Ok(e) => e,
-        Err(_) => return Vec::new(),
+        Err(_) => return,
     };
 
     let audioconvert = match gst::ElementFactory::make("audioconvert").build() {
         Ok(e) => e,
-        Err(_) => return Vec::new(),
+        Err(_) => return,
     };
 
     let audioresample = match gst::ElementFactory::make("audioresample").build() {
         Ok(e) => e,
-        Err(_) => return Vec::new(),
+        Err(_) => return,
     };
 
     let caps = gst::Caps::builder("audio/x-raw")
         .field("format", "F32LE")
-        .field("channels", 1i32)
+        .field("channels", channels as i32)
         .field("layout", "interleaved")
-        .field("rate", 44100i32)
+        .field("rate", WAVEFORM_SAMPLE_RATE as i32)
         .build();
 
     let appsink = gst_app::AppSink::builder().caps(&caps).sync(false).build();
@@ -84,11 +454,11 @@ fn decode_all_audio_mono(path: &Path) -> Vec<f32> {
         ])
         .is_err()
     {
-        return Vec::new();
+        return;
     }
 
     if gst::Element::link_many([&filesrc, &decodebin]).is_err() {
-        return Vec::new();
+        return;
     }
 
     if gst::Element::link_many([
@@ -98,7 +468,7 @@ fn decode_all_audio_mono(path: &Path) -> Vec<f32> {
     ])
     .is_err()
     {
-        return Vec::new();
+        return;
     }
 
     let audioconvert_weak = audioconvert.downgrade();
@@ -114,29 +484,67 @@ fn decode_all_audio_mono(path: &Path) -> Vec<f32> {
             if let Some(aconv) = audioconvert_weak.upgrade() {
                 let sink_pad = aconv.static_pad("sink").expect("audioconvert has sink");
                 if !sink_pad.is_linked() {
+                    apply_downmix_matrix(&aconv, &pad_caps, channels as u32);
                     let _ = src_pad.link(&sink_pad);
                 }
             }
         }
     });
 
+    if let Some(ref range) = range {
+        if seek_to_range(&pipeline, range).is_err() {
+            let _ = pipeline.set_state(gst::State::Null);
+            return;
+        }
+    }
+
     if pipeline.set_state(gst::State::Playing).is_err() {
         let _ = pipeline.set_state(gst::State::Null);
-        return Vec::new();
+        return;
     }
 
-    let mut all_samples = Vec::new();
+    let rate = WAVEFORM_SAMPLE_RATE as f64;
+    let trim = range.map(|r| {
+        (
+            (r.start * rate).round() as i64,
+            (r.end * rate).round() as i64,
+        )
+    });
+    let mut samples = Vec::new();
+    let mut duration: Option<f64> = None;
 
     loop {
         match appsink.try_pull_sample(gst::ClockTime::from_seconds(5)) {
             Some(sample) => {
+                if duration.is_none() {
+                    duration = pipeline
+                        .query_duration::<gst::ClockTime>()
+                        .map(|d| d.nseconds() as f64 / 1_000_000_000.0);
+                }
                 if let Some(buffer) = sample.buffer() {
                     if let Ok(map) = buffer.map_readable() {
                         let data = map.as_slice();
+                        samples.clear();
                         for chunk in data.chunks_exact(4) {
-                            all_samples
+                            samples
                                 .push(f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
                         }
+                        let mut window = 0..samples.len();
+                        let mut past_end = false;
+                        if let (Some((start, end)), Some(pts)) = (trim, buffer.pts()) {
+                            let first =
+                                (pts.nseconds() as f64 / 1_000_000_000.0 * rate).round() as i64;
+                            let len = (samples.len() / channels) as i64;
+                            window = (start - first).clamp(0, len) as usize * channels
+                                ..(end - first).clamp(0, len) as usize * channels;
+                            past_end = first + len >= end;
+                        }
+                        if !window.is_empty() && !on_samples(&samples[window], duration) {
+                            break;
+                        }
+                        if past_end {
+                            break;
+                        }
                     }
                 }
             }
@@ -150,5 +558,24 @@ fn decode_all_audio_mono(path: &Path) -> Vec<f32> {
     }
 
     let _ = pipeline.set_state(gst::State::Null);
-    all_samples
+}
+
+fn seek_to_range(pipeline: &gst::Pipeline, range: &Range<f64>) -> Result<(), String> {
+    pipeline
+        .set_state(gst::State::Paused)
+        .map_err(|e| format!("Failed to set Paused: {e}"))?;
+    let bus = pipeline.bus().ok_or("No bus")?;
+    let timeout = gst::ClockTime::from_seconds(10);
+    wait_for_async_done(&bus, timeout)?;
+    pipeline
+        .seek(
+            1.0,
+            gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds((range.start * 1_000_000_000.0) as u64),
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds((range.end * 1_000_000_000.0) as u64),
+        )
+        .map_err(|e| format!("Seek failed: {e}"))?;
+    wait_for_async_done(&bus, timeout)
 }
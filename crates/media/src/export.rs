@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ringbuf::traits::{Consumer, Observer, Split};
+use ringbuf::HeapRb;
+use wizard_state::clip::{Clip, ClipId};
+use wizard_state::task::BackgroundTask;
+use wizard_state::timeline::{db_to_linear, finish_mix, SourceEnvelope, Timeline, TimelineClip};
+
+use crate::gst_audio_decoder::{AudioPacing, GstAudioOnlyHandle};
+
+const WAV_HEADER_LEN: u64 = 44;
+const OFFLINE_RING_LEN: usize = 1 << 16;
+const OFFLINE_CHUNK_LEN: usize = 4096;
+const OFFLINE_STALL: Duration = Duration::from_secs(5);
+const OFFLINE_POLL: Duration = Duration::from_millis(2);
+
+// Mixes every unmuted audio track into a 16-bit WAV. Sources are decoded mono like live
+// playback and go through the live mixer's gain path (track gain, clip fades, limiter)
+// before being copied to each output channel. A clip that can't be decoded fails the whole
+// export, naming the file, so a silent or partial mix is never written. Progress is reported
+// per clip, and cancelling leaves no file behind. With a `range`, only that stretch of the
+// timeline is rendered and the file starts at its beginning.
+pub fn export_audio(
+    timeline: &Timeline,
+    clips: &HashMap<ClipId, Clip>,
+    out_path: &Path,
+    sample_rate: u32,
+    channels: u16,
+    range: Option<(f64, f64)>,
+    task: &BackgroundTask,
+) -> Result<(), String> {
+    if sample_rate == 0 || channels == 0 {
+        return Err("Invalid output format".to_string());
+    }
+
+    let duration = timeline.timeline_duration();
+    let (start, end) = match range {
+        Some((start, end)) => (start.clamp(0.0, duration), end.clamp(0.0, duration)),
+        None => (0.0, duration),
+    };
+    if range.is_some() && start >= end {
+        return Err("Export range is empty".to_string());
+    }
+
+    let total_frames = ((end - start) * sample_rate as f64).ceil() as usize;
+    let data_len = total_frames as u64 * channels as u64 * 2;
+    if data_len + WAV_HEADER_LEN > u32::MAX as u64 {
+        return Err("Timeline is too long for a WAV file".to_string());
+    }
+
+    let sources: Vec<(&Path, &TimelineClip, f32)> = timeline
+        .audio_tracks
+        .iter()
+        .filter(|track| !track.muted)
+        .map(|track| (track, db_to_linear(track.gain_db)))
+        .filter(|(_, gain)| *gain > 0.0)
+        .flat_map(|(track, gain)| {
+            track
+                .clips
+                .iter()
+                .filter(|tc| tc.enabled)
+                .filter(move |tc| {
+                    tc.timeline_start < end && tc.timeline_start + tc.duration > start
+                })
+                .filter_map(move |tc| {
+                    clips
+                        .get(&tc.source_id)
+                        .map(|clip| (clip.path.as_path(), tc, gain))
+                })
+        })
+        .collect();
+
+    let mut mix = vec![0.0f32; total_frames];
+    for (index, (path, tc, gain)) in sources.iter().enumerate() {
+        let progress = ClipProgress {
+            task,
+            base: index as f32 / sources.len() as f32,
+            span: 1.0 / sources.len() as f32,
+        };
+        let mixed = mix_clip(path, tc, *gain, sample_rate, start, &mut mix, &progress);
+        if task.is_cancelled() {
+            return Err("Export cancelled".to_string());
+        }
+        mixed.map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+
+    finish_mix(&mut mix, 1.0);
+
+    let file = File::create(out_path)
+        .map_err(|e| format!("Failed to create {}: {e}", out_path.display()))?;
+    let mut out = BufWriter::new(file);
+    write_wav(&mut out, &mix, sample_rate, channels)
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("Failed to write {}: {e}", out_path.display()))
+}
+
+// The share of the overall task one clip covers, so progress moves within long clips too.
+struct ClipProgress<'a> {
+    task: &'a BackgroundTask,
+    base: f32,
+    span: f32,
+}
+
+impl ClipProgress<'_> {
+    fn report(&self, fraction: f64) {
+        self.task
+            .set_progress(self.base + self.span * fraction.clamp(0.0, 1.0) as f32);
+    }
+}
+
+// Decodes through an offline audio-only handle, so the clip renders as fast as it decodes
+// rather than at playback speed. The handle lines buffers up by their PTS from the seek
+// point, so samples are placed sequentially from the clip's first frame in the mix, and
+// anything of the clip before `mix_start` is never decoded. A decoder that goes quiet
+// before the clip's end is an error rather than a short clip.
+fn mix_clip(
+    path: &Path,
+    tc: &TimelineClip,
+    gain: f32,
+    sample_rate: u32,
+    mix_start: f64,
+    mix: &mut [f32],
+    progress: &ClipProgress,
+) -> Result<(), String> {
+    let rate = sample_rate as f64;
+    let clip_start = ((tc.timeline_start - mix_start) * rate).round() as i64;
+    let clip_end = (((tc.timeline_start + tc.duration - mix_start) * rate).round() as i64)
+        .min(mix.len() as i64);
+    let first = clip_start.max(0);
+    if first >= clip_end {
+        return Ok(());
+    }
+
+    let (producer, mut consumer) = HeapRb::<f32>::new(OFFLINE_RING_LEN).split();
+    let seek_to = tc.source_in + (mix_start - tc.timeline_start).max(0.0);
+    let handle = GstAudioOnlyHandle::start(
+        path,
+        seek_to,
+        Arc::new(Mutex::new(producer)),
+        sample_rate,
+        1,
+        1.0,
+        AudioPacing::Offline,
+    )?;
+    handle.begin_playing()?;
+
+    let envelope = SourceEnvelope::for_clip(tc, tc.source_in, 1.0);
+    let mut chunk = vec![0.0f32; OFFLINE_CHUNK_LEN];
+    let mut frame = first;
+    let mut last_data = Instant::now();
+
+    while frame < clip_end && !progress.task.is_cancelled() {
+        let want = ((clip_end - frame) as usize).min(chunk.len());
+        let got = consumer.pop_slice(&mut chunk[..want]);
+        if got == 0 {
+            if handle.is_finished() && consumer.is_empty() {
+                break;
+            }
+            if last_data.elapsed() > OFFLINE_STALL {
+                return Err("Audio decode stalled".to_string());
+            }
+            std::thread::sleep(OFFLINE_POLL);
+            continue;
+        }
+        last_data = Instant::now();
+
+        for &value in &chunk[..got] {
+            let fade = envelope.gain((frame - clip_start) as usize, rate);
+            mix[frame as usize] += value * gain * fade;
+            frame += 1;
+        }
+        progress.report((frame - first) as f64 / (clip_end - first) as f64);
+    }
+
+    Ok(())
+}
+
+fn write_wav(
+    out: &mut impl Write,
+    mix: &[f32],
+    sample_rate: u32,
+    channels: u16,
+) -> std::io::Result<()> {
+    let block_align = channels * 2;
+    let data_len = mix.len() as u32 * block_align as u32;
+
+    out.write_all(b"RIFF")?;
+    out.write_all(&(data_len + WAV_HEADER_LEN as u32 - 8).to_le_bytes())?;
+    out.write_all(b"WAVE")?;
+    out.write_all(b"fmt ")?;
+    out.write_all(&16u32.to_le_bytes())?;
+    out.write_all(&1u16.to_le_bytes())?;
+    out.write_all(&channels.to_le_bytes())?;
+    out.write_all(&sample_rate.to_le_bytes())?;
+    out.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+    out.write_all(&block_align.to_le_bytes())?;
+    out.write_all(&16u16.to_le_bytes())?;
+    out.write_all(b"data")?;
+    out.write_all(&data_len.to_le_bytes())?;
+
+    for &sample in mix {
+        let value = (sample * i16::MAX as f32).round() as i16;
+        for _ in 0..channels {
+            out.write_all(&value.to_le_bytes())?;
+        }
+    }
+    Ok(())
+}
@@ -12,10 +12,20 @@ use gstreamer_pbutils;
 use crate::gst_init::*;
 use crate::pipeline::AudioProducer;
 
+// Realtime sources are clocked by the sink so live playback keeps pace with video. Offline
+// sources decode as fast as the consumer drains them at the requested rate, never dropping
+// samples, for renders that shouldn't take the clip's own length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioPacing {
+    Realtime,
+    Offline,
+}
+
 pub struct GstAudioOnlyHandle {
     stop_tx: Option<mpsc::Sender<()>>,
     pipeline: gst::Pipeline,
     first_frame_ready: Arc<AtomicBool>,
+    finished: Arc<AtomicBool>,
     _bridge_handle: Option<JoinHandle<()>>,
 }
 
@@ -25,9 +35,10 @@ impl GstAudioOnlyHandle {
         path: &Path,
         start_time: f64,
         audio_producer: Arc<Mutex<AudioProducer>>,
-        _sample_rate: u32,
+        sample_rate: u32,
         channels: u16,
         speed: f64,
+        pacing: AudioPacing,
     ) -> Result<Self, String> {
         prewarm_file_sync(path);
         init_once();
@@ -53,13 +64,16 @@ impl GstAudioOnlyHandle {
 
         let audioconvert = make_element("audioconvert")?;
         let audioresample = make_element("audioresample")?;
-        let audio_caps = build_audio_caps();
+        let audio_caps = match pacing {
+            AudioPacing::Realtime => build_audio_caps(),
+            AudioPacing::Offline => build_audio_caps_at_rate(sample_rate),
+        };
 
         let audio_appsink = gst_app::AppSink::builder()
             .caps(&audio_caps)
             .max_buffers(64)
             .drop(false)
-            .sync(true)
+            .sync(pacing == AudioPacing::Realtime)
             .build();
 
         pipeline
@@ -78,20 +92,24 @@ impl GstAudioOnlyHandle {
 
         connect_decodebin_audio_only(&decodebin, &audioconvert);
 
-        preroll_and_seek(&pipeline, start_time, speed)?;
+        preroll_and_seek(&pipeline, audio_appsink.upcast_ref(), start_time, speed)?;
 
         let (stop_tx, stop_rx) = mpsc::channel::<()>();
         let first_frame_ready = Arc::new(AtomicBool::new(false));
+        let finished = Arc::new(AtomicBool::new(false));
         let muted = speed < 0.99;
         let ch = channels;
+        let rate = sample_rate as f64;
 
         let bridge_handle = {
             let ffr = first_frame_ready.clone();
+            let finished = finished.clone();
             std::thread::Builder::new()
                 .name("gst-audio-only-bridge".into())
                 .spawn(move || {
                     let _ = audio_appsink.pull_preroll();
                     ffr.store(true, Ordering::Release);
+                    let mut placed: u64 = 0;
 
                     loop {
                         if stop_rx.try_recv().is_ok() {
@@ -104,6 +122,7 @@ impl GstAudioOnlyHandle {
                             Some(s) => s,
                             None => {
                                 if audio_appsink.is_eos() {
+                                    finished.store(true, Ordering::Release);
                                     return;
                                 }
                                 continue;
@@ -121,7 +140,27 @@ impl GstAudioOnlyHandle {
                             continue;
                         };
 
-                        push_audio_from_buffer(map.as_slice(), ch, &audio_producer);
+                        match pacing {
+                            AudioPacing::Realtime => {
+                                push_audio_from_buffer(map.as_slice(), ch, &audio_producer);
+                            }
+                            AudioPacing::Offline => {
+                                let pts =
+                                    buffer.pts().map(|p| p.nseconds() as f64 / 1_000_000_000.0);
+                                let samples = align_to_pts(
+                                    upmix_mono(map.as_slice(), ch),
+                                    pts,
+                                    start_time,
+                                    rate,
+                                    ch as usize,
+                                    placed,
+                                );
+                                if !push_all_samples(&samples, &audio_producer, &stop_rx) {
+                                    return;
+                                }
+                                placed += (samples.len() / ch.max(1) as usize) as u64;
+                            }
+                        }
                     }
                 })
                 .expect("failed to spawn gst audio-only bridge thread")
@@ -131,6 +170,7 @@ impl GstAudioOnlyHandle {
             stop_tx: Some(stop_tx),
             pipeline,
             first_frame_ready,
+            finished,
             _bridge_handle: Some(bridge_handle),
         })
     }
@@ -139,6 +179,11 @@ impl GstAudioOnlyHandle {
         self.first_frame_ready.load(Ordering::Acquire)
     }
 
+    // Set once the source has reached its end and everything decoded has been pushed.
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+
     pub fn begin_playing(&self) -> Result<(), String> {
         self.pipeline
             .set_state(gst::State::Playing)
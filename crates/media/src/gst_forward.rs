@@ -1,4 +1,4 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
@@ -16,6 +16,8 @@ pub struct GstPipelineHandle {
     stop_tx: Option<mpsc::Sender<()>>,
     pipeline: gst::Pipeline,
     first_frame_ready: Arc<AtomicBool>,
+    preroll_steps: Arc<AtomicU32>,
+    playing: Arc<AtomicBool>,
     _bridge_handle: Option<JoinHandle<()>>,
     _audio_bridge_handle: Option<JoinHandle<()>>,
 }
@@ -110,46 +112,36 @@ impl GstPipelineHandle {
             connect_decodebin_video_only(&decodebin, &videoconvert);
         }
 
-        preroll_and_seek(&pipeline, start_time_seconds, speed)?;
+        preroll_and_seek(
+            &pipeline,
+            video_appsink.upcast_ref(),
+            start_time_seconds,
+            speed,
+        )?;
 
         let (frame_tx, frame_rx) = mpsc::sync_channel::<DecodedFrame>(16);
         let (buf_return_tx, buf_return_rx) = mpsc::channel::<Vec<u8>>();
         let (stop_tx, stop_rx) = mpsc::channel::<()>();
         let first_frame_ready = Arc::new(AtomicBool::new(false));
+        let preroll_steps = Arc::new(AtomicU32::new(0));
+        let playing = Arc::new(AtomicBool::new(false));
 
         let bridge_handle = {
             let video_sink = video_appsink;
             let tw = target_w;
             let th = target_h;
             let ffr = first_frame_ready.clone();
+            let steps = preroll_steps.clone();
+            let playing = playing.clone();
             std::thread::Builder::new()
                 .name("gst-video-bridge".into())
                 .spawn(move || {
                     let mut buf_pool: Vec<Vec<u8>> = Vec::with_capacity(8);
-                    let expected_size = (tw as usize) * (th as usize) * 4;
 
                     if let Ok(preroll_sample) = video_sink.pull_preroll() {
-                        if let Some(buffer) = preroll_sample.buffer() {
-                            let pts_seconds = buffer
-                                .pts()
-                                .map(|p| p.nseconds() as f64 / 1_000_000_000.0)
-                                .unwrap_or(0.0);
-                            if let Ok(map) = buffer.map_readable() {
-                                let data = map.as_slice();
-                                let mut rgba_data = Vec::with_capacity(expected_size);
-                                if data.len() >= expected_size {
-                                    rgba_data.extend_from_slice(&data[..expected_size]);
-                                } else {
-                                    rgba_data.extend_from_slice(data);
-                                    rgba_data.resize(expected_size, 0);
-                                }
-                                let _ = frame_tx.send(DecodedFrame {
-                                    pts_seconds,
-                                    width: tw,
-                                    height: th,
-                                    rgba_data,
-                                });
-                            }
+                        if let Some(frame) = frame_from_sample(&preroll_sample, tw, th, Vec::new())
+                        {
+                            let _ = frame_tx.send(frame);
                         }
                     }
                     ffr.store(true, Ordering::Release);
@@ -165,6 +157,30 @@ impl GstPipelineHandle {
                             }
                         }
 
+                        // While still paused, step the sink one buffer at a time so callers can
+                        // collect frames past the preroll before the pipeline starts playing.
+                        if !playing.load(Ordering::Acquire) && steps.load(Ordering::Acquire) > 0 {
+                            steps.fetch_sub(1, Ordering::AcqRel);
+                            let _ = video_sink.send_event(gst::event::Step::new(
+                                gst::format::Buffers::ONE,
+                                1.0,
+                                true,
+                                false,
+                            ));
+                            let Some(sample) =
+                                video_sink.try_pull_preroll(gst::ClockTime::from_mseconds(50))
+                            else {
+                                continue;
+                            };
+                            let rgba_data = buf_pool.pop().unwrap_or_default();
+                            if let Some(frame) = frame_from_sample(&sample, tw, th, rgba_data) {
+                                if frame_tx.send(frame).is_err() {
+                                    return;
+                                }
+                            }
+                            continue;
+                        }
+
                         let sample =
                             match video_sink.try_pull_sample(gst::ClockTime::from_mseconds(8)) {
                                 Some(s) => s,
@@ -176,42 +192,12 @@ impl GstPipelineHandle {
                                 }
                             };
 
-                        let Some(buffer) = sample.buffer() else {
+                        let rgba_data = buf_pool.pop().unwrap_or_default();
+                        let Some(frame) = frame_from_sample(&sample, tw, th, rgba_data) else {
                             continue;
                         };
 
-                        let pts_seconds = buffer
-                            .pts()
-                            .map(|p| p.nseconds() as f64 / 1_000_000_000.0)
-                            .unwrap_or(0.0);
-
-                        let Ok(map) = buffer.map_readable() else {
-                            continue;
-                        };
-
-                        let data = map.as_slice();
-                        let mut rgba_data = buf_pool.pop().unwrap_or_default();
-                        rgba_data.clear();
-                        if rgba_data.capacity() < expected_size {
-                            rgba_data.reserve(expected_size - rgba_data.capacity());
-                        }
-
-                        if data.len() >= expected_size {
-                            rgba_data.extend_from_slice(&data[..expected_size]);
-                        } else {
-                            rgba_data.extend_from_slice(data);
-                            rgba_data.resize(expected_size, 0);
-                        }
-
-                        if frame_tx
-                            .send(DecodedFrame {
-                                pts_seconds,
-                                width: tw,
-                                height: th,
-                                rgba_data,
-                            })
-                            .is_err()
-                        {
+                        if frame_tx.send(frame).is_err() {
                             return;
                         }
                     }
@@ -264,6 +250,8 @@ impl GstPipelineHandle {
             stop_tx: Some(stop_tx),
             pipeline,
             first_frame_ready,
+            preroll_steps,
+            playing,
             _bridge_handle: Some(bridge_handle),
             _audio_bridge_handle: audio_bridge_handle,
         })
@@ -273,7 +261,16 @@ impl GstPipelineHandle {
         self.first_frame_ready.load(Ordering::Acquire)
     }
 
+    // Steps the paused pipeline so up to `count` frames past the preroll are delivered
+    // before `begin_playing`. Ignored once the pipeline is playing.
+    pub fn request_preroll_frames(&self, count: u32) {
+        if !self.playing.load(Ordering::Acquire) {
+            self.preroll_steps.fetch_add(count, Ordering::AcqRel);
+        }
+    }
+
     pub fn begin_playing(&self) -> Result<(), String> {
+        self.playing.store(true, Ordering::Release);
         self.pipeline
             .set_state(gst::State::Playing)
             .map_err(|e| format!("Failed to set Playing: {e}"))?;
@@ -318,3 +315,35 @@ impl Drop for GstPipelineHandle {
         self.signal_stop();
     }
 }
+
+fn frame_from_sample(
+    sample: &gst::Sample,
+    width: u32,
+    height: u32,
+    mut rgba_data: Vec<u8>,
+) -> Option<DecodedFrame> {
+    let buffer = sample.buffer()?;
+    let pts_seconds = buffer
+        .pts()
+        .map(|p| p.nseconds() as f64 / 1_000_000_000.0)
+        .unwrap_or(0.0);
+    let map = buffer.map_readable().ok()?;
+
+    let data = map.as_slice();
+    let expected_size = (width as usize) * (height as usize) * 4;
+    rgba_data.clear();
+    rgba_data.reserve(expected_size);
+    if data.len() >= expected_size {
+        rgba_data.extend_from_slice(&data[..expected_size]);
+    } else {
+        rgba_data.extend_from_slice(data);
+        rgba_data.resize(expected_size, 0);
+    }
+
+    Some(DecodedFrame {
+        pts_seconds,
+        width,
+        height,
+        rgba_data,
+    })
+}
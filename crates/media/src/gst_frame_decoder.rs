@@ -6,12 +6,15 @@ use gstreamer_app as gst_app;
 
 use crate::gst_init::*;
 
+const EXACT_SEEK_TOLERANCE_S: f64 = 0.001;
+
 pub struct GstFrameDecoder {
     pipeline: gst::Pipeline,
     appsink: gst_app::AppSink,
     target_w: u32,
     target_h: u32,
     last_decode_ts: Option<f64>,
+    held_frame: Option<(image::RgbaImage, f64)>,
     duration_secs: Option<f64>,
     is_playing: bool,
 }
@@ -86,6 +89,7 @@ impl GstFrameDecoder {
             target_w,
             target_h,
             last_decode_ts: None,
+            held_frame: None,
             duration_secs,
             is_playing: false,
         })
@@ -120,6 +124,7 @@ impl GstFrameDecoder {
         }
 
         self.last_decode_ts = None;
+        self.held_frame = None;
         self.pull_next_frame()
     }
 
@@ -137,9 +142,71 @@ impl GstFrameDecoder {
         }
 
         self.last_decode_ts = None;
+        self.held_frame = None;
         self.pull_next_frame()
     }
 
+    // Seeks to the keyframe at or before `time_seconds` and decodes forward, discarding
+    // frames until reaching the one on screen at `time_seconds`. Gives up after
+    // `max_frames` and returns the closest frame decoded so far.
+    pub fn seek_and_decode_exact(
+        &mut self,
+        time_seconds: f64,
+        max_frames: u32,
+    ) -> Option<image::RgbaImage> {
+        self.ensure_playing();
+
+        let seek_pos =
+            gst::ClockTime::from_nseconds((time_seconds.max(0.0) * 1_000_000_000.0) as u64);
+        if self
+            .pipeline
+            .seek_simple(
+                gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT | gst::SeekFlags::SNAP_BEFORE,
+                seek_pos,
+            )
+            .is_err()
+        {
+            return None;
+        }
+
+        self.last_decode_ts = None;
+        self.held_frame = None;
+        self.decode_until(time_seconds, max_frames)
+    }
+
+    // Decodes forward from the current position until the frame on screen at
+    // `time_seconds`, i.e. the last frame whose pts doesn't pass it.
+    pub fn decode_until(&mut self, time_seconds: f64, max_frames: u32) -> Option<image::RgbaImage> {
+        self.ensure_playing();
+
+        let mut previous: Option<(image::RgbaImage, f64)> = None;
+        for _ in 0..max_frames.max(1) {
+            let next = match self.held_frame.take() {
+                Some(held) => Some(held),
+                None => self.decode_next_frame_with_pts(),
+            };
+            let Some((img, pts)) = next else {
+                break;
+            };
+            if pts > time_seconds + EXACT_SEEK_TOLERANCE_S {
+                // Overshot: the previous frame is the one still on screen, unless this is
+                // the first frame available. The overshoot is held for the next call.
+                let Some((prev, prev_pts)) = previous else {
+                    self.last_decode_ts = Some(pts);
+                    return Some(img);
+                };
+                self.held_frame = Some((img, pts));
+                self.last_decode_ts = Some(prev_pts);
+                return Some(prev);
+            }
+            if pts >= time_seconds - EXACT_SEEK_TOLERANCE_S {
+                return Some(img);
+            }
+            previous = Some((img, pts));
+        }
+        previous.map(|(img, _)| img)
+    }
+
     pub fn decode_next_frame(&mut self) -> Option<image::RgbaImage> {
         self.ensure_playing();
         self.pull_next_frame()
@@ -147,6 +214,7 @@ impl GstFrameDecoder {
 
     pub fn decode_next_frame_with_pts(&mut self) -> Option<(image::RgbaImage, f64)> {
         self.ensure_playing();
+        self.held_frame = None;
 
         let expected_size = (self.target_w as usize) * (self.target_h as usize) * 4;
 
@@ -184,6 +252,7 @@ impl GstFrameDecoder {
             .pipeline
             .seek_simple(gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE, seek_pos);
         self.last_decode_ts = None;
+        self.held_frame = None;
     }
 
     fn pull_next_frame(&mut self) -> Option<image::RgbaImage> {
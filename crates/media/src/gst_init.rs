@@ -1,8 +1,10 @@
 use std::path::Path;
+use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 
 use gstreamer as gst;
 use gstreamer::prelude::*;
+use gstreamer_audio as gst_audio;
 use ringbuf::traits::Producer;
 
 use crate::pipeline::AudioProducer;
@@ -134,12 +136,80 @@ pub(crate) fn build_audio_caps() -> gst::Caps {
         .build()
 }
 
+pub(crate) fn build_audio_caps_at_rate(sample_rate: u32) -> gst::Caps {
+    gst::Caps::builder("audio/x-raw")
+        .field("format", "F32LE")
+        .field("channels", 1i32)
+        .field("layout", "interleaved")
+        .field("rate", sample_rate as i32)
+        .build()
+}
+
 pub(crate) fn make_element(factory_name: &str) -> Result<gst::Element, String> {
     gst::ElementFactory::make(factory_name)
         .build()
         .map_err(|e| format!("Failed to create {factory_name}: {e}"))
 }
 
+// Weights toward (left, right) for the channel at bit `position` of a GStreamer channel
+// mask, after the ITU downmix: fronts go to their own side, the centre (where dialogue
+// sits) and the surrounds come in at -3 dB, and LFE is left out.
+fn downmix_weights(position: u32) -> (f32, f32) {
+    const MINUS_3DB: f32 = std::f32::consts::FRAC_1_SQRT_2;
+    match position {
+        0 => (1.0, 0.0),
+        1 => (0.0, 1.0),
+        2 => (MINUS_3DB, MINUS_3DB),
+        3 | 9 => (0.0, 0.0),
+        4 | 6 | 10 => (MINUS_3DB, 0.0),
+        5 | 7 | 11 => (0.0, MINUS_3DB),
+        _ => (0.5, 0.5),
+    }
+}
+
+// Left to itself, audioconvert's downmix of a 5.1 or 7.1 source can bury the centre
+// dialogue, so sources with more than two channels get an explicit matrix for mono or
+// stereo output. A missing channel mask is taken to be the usual layout for the count.
+pub(crate) fn apply_downmix_matrix(audioconvert: &gst::Element, caps: &gst::CapsRef, out: u32) {
+    let Some(structure) = caps.structure(0) else {
+        return;
+    };
+    let Ok(channels) = structure.get::<i32>("channels") else {
+        return;
+    };
+    let channels = channels.max(0) as u32;
+    if channels <= 2 || !(1..=2).contains(&out) {
+        return;
+    }
+    let mask = structure
+        .get::<gst::Bitmask>("channel-mask")
+        .map(|mask| mask.0)
+        .unwrap_or_else(|_| gst_audio::AudioChannelPosition::fallback_mask(channels));
+    let weights: Vec<(f32, f32)> = (0..64)
+        .filter(|bit| mask & (1u64 << bit) != 0)
+        .map(downmix_weights)
+        .collect();
+    if weights.len() != channels as usize {
+        return;
+    }
+    let rows: Vec<Vec<f32>> = if out == 1 {
+        vec![weights.iter().map(|(l, r)| l + r).collect()]
+    } else {
+        vec![
+            weights.iter().map(|(l, _)| *l).collect(),
+            weights.iter().map(|(_, r)| *r).collect(),
+        ]
+    };
+    // Each output is held to unity total gain so a loud surround mix can't clip.
+    let matrix = gst::Array::from_values(rows.into_iter().map(|row| {
+        let total: f32 = row.iter().sum();
+        let scale = if total > 1.0 { 1.0 / total } else { 1.0 };
+        gst::Array::from_values(row.into_iter().map(|w| (w * scale).to_send_value()))
+            .to_send_value()
+    }));
+    audioconvert.set_property("mix-matrix", matrix);
+}
+
 pub(crate) fn connect_decodebin_video_only(decodebin: &gst::Element, videoconvert: &gst::Element) {
     let videoconvert_weak = videoconvert.downgrade();
     decodebin.connect_pad_added(move |_dbin, src_pad| {
@@ -175,6 +245,7 @@ pub(crate) fn connect_decodebin_audio_only(decodebin: &gst::Element, audioconver
             if let Some(aconv) = audioconvert_weak.upgrade() {
                 let sink_pad = aconv.static_pad("sink").expect("audioconvert has sink");
                 if !sink_pad.is_linked() {
+                    apply_downmix_matrix(&aconv, &caps, 1);
                     let _ = src_pad.link(&sink_pad);
                 }
             }
@@ -210,6 +281,7 @@ pub(crate) fn connect_decodebin_video_and_audio(
             if let Some(aconv) = audioconvert_weak.upgrade() {
                 let sink_pad = aconv.static_pad("sink").expect("audioconvert has sink");
                 if !sink_pad.is_linked() {
+                    apply_downmix_matrix(&aconv, &caps, 1);
                     let _ = src_pad.link(&sink_pad);
                 }
             }
@@ -217,11 +289,39 @@ pub(crate) fn connect_decodebin_video_and_audio(
     });
 }
 
+const OFFLINE_PUSH_RETRY_MS: u64 = 2;
+
+// Some muxers hand back a buffer well past the requested time when prerolling or
+// seeking; anything later than this is treated as a bad landing.
+const START_PTS_TOLERANCE_S: f64 = 0.5;
+
 pub(crate) fn preroll_and_seek(
     pipeline: &gst::Pipeline,
+    sink: &gst::Element,
     start_time_seconds: f64,
     speed: f64,
 ) -> Result<(), String> {
+    let last_pts: Arc<Mutex<Option<f64>>> = Arc::new(Mutex::new(None));
+    let sink_pad = sink.static_pad("sink");
+    let probe = sink_pad.as_ref().and_then(|pad| {
+        let last_pts = last_pts.clone();
+        pad.add_probe(gst::PadProbeType::BUFFER, move |_, info| {
+            if let Some(pts) = info.buffer().and_then(|b| b.pts()) {
+                if let Ok(mut guard) = last_pts.lock() {
+                    *guard = Some(pts.nseconds() as f64 / 1_000_000_000.0);
+                }
+            }
+            gst::PadProbeReturn::Ok
+        })
+    });
+    let landed_late = || {
+        last_pts
+            .lock()
+            .ok()
+            .and_then(|guard| *guard)
+            .is_some_and(|pts| pts > start_time_seconds.max(0.0) + START_PTS_TOLERANCE_S)
+    };
+
     pipeline
         .set_state(gst::State::Paused)
         .map_err(|e| format!("Failed to set Paused: {e}"))?;
@@ -230,7 +330,12 @@ pub(crate) fn preroll_and_seek(
     let timeout = gst::ClockTime::from_seconds(5);
     wait_for_async_done(&bus, timeout).map_err(|e| format!("Preroll error: {e}"))?;
 
+    // Seeking to 0 can land after the first keyframe on some muxers, so near-zero starts
+    // take the natural preroll and only seek if it came back late.
     if start_time_seconds > 0.01 {
+        if let Ok(mut guard) = last_pts.lock() {
+            *guard = None;
+        }
         let seek_pos = gst::ClockTime::from_nseconds((start_time_seconds * 1_000_000_000.0) as u64);
         pipeline
             .seek_simple(gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE, seek_pos)
@@ -238,6 +343,22 @@ pub(crate) fn preroll_and_seek(
         wait_for_async_done(&bus, timeout).map_err(|e| format!("Seek error: {e}"))?;
     }
 
+    if landed_late() {
+        let seek_pos =
+            gst::ClockTime::from_nseconds((start_time_seconds.max(0.0) * 1_000_000_000.0) as u64);
+        pipeline
+            .seek_simple(
+                gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT | gst::SeekFlags::SNAP_BEFORE,
+                seek_pos,
+            )
+            .map_err(|e| format!("Fallback seek failed: {e}"))?;
+        wait_for_async_done(&bus, timeout).map_err(|e| format!("Fallback seek error: {e}"))?;
+    }
+
+    if let (Some(pad), Some(probe)) = (sink_pad, probe) {
+        pad.remove_probe(probe);
+    }
+
     if (speed - 1.0).abs() > 0.01 {
         let cur_pos: Option<gst::ClockTime> = pipeline.query_position();
         let pos = cur_pos.unwrap_or(gst::ClockTime::ZERO);
@@ -256,6 +377,61 @@ pub(crate) fn preroll_and_seek(
 }
 
 pub(crate) fn push_audio_from_buffer(data: &[u8], ch: u16, producer: &Arc<Mutex<AudioProducer>>) {
+    let buf = upmix_mono(data, ch);
+    if let Ok(mut guard) = producer.lock() {
+        guard.push_slice(&buf);
+    }
+}
+
+// Blocks until every sample is in the ring instead of dropping what doesn't fit. Returns
+// false if a stop arrives first.
+pub(crate) fn push_all_samples(
+    samples: &[f32],
+    producer: &Arc<Mutex<AudioProducer>>,
+    stop_rx: &mpsc::Receiver<()>,
+) -> bool {
+    let mut written = 0;
+    while written < samples.len() {
+        if let Ok(mut guard) = producer.lock() {
+            written += guard.push_slice(&samples[written..]);
+        }
+        if written < samples.len() {
+            if stop_rx.try_recv().is_ok() {
+                return false;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(OFFLINE_PUSH_RETRY_MS));
+        }
+    }
+    true
+}
+
+// Lines a buffer up with the frame its PTS puts it at, given `placed` frames already
+// pushed since `start_time`: a seek that lands early is trimmed and a gap in the source
+// is filled with silence, so nothing after it drifts.
+pub(crate) fn align_to_pts(
+    samples: Vec<f32>,
+    pts: Option<f64>,
+    start_time: f64,
+    rate: f64,
+    ch: usize,
+    placed: u64,
+) -> Vec<f32> {
+    let Some(pts) = pts else {
+        return samples;
+    };
+    let frame = ((pts - start_time) * rate).round() as i64;
+    let placed = placed as i64;
+    if frame > placed {
+        let mut out = vec![0.0; (frame - placed) as usize * ch];
+        out.extend(samples);
+        out
+    } else {
+        let skip = ((placed - frame) as usize * ch).min(samples.len());
+        samples[skip..].to_vec()
+    }
+}
+
+pub(crate) fn upmix_mono(data: &[u8], ch: u16) -> Vec<f32> {
     let sample_count = data.len() / 4;
     let ch_out = ch as usize;
     let mut buf = Vec::with_capacity(sample_count * ch_out);
@@ -275,8 +451,5 @@ pub(crate) fn push_audio_from_buffer(data: &[u8], ch: u16, producer: &Arc<Mutex<
             buf.push(sample);
         }
     }
-
-    if let Ok(mut guard) = producer.lock() {
-        guard.push_slice(&buf);
-    }
+    buf
 }
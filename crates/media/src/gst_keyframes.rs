@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+
+use crate::gst_init::*;
+
+const SCAN_PULL_TIMEOUT_S: u64 = 5;
+
+pub struct KeyframeIndex {
+    times: Vec<f64>,
+}
+
+impl KeyframeIndex {
+    // The start of the GOP that ends at `end`, i.e. the last keyframe strictly before it.
+    pub fn gop_start_before(&self, end: f64) -> Option<f64> {
+        let idx = self.times.partition_point(|&t| t < end - 0.001);
+        idx.checked_sub(1).map(|i| self.times[i])
+    }
+
+    // The keyframe to start a window ending at `end` from: walks back over short GOPs until
+    // the window spans at least `min_span`, stopping at the first keyframe.
+    pub fn window_start_before(&self, end: f64, min_span: f64) -> Option<f64> {
+        let idx = self.times.partition_point(|&t| t <= end - min_span);
+        match idx.checked_sub(1) {
+            Some(i) => Some(self.times[i]),
+            None => self.gop_start_before(end).map(|_| self.times[0]),
+        }
+    }
+}
+
+enum IndexEntry {
+    Scanning,
+    Ready(Arc<KeyframeIndex>),
+    Failed,
+}
+
+fn index_cache() -> &'static Mutex<HashMap<PathBuf, IndexEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, IndexEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// Returns the cached index for `path`, kicking off a background scan the first time a
+// path is seen. Callers fall back to fixed windows until the scan lands.
+pub fn keyframe_index(path: &Path) -> Option<Arc<KeyframeIndex>> {
+    let mut cache = index_cache().lock().ok()?;
+    match cache.get(path) {
+        Some(IndexEntry::Ready(index)) => return Some(index.clone()),
+        Some(IndexEntry::Scanning | IndexEntry::Failed) => return None,
+        None => {}
+    }
+    cache.insert(path.to_path_buf(), IndexEntry::Scanning);
+    drop(cache);
+
+    let path = path.to_path_buf();
+    std::thread::Builder::new()
+        .name("keyframe-scan".into())
+        .spawn(move || {
+            let entry = match scan_keyframes(&path) {
+                Ok(times) if !times.is_empty() => {
+                    IndexEntry::Ready(Arc::new(KeyframeIndex { times }))
+                }
+                _ => IndexEntry::Failed,
+            };
+            if let Ok(mut cache) = index_cache().lock() {
+                cache.insert(path, entry);
+            }
+        })
+        .ok();
+    None
+}
+
+fn scan_keyframes(path: &Path) -> Result<Vec<f64>, String> {
+    init_once();
+
+    let pipeline = gst::Pipeline::new();
+    let filesrc = gst::ElementFactory::make("filesrc")
+        .property("location", path.to_str().unwrap_or_default())
+        .build()
+        .map_err(|e| format!("Failed to create filesrc: {e}"))?;
+    let parsebin = make_element("parsebin")?;
+    let appsink = gst_app::AppSink::builder().sync(false).build();
+
+    pipeline
+        .add_many([&filesrc, &parsebin, appsink.upcast_ref::<gst::Element>()])
+        .map_err(|e| format!("Failed to add elements: {e}"))?;
+    gst::Element::link_many([&filesrc, &parsebin])
+        .map_err(|e| format!("Failed to link filesrc->parsebin: {e}"))?;
+
+    let sink_weak = appsink.downgrade();
+    parsebin.connect_pad_added(move |_pbin, src_pad| {
+        let caps = match src_pad.current_caps() {
+            Some(c) => c,
+            None => src_pad.query_caps(None),
+        };
+        let Some(structure) = caps.structure(0) else {
+            return;
+        };
+        if structure.name().as_str().starts_with("video/") {
+            if let Some(sink) = sink_weak.upgrade() {
+                let sink_pad = sink.static_pad("sink").expect("appsink has sink");
+                if !sink_pad.is_linked() {
+                    let _ = src_pad.link(&sink_pad);
+                }
+            }
+        }
+    });
+
+    pipeline
+        .set_state(gst::State::Playing)
+        .map_err(|e| format!("Failed to set Playing: {e}"))?;
+
+    let mut times = Vec::new();
+    while let Some(sample) =
+        appsink.try_pull_sample(gst::ClockTime::from_seconds(SCAN_PULL_TIMEOUT_S))
+    {
+        let Some(buffer) = sample.buffer() else {
+            continue;
+        };
+        if buffer.flags().contains(gst::BufferFlags::DELTA_UNIT) {
+            continue;
+        }
+        if let Some(pts) = buffer.pts() {
+            times.push(pts.nseconds() as f64 / 1_000_000_000.0);
+        }
+    }
+    let _ = pipeline.set_state(gst::State::Null);
+
+    times.sort_by(|a, b| a.total_cmp(b));
+    times.dedup();
+    Ok(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_gops_are_grouped_back_to_the_minimum_window() {
+        let all_intra = KeyframeIndex {
+            times: (0..300).map(|i| i as f64 / 30.0).collect(),
+        };
+        let start = all_intra.window_start_before(5.0, 1.0).unwrap();
+        assert!((start - 4.0).abs() < 1e-9);
+        assert_eq!(all_intra.window_start_before(0.5, 1.0), Some(0.0));
+        assert_eq!(all_intra.window_start_before(0.0, 1.0), None);
+
+        let long_gops = KeyframeIndex {
+            times: vec![0.0, 2.0, 4.0],
+        };
+        assert_eq!(long_gops.window_start_before(5.0, 1.0), Some(4.0));
+        assert_eq!(long_gops.window_start_before(4.5, 1.0), Some(2.0));
+    }
+}
@@ -6,51 +6,85 @@ use std::time::Instant;
 
 use crate::gst_frame_decoder::GstFrameDecoder;
 use crate::gst_init::init_once;
+use crate::gst_keyframes::keyframe_index;
 use crate::pipeline::DecodedFrame;
 
 const REVERSE_GOP_WINDOW: f64 = 4.0;
-
+const REVERSE_MIN_WINDOW: f64 = REVERSE_GOP_WINDOW / 4.0;
+// Further behind than this and the clock gives up the rest of the lag instead of flushing
+// frames to catch up.
+const PACER_MAX_LAG_S: f64 = 0.25;
+// The longest one frame is held, so a gap in the stream or a very slow speed still moves.
+const PACER_MAX_WAIT_S: f64 = 1.0;
+// Sleeps are sliced so speed changes land within a tick even between sparse frames.
+const PACER_MAX_SLEEP: std::time::Duration = std::time::Duration::from_millis(50);
+
+// Times are seconds since `epoch`, so the pacing maths can be driven by a synthetic clock.
 struct ReverseStreamClock {
-    start_time: Instant,
+    epoch: Instant,
+    start_time: f64,
     start_pts: Option<f64>,
+    last_pts: Option<f64>,
     speed: f64,
 }
 
 impl ReverseStreamClock {
     fn new(speed: f64) -> Self {
         Self {
-            start_time: Instant::now(),
+            epoch: Instant::now(),
+            start_time: 0.0,
             start_pts: None,
+            last_pts: None,
             speed: speed.max(0.01),
         }
     }
 
+    fn now(&self) -> f64 {
+        self.epoch.elapsed().as_secs_f64()
+    }
+
     fn reset(&mut self, pts: f64) {
-        self.start_time = Instant::now();
+        self.reset_at(pts, self.now());
+    }
+
+    fn reset_at(&mut self, pts: f64, now: f64) {
+        self.start_time = now;
         self.start_pts = Some(pts);
+        self.last_pts = Some(pts);
     }
 
     fn set_speed(&mut self, new_speed: f64) {
         if let Some(start_pts) = self.start_pts {
-            let elapsed = self.start_time.elapsed().as_secs_f64();
-            let current_pts = start_pts + elapsed * self.speed;
-            self.start_pts = Some(current_pts);
-            self.start_time = Instant::now();
+            let now = self.now();
+            self.start_pts = Some(start_pts + (now - self.start_time) * self.speed);
+            self.start_time = now;
         }
         self.speed = new_speed.max(0.01);
     }
 
     fn delay(&mut self, pts_seconds: f64) -> std::time::Duration {
-        let start_pts = *self.start_pts.get_or_insert(pts_seconds);
-        let elapsed = self.start_time.elapsed().as_secs_f64();
-        let target = (pts_seconds - start_pts) / self.speed;
-        let diff = target - elapsed;
-        if diff > 0.001 {
-            std::time::Duration::from_secs_f64(diff)
+        let wait = self.wait_at(pts_seconds, self.now());
+        if wait > 0.001 {
+            std::time::Duration::from_secs_f64(wait)
         } else {
             std::time::Duration::ZERO
         }
     }
+
+    // Only a jump back in the stream re-anchors the clock. A wait outside the lag and wait
+    // bounds is clamped by sliding the anchor instead, so every frame stays paced.
+    fn wait_at(&mut self, pts_seconds: f64, now: f64) -> f64 {
+        if self.last_pts.is_some_and(|last| pts_seconds < last - 0.001) {
+            self.reset_at(pts_seconds, now);
+        }
+        self.last_pts = Some(pts_seconds);
+        let start_pts = *self.start_pts.get_or_insert(pts_seconds);
+        let target = (pts_seconds - start_pts) / self.speed;
+        let diff = target - (now - self.start_time);
+        let wait = diff.clamp(-PACER_MAX_LAG_S, PACER_MAX_WAIT_S);
+        self.start_pts = Some(start_pts + (diff - wait) * self.speed);
+        wait
+    }
 }
 
 fn decode_gop_range_with(
@@ -100,6 +134,7 @@ fn decode_gop_range_with(
 pub struct GstReversePipelineHandle {
     frame_rx: mpsc::Receiver<DecodedFrame>,
     stop_tx: Option<mpsc::Sender<()>>,
+    pacer_stop_tx: Option<mpsc::Sender<()>>,
     speed_tx: mpsc::Sender<f64>,
     first_frame_ready: Arc<AtomicBool>,
     _decode_handle: Option<JoinHandle<()>>,
@@ -113,13 +148,16 @@ impl GstReversePipelineHandle {
         speed: f64,
         target_w: u32,
         target_h: u32,
+        buffer_frames: usize,
     ) -> Result<Self, String> {
         init_once();
 
         let path_owned = path.to_path_buf();
         let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let (pacer_stop_tx, pacer_stop_rx) = mpsc::channel::<()>();
         let (speed_tx, speed_rx) = mpsc::channel::<f64>();
-        let (intermediate_tx, intermediate_rx) = mpsc::sync_channel::<DecodedFrame>(8);
+        let (intermediate_tx, intermediate_rx) =
+            mpsc::sync_channel::<DecodedFrame>(buffer_frames.max(1));
         let (frame_tx, frame_rx) = mpsc::sync_channel::<DecodedFrame>(4);
         let first_frame_ready = Arc::new(AtomicBool::new(false));
 
@@ -137,7 +175,14 @@ impl GstReversePipelineHandle {
                         return;
                     }
 
-                    let gop_start = (current_end - REVERSE_GOP_WINDOW).max(0.0);
+                    // Snap the window to real GOPs once the keyframe scan has landed, so each
+                    // seek starts on a keyframe. Short GOPs are grouped up to a minimum span so
+                    // all-intra sources don't seek once per frame.
+                    let gop_start = keyframe_index(&path_owned)
+                        .and_then(|index| {
+                            index.window_start_before(current_end, REVERSE_MIN_WINDOW)
+                        })
+                        .unwrap_or_else(|| (current_end - REVERSE_GOP_WINDOW).max(0.0));
                     let mut frames = decode_gop_range_with(
                         &mut decoder,
                         gop_start,
@@ -190,9 +235,18 @@ impl GstReversePipelineHandle {
                     }
 
                     let distance = (gop_base_pts - frame.pts_seconds).abs();
-                    let delay = clock.delay(distance);
-                    if !delay.is_zero() {
-                        std::thread::sleep(delay);
+                    let mut delay = clock.delay(distance);
+                    while !delay.is_zero() {
+                        if let Ok(_) | Err(mpsc::TryRecvError::Disconnected) =
+                            pacer_stop_rx.try_recv()
+                        {
+                            return;
+                        }
+                        std::thread::sleep(delay.min(PACER_MAX_SLEEP));
+                        while let Ok(s) = speed_rx.try_recv() {
+                            clock.set_speed(s);
+                        }
+                        delay = clock.delay(distance);
                     }
 
                     last_pts = Some(frame.pts_seconds);
@@ -212,6 +266,7 @@ impl GstReversePipelineHandle {
         Ok(Self {
             frame_rx,
             stop_tx: Some(stop_tx),
+            pacer_stop_tx: Some(pacer_stop_tx),
             speed_tx,
             first_frame_ready,
             _decode_handle: Some(decode_handle),
@@ -236,7 +291,10 @@ impl GstReversePipelineHandle {
     }
 
     fn signal_stop(&mut self) {
-        if let Some(tx) = self.stop_tx.take() {
+        for tx in [self.stop_tx.take(), self.pacer_stop_tx.take()]
+            .into_iter()
+            .flatten()
+        {
             let _ = tx.send(());
         }
     }
@@ -247,3 +305,67 @@ impl Drop for GstReversePipelineHandle {
         self.signal_stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Feeds `pts` to a clock whose frames are shown the moment their wait ends, returning
+    // each wait.
+    fn waits(clock: &mut ReverseStreamClock, now: &mut f64, pts: &[f64]) -> Vec<f64> {
+        pts.iter()
+            .map(|&p| {
+                let wait = clock.wait_at(p, *now);
+                *now += wait.max(0.0);
+                wait
+            })
+            .collect()
+    }
+
+    fn assert_waits(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn irregular_pts_are_paced_and_a_gap_is_held_for_at_most_the_max_wait() {
+        let mut clock = ReverseStreamClock::new(1.0);
+        let mut now = 0.0;
+        let w = waits(&mut clock, &mut now, &[0.0, 0.04, 0.05, 0.2, 2.5, 2.54]);
+        assert_waits(&w, &[0.0, 0.04, 0.01, 0.15, PACER_MAX_WAIT_S, 0.04]);
+    }
+
+    #[test]
+    fn slow_speeds_hold_every_frame_rather_than_none() {
+        let mut clock = ReverseStreamClock::new(0.01);
+        let mut now = 0.0;
+        let w = waits(&mut clock, &mut now, &[0.0, 1.0 / 30.0, 2.0 / 30.0]);
+        assert_waits(&w, &[0.0, PACER_MAX_WAIT_S, PACER_MAX_WAIT_S]);
+
+        let mut clock = ReverseStreamClock::new(0.1);
+        let mut now = 0.0;
+        let w = waits(&mut clock, &mut now, &[0.0, 0.04, 0.08]);
+        assert_waits(&w, &[0.0, 0.4, 0.4]);
+    }
+
+    #[test]
+    fn a_stall_is_caught_up_by_at_most_the_max_lag() {
+        let mut clock = ReverseStreamClock::new(1.0);
+        let mut now = 0.0;
+        waits(&mut clock, &mut now, &[0.0, 0.04]);
+        now += 2.0;
+        let w = waits(&mut clock, &mut now, &[0.08, 0.12, 0.5]);
+        assert_waits(&w, &[-PACER_MAX_LAG_S, -0.21, 0.17]);
+    }
+
+    #[test]
+    fn only_a_backward_jump_re_anchors() {
+        let mut clock = ReverseStreamClock::new(1.0);
+        let mut now = 0.0;
+        waits(&mut clock, &mut now, &[0.0, 0.04, 0.08]);
+        let w = waits(&mut clock, &mut now, &[0.0, 0.04]);
+        assert_waits(&w, &[0.0, 0.04]);
+    }
+}
@@ -1,16 +1,19 @@
 pub mod audio;
+pub mod export;
 pub mod gst_audio_decoder;
 pub mod gst_forward;
 pub mod gst_frame_decoder;
 pub mod gst_init;
+pub mod gst_keyframes;
 pub mod gst_reverse;
 pub mod import;
 pub mod metadata;
 pub mod pipeline;
 pub mod thumbnail;
+mod thumbnail_cache;
 
 pub mod gst_pipeline {
-    pub use crate::gst_audio_decoder::{GstAudioDecoder, GstAudioOnlyHandle};
+    pub use crate::gst_audio_decoder::{AudioPacing, GstAudioDecoder, GstAudioOnlyHandle};
     pub use crate::gst_forward::GstPipelineHandle;
     pub use crate::gst_frame_decoder::GstFrameDecoder;
     pub use crate::gst_init::{init_once, prewarm_file};
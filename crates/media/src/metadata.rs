@@ -1,3 +1,5 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
 use gst_pbutils::prelude::DiscovererStreamInfoExt;
@@ -9,8 +11,11 @@ use crate::gst_pipeline::init_once;
 pub struct MediaMetadata {
     pub duration: Option<f64>,
     pub resolution: Option<(u32, u32)>,
+    pub fps: Option<f64>,
     pub codec: Option<String>,
     pub has_video: bool,
+    pub has_audio: bool,
+    pub audio_channels: Option<u32>,
 }
 
 pub fn extract_metadata(path: &Path) -> MediaMetadata {
@@ -22,8 +27,11 @@ pub fn extract_metadata(path: &Path) -> MediaMetadata {
             return MediaMetadata {
                 duration: None,
                 resolution: None,
+                fps: None,
                 codec: None,
                 has_video: false,
+                has_audio: false,
+                audio_channels: None,
             };
         }
     };
@@ -34,8 +42,11 @@ pub fn extract_metadata(path: &Path) -> MediaMetadata {
             return MediaMetadata {
                 duration: None,
                 resolution: None,
+                fps: None,
                 codec: None,
                 has_video: false,
+                has_audio: false,
+                audio_channels: None,
             };
         }
     };
@@ -46,8 +57,11 @@ pub fn extract_metadata(path: &Path) -> MediaMetadata {
             return MediaMetadata {
                 duration: None,
                 resolution: None,
+                fps: None,
                 codec: None,
                 has_video: false,
+                has_audio: false,
+                audio_channels: None,
             };
         }
     };
@@ -57,6 +71,7 @@ pub fn extract_metadata(path: &Path) -> MediaMetadata {
         .map(|d| d.nseconds() as f64 / 1_000_000_000.0);
 
     let mut resolution = None;
+    let mut fps = None;
     let mut codec = None;
     let mut has_video = false;
 
@@ -67,6 +82,11 @@ pub fn extract_metadata(path: &Path) -> MediaMetadata {
         if w > 0 && h > 0 {
             resolution = Some((w, h));
         }
+        // Still images and some variable-rate streams report 0/1.
+        let rate = stream.framerate();
+        if rate.numer() > 0 && rate.denom() > 0 {
+            fps = Some(rate.numer() as f64 / rate.denom() as f64);
+        }
         if let Some(caps) = DiscovererStreamInfoExt::caps(&stream) {
             if let Some(structure) = caps.structure(0) {
                 codec = Some(structure.name().as_str().to_string());
@@ -74,12 +94,81 @@ pub fn extract_metadata(path: &Path) -> MediaMetadata {
         }
     }
 
+    let audio_streams = info.audio_streams();
+    let audio_channels = audio_streams
+        .first()
+        .map(|stream| stream.channels())
+        .filter(|&channels| channels > 0);
+
     MediaMetadata {
         duration,
         resolution,
+        fps,
         codec,
         has_video,
+        has_audio: !audio_streams.is_empty(),
+        audio_channels,
+    }
+}
+
+// A cheap duration read for MP4/MOV files: only the movie header, found by walking the box
+// tree, instead of a full discovery. Other containers return None and wait for
+// extract_metadata, which also replaces this estimate once it's done.
+pub fn probe_duration(path: &Path) -> Option<f64> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let (moov_start, moov_end) = find_box(&mut file, 0, len, b"moov")?;
+    let (mvhd_start, _) = find_box(&mut file, moov_start, moov_end, b"mvhd")?;
+    file.seek(SeekFrom::Start(mvhd_start)).ok()?;
+    let mut version = [0u8; 4];
+    file.read_exact(&mut version).ok()?;
+    // Creation and modification times come first, 32 or 64 bits each depending on version.
+    let (timescale, duration) = if version[0] == 1 {
+        let mut buf = [0u8; 28];
+        file.read_exact(&mut buf).ok()?;
+        let timescale = u32::from_be_bytes(buf[16..20].try_into().ok()?);
+        let duration = u64::from_be_bytes(buf[20..28].try_into().ok()?);
+        (timescale, duration)
+    } else {
+        let mut buf = [0u8; 16];
+        file.read_exact(&mut buf).ok()?;
+        let timescale = u32::from_be_bytes(buf[8..12].try_into().ok()?);
+        let duration = match u32::from_be_bytes(buf[12..16].try_into().ok()?) {
+            u32::MAX => u64::MAX,
+            duration => duration as u64,
+        };
+        (timescale, duration)
+    };
+    // All ones means the duration is unknown, as with fragmented files.
+    (timescale > 0 && duration > 0 && duration != u64::MAX)
+        .then(|| duration as f64 / timescale as f64)
+}
+
+// The payload range of the first box of type `kind` between `start` and `end`.
+fn find_box(file: &mut File, start: u64, end: u64, kind: &[u8; 4]) -> Option<(u64, u64)> {
+    let mut pos = start;
+    while pos + 8 <= end {
+        file.seek(SeekFrom::Start(pos)).ok()?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header).ok()?;
+        let (header_len, size) = match u32::from_be_bytes(header[0..4].try_into().ok()?) {
+            0 => (8, end - pos),
+            1 => {
+                let mut large = [0u8; 8];
+                file.read_exact(&mut large).ok()?;
+                (16, u64::from_be_bytes(large))
+            }
+            size => (8, size as u64),
+        };
+        if size < header_len {
+            return None;
+        }
+        if &header[4..8] == kind {
+            return Some((pos + header_len, pos.saturating_add(size).min(end)));
+        }
+        pos = pos.saturating_add(size);
     }
+    None
 }
 
 fn url_from_path(path: &Path) -> Option<String> {
@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use crate::gst_pipeline::GstFrameDecoder;
+use crate::thumbnail_cache;
 
 const THUMB_WIDTH: u32 = 480;
 const THUMB_HEIGHT: u32 = 270;
@@ -8,10 +9,14 @@ const THUMB_HEIGHT: u32 = 270;
 const PREVIEW_WIDTH: u32 = 240;
 const PREVIEW_HEIGHT: u32 = 135;
 
-fn is_mostly_black(img: &image::RgbaImage) -> bool {
+// Opening frames are treated as black only if every one of these decodes fully black.
+const OPENING_FRAME_TIMES: [f64; 3] = [0.0, 0.25, 0.5];
+
+// Returns (dark, checked) over a sparse sample of pixels.
+fn dark_pixel_counts(img: &image::RgbaImage) -> (usize, usize) {
     let total = img.width() as usize * img.height() as usize;
     if total == 0 {
-        return true;
+        return (0, 0);
     }
     let pixels = img.as_raw();
     let step = (total / 200).max(1);
@@ -30,11 +35,23 @@ fn is_mostly_black(img: &image::RgbaImage) -> bool {
         }
         checked += 1;
     }
+    (dark_count, checked)
+}
+
+fn is_mostly_black(img: &image::RgbaImage) -> bool {
+    if img.width() == 0 || img.height() == 0 {
+        return true;
+    }
+    let (dark_count, checked) = dark_pixel_counts(img);
     checked > 0 && dark_count * 100 / checked > 90
 }
 
-pub fn extract_thumbnail(path: &Path) -> Option<image::RgbaImage> {
-    let mut decoder = GstFrameDecoder::open(path, THUMB_WIDTH, THUMB_HEIGHT).ok()?;
+fn is_fully_black(img: &image::RgbaImage) -> bool {
+    let (dark_count, checked) = dark_pixel_counts(img);
+    checked > 0 && dark_count == checked
+}
+
+fn find_thumbnail(decoder: &mut GstFrameDecoder) -> Option<image::RgbaImage> {
     let times = [0.5, 1.0, 2.0, 0.0, 0.04, 0.25, 5.0];
     for &t in &times {
         if let Some(img) = decoder.seek_and_decode(t) {
@@ -46,6 +63,54 @@ pub fn extract_thumbnail(path: &Path) -> Option<image::RgbaImage> {
     decoder.seek_and_decode(1.0)
 }
 
+fn opening_frames_black_with(decoder: &mut GstFrameDecoder) -> bool {
+    let mut decoded = 0;
+    for &t in &OPENING_FRAME_TIMES {
+        if let Some(img) = decoder.seek_and_decode(t) {
+            if !is_fully_black(&img) {
+                return false;
+            }
+            decoded += 1;
+        }
+    }
+    decoded > 0
+}
+
+fn find_and_cache_thumbnail(
+    path: &Path,
+    decoder: &mut GstFrameDecoder,
+) -> Option<image::RgbaImage> {
+    let img = find_thumbnail(decoder)?;
+    thumbnail_cache::store(path, &img);
+    Some(img)
+}
+
+pub fn extract_thumbnail(path: &Path) -> Option<image::RgbaImage> {
+    if let Some(img) = thumbnail_cache::load(path) {
+        return Some(img);
+    }
+    let mut decoder = GstFrameDecoder::open(path, THUMB_WIDTH, THUMB_HEIGHT).ok()?;
+    find_and_cache_thumbnail(path, &mut decoder)
+}
+
+// Shares the thumbnail decoder so the black check only adds a few seeks on import. A cached
+// thumbnail still needs the decoder for the black check, but skips the thumbnail search.
+pub fn extract_thumbnail_checking_black(path: &Path) -> (Option<image::RgbaImage>, bool) {
+    if let Some(img) = thumbnail_cache::load(path) {
+        return (Some(img), opening_frames_black(path));
+    }
+    let Ok(mut decoder) = GstFrameDecoder::open(path, THUMB_WIDTH, THUMB_HEIGHT) else {
+        return (None, false);
+    };
+    let black = opening_frames_black_with(&mut decoder);
+    (find_and_cache_thumbnail(path, &mut decoder), black)
+}
+
+pub fn opening_frames_black(path: &Path) -> bool {
+    GstFrameDecoder::open(path, THUMB_WIDTH, THUMB_HEIGHT)
+        .is_ok_and(|mut decoder| opening_frames_black_with(&mut decoder))
+}
+
 pub fn extract_frames_streaming(
     path: &Path,
     count: usize,
@@ -0,0 +1,46 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+// Thumbnails are stored as PNGs named after a hash of the source path and its mtime, so an
+// edited file simply misses and gets a fresh entry.
+fn cache_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+    Some(base.join("wizard-editor").join("thumbnails"))
+}
+
+fn entry_path(path: &Path) -> Option<PathBuf> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let mtime = modified.duration_since(UNIX_EPOCH).ok()?;
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    mtime.as_nanos().hash(&mut hasher);
+    Some(cache_dir()?.join(format!("{:016x}.png", hasher.finish())))
+}
+
+pub(crate) fn load(path: &Path) -> Option<image::RgbaImage> {
+    let entry = entry_path(path)?;
+    Some(image::open(entry).ok()?.into_rgba8())
+}
+
+pub(crate) fn store(path: &Path, img: &image::RgbaImage) {
+    let Some(entry) = entry_path(path) else {
+        return;
+    };
+    if let Some(dir) = entry.parent() {
+        if std::fs::create_dir_all(dir).is_err() {
+            return;
+        }
+    }
+    // Written under a temporary name so a concurrent reader never sees a partial PNG.
+    let tmp = entry.with_extension("png.tmp");
+    if img.save_with_format(&tmp, image::ImageFormat::Png).is_ok() {
+        let _ = std::fs::rename(&tmp, &entry);
+    } else {
+        let _ = std::fs::remove_file(&tmp);
+    }
+}
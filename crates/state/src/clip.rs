@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use uuid::Uuid;
 
-use crate::tag::Tag;
+use crate::tag::TagSet;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ClipId(Uuid);
@@ -26,8 +26,16 @@ pub struct Clip {
     pub display_name: Option<String>,
     pub duration: Option<f64>,
     pub resolution: Option<(u32, u32)>,
+    pub fps: Option<f64>,
     pub codec: Option<String>,
     pub audio_only: bool,
+    // Assumed until metadata says otherwise, so early drops still get their audio.
+    pub has_audio: bool,
+    pub audio_channels: Option<u32>,
+    pub metadata_loaded: bool,
+    // Quality flags, only filled in while quality checks are switched on.
+    pub silent: bool,
+    pub black_opening: bool,
     pub search_haystack: String,
 }
 
@@ -51,8 +59,14 @@ impl Clip {
             display_name: None,
             duration: None,
             resolution: None,
+            fps: None,
             codec: None,
             audio_only: false,
+            has_audio: true,
+            audio_channels: None,
+            metadata_loaded: false,
+            silent: false,
+            black_opening: false,
             search_haystack,
         }
     }
@@ -61,11 +75,51 @@ impl Clip {
         self.display_name.as_deref().unwrap_or(&self.filename)
     }
 
+    pub fn resolution_badge(&self) -> Option<&'static str> {
+        let (w, h) = self.resolution?;
+        Some(match (w, h) {
+            (w, h) if w >= 7680 || h >= 4320 => "8K",
+            (w, h) if w >= 3840 || h >= 2160 => "4K",
+            (_, h) if h >= 1440 => "1440p",
+            (_, h) if h >= 1080 => "1080p",
+            (_, h) if h >= 720 => "720p",
+            _ => "SD",
+        })
+    }
+
+    // Codecs are stored as GStreamer caps names ("video/x-h264"); badges want the family.
+    pub fn codec_badge(&self) -> Option<&str> {
+        let codec = self.codec.as_deref()?;
+        let name = codec.rsplit('/').next().unwrap_or(codec);
+        let name = name.strip_prefix("x-").unwrap_or(name);
+        Some(match name {
+            "h264" => "H.264",
+            "h265" => "HEVC",
+            "vp8" => "VP8",
+            "vp9" => "VP9",
+            "av1" => "AV1",
+            "prores" => "ProRes",
+            "mpeg" => "MPEG",
+            "jpeg" => "MJPEG",
+            _ => name,
+        })
+    }
+
+    pub fn channel_layout(&self) -> Option<String> {
+        Some(match self.audio_channels? {
+            1 => "Mono".to_string(),
+            2 => "Stereo".to_string(),
+            6 => "5.1".to_string(),
+            8 => "7.1".to_string(),
+            n => format!("{n} channels"),
+        })
+    }
+
     pub fn extension(&self) -> &str {
         self.path.extension().and_then(|e| e.to_str()).unwrap_or("")
     }
 
-    pub fn rebuild_search_haystack(&mut self, tag_mask: u32) {
+    pub fn rebuild_search_haystack(&mut self, tags: &TagSet, tag_mask: u32) {
         let mut haystack = self.filename.to_lowercase();
 
         if let Some(name) = &self.display_name {
@@ -100,11 +154,9 @@ impl Clip {
             haystack.push_str(&dur_i.to_string());
         }
 
-        for tag in Tag::ALL {
-            if (tag_mask & tag.bit()) != 0 {
-                haystack.push(' ');
-                haystack.push_str(&tag.label().to_lowercase());
-            }
+        for name in tags.names(tag_mask) {
+            haystack.push(' ');
+            haystack.push_str(&name.to_lowercase());
         }
 
         self.search_haystack = haystack;
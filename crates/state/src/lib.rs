@@ -3,5 +3,8 @@ pub mod playback;
 pub mod project;
 pub mod selection;
 pub mod tag;
+pub mod task;
+pub mod timecode;
 pub mod timeline;
+pub mod toast;
 pub mod undo;
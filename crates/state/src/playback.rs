@@ -6,12 +6,108 @@ pub enum PlaybackState {
     PlayingReverse,
 }
 
+const END_EPSILON_S: f64 = 1e-3;
+const CLOCK_MAX_STEP_S: f64 = 0.033;
+const CLOCK_MAX_PENDING_S: f64 = 0.25;
+const CLOCK_DRIFT_TOLERANCE_S: f64 = 0.08;
+const CLOCK_DRIFT_SNAP_S: f64 = 0.5;
+const CLOCK_CORRECTION_PER_S: f64 = 4.0;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlaybackDirection {
     Forward,
     Reverse,
 }
 
+// How much work reverse playback may do. Lower budgets decode smaller frames (upscaled
+// for display) and keep fewer frames in flight so reverse stays real-time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReverseBudget {
+    #[default]
+    Full,
+    Balanced,
+    Low,
+}
+
+impl ReverseBudget {
+    pub const ALL: [ReverseBudget; 3] = [
+        ReverseBudget::Full,
+        ReverseBudget::Balanced,
+        ReverseBudget::Low,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReverseBudget::Full => "Full",
+            ReverseBudget::Balanced => "Balanced",
+            ReverseBudget::Low => "Low",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|b| b.label() == label)
+    }
+
+    pub fn buffered_frames(self) -> usize {
+        match self {
+            ReverseBudget::Full => 8,
+            ReverseBudget::Balanced => 4,
+            ReverseBudget::Low => 2,
+        }
+    }
+
+    pub fn resolution_scale(self) -> f64 {
+        match self {
+            ReverseBudget::Full => 1.0,
+            ReverseBudget::Balanced => 0.667,
+            ReverseBudget::Low => 0.5,
+        }
+    }
+
+    pub fn decode_size(self, width: u32, height: u32) -> (u32, u32) {
+        let scale = self.resolution_scale();
+        let w = ((width as f64 * scale) as u32).max(2) & !1;
+        let h = ((height as f64 * scale) as u32).max(2) & !1;
+        (w, h)
+    }
+}
+
+// What scrubbing trades for speed. Fast decodes small frames, gives up on a frame after a
+// short decode and lets the scrub cache stand in; Accurate always decodes the exact frame
+// at full size, for precise trims.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScrubQuality {
+    #[default]
+    Fast,
+    Accurate,
+}
+
+impl ScrubQuality {
+    pub const ALL: [ScrubQuality; 2] = [ScrubQuality::Fast, ScrubQuality::Accurate];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ScrubQuality::Fast => "Fast",
+            ScrubQuality::Accurate => "Accurate",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|q| q.label() == label)
+    }
+
+    pub fn uses_scrub_cache(self) -> bool {
+        self == ScrubQuality::Fast
+    }
+
+    pub fn decode_size(self, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            ScrubQuality::Fast => ((width / 2).max(2) & !1, (height / 2).max(2) & !1),
+            ScrubQuality::Accurate => (width, height),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Playback {
     pub state: PlaybackState,
@@ -37,11 +133,19 @@ impl Playback {
         }
     }
 
-    pub fn toggle_play(&mut self) {
+    // Any playback stops; starting always plays forward at normal speed, from the top if
+    // the playhead is parked at the end.
+    pub fn toggle_play(&mut self, end: f64) {
         self.state = match self.state {
             PlaybackState::Playing => PlaybackState::Stopped,
             PlaybackState::PlayingReverse => PlaybackState::Stopped,
-            PlaybackState::Stopped => PlaybackState::Playing,
+            PlaybackState::Stopped => {
+                if self.playhead >= end - END_EPSILON_S {
+                    self.playhead = 0.0;
+                }
+                self.speed = 1.0;
+                PlaybackState::Playing
+            }
         };
     }
 
@@ -56,7 +160,8 @@ impl Playback {
         self.state = PlaybackState::Stopped;
     }
 
-    pub fn advance(&mut self, dt: f64, duration: f64) {
+    // `end` is where forward playback parks, normally the start of the last frame.
+    pub fn advance(&mut self, dt: f64, end: f64) {
         match self.state {
             PlaybackState::Playing => self.playhead += dt * self.speed,
             PlaybackState::PlayingReverse => self.playhead -= dt * self.speed,
@@ -66,6 +171,50 @@ impl Playback {
             self.playhead = 0.0;
             self.state = PlaybackState::Stopped;
         }
-        let _ = duration;
+        if self.state == PlaybackState::Playing && self.playhead >= end {
+            self.playhead = end.max(0.0);
+            self.state = PlaybackState::Stopped;
+        }
+    }
+}
+
+// The one thing that moves the playhead during playback. It runs off the UI clock at
+// `speed`; decoded frames only tell it how far off it is. Small drift is ignored, larger
+// drift (and time lost to a long UI frame) is paid back a little each step so the playhead
+// glides rather than jumps, and only a gap too big to glide across is taken in one go.
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackClock {
+    pending: f64,
+}
+
+impl PlaybackClock {
+    pub fn reset(&mut self) {
+        self.pending = 0.0;
+    }
+
+    pub fn advance(&mut self, playback: &mut Playback, dt: f64, end: f64) {
+        let step = dt.min(CLOCK_MAX_STEP_S);
+        let lost = (dt - step) * playback.speed;
+        let lost = match playback.state {
+            PlaybackState::Playing => lost,
+            PlaybackState::PlayingReverse => -lost,
+            PlaybackState::Stopped => return,
+        };
+        self.pending = (self.pending + lost).clamp(-CLOCK_MAX_PENDING_S, CLOCK_MAX_PENDING_S);
+        let correction = self.pending * (step * CLOCK_CORRECTION_PER_S).min(1.0);
+        self.pending -= correction;
+        playback.playhead += correction;
+        playback.advance(step, end);
+    }
+
+    // `decoded` is the timeline position of the frame just shown.
+    pub fn sync_to_decoded(&mut self, playback: &mut Playback, decoded: f64) {
+        let drift = decoded - playback.playhead;
+        if drift.abs() >= CLOCK_DRIFT_SNAP_S {
+            playback.playhead = decoded;
+            self.pending = 0.0;
+        } else if drift.abs() > CLOCK_DRIFT_TOLERANCE_S {
+            self.pending = drift;
+        }
     }
 }
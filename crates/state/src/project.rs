@@ -1,11 +1,16 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::clip::{Clip, ClipId};
-use crate::playback::Playback;
-use crate::selection::Selection;
-use crate::tag::Tag;
-use crate::timeline::{Timeline, TimelineClipId, TrackId};
-use crate::undo::UndoManager;
+use crate::playback::{Playback, ReverseBudget, ScrubQuality};
+use crate::selection::{Selection, SourceMarks};
+use crate::tag::{Tag, TagSet};
+use crate::task::BackgroundTasks;
+use crate::timeline::{
+    OverlapSettings, PlayheadHit, Timeline, TimelineClip, TimelineClipId, TrackId,
+    MIN_CLIP_DURATION,
+};
+use crate::toast::Toasts;
+use crate::undo::{ProjectSnapshot, UndoCoalesceToken, UndoManager};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TrimEdge {
@@ -52,18 +57,386 @@ impl SortMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PreviewScale {
+    #[default]
+    Fit,
+    Native,
+}
+
+impl PreviewScale {
+    pub const ALL: [PreviewScale; 2] = [PreviewScale::Fit, PreviewScale::Native];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PreviewScale::Fit => "Fit",
+            PreviewScale::Native => "100%",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            PreviewScale::Fit => PreviewScale::Native,
+            PreviewScale::Native => PreviewScale::Fit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZoomAnchor {
+    Pointer,
+    #[default]
+    PlayheadWhilePlaying,
+    Playhead,
+}
+
+impl ZoomAnchor {
+    pub const ALL: [ZoomAnchor; 3] = [
+        ZoomAnchor::Pointer,
+        ZoomAnchor::PlayheadWhilePlaying,
+        ZoomAnchor::Playhead,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ZoomAnchor::Pointer => "Zoom at pointer",
+            ZoomAnchor::PlayheadWhilePlaying => "Zoom at playhead while playing",
+            ZoomAnchor::Playhead => "Zoom at playhead",
+        }
+    }
+}
+
+// Sizes the output ring buffer. Short buffers make scrubbing feel immediate; long ones ride
+// out slow frames without dropouts. Auto leaves the size to the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AudioLatency {
+    #[default]
+    Auto,
+    Low,
+    Normal,
+    High,
+}
+
+impl AudioLatency {
+    pub const ALL: [AudioLatency; 4] = [
+        AudioLatency::Auto,
+        AudioLatency::Low,
+        AudioLatency::Normal,
+        AudioLatency::High,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AudioLatency::Auto => "Auto",
+            AudioLatency::Low => "Low",
+            AudioLatency::Normal => "Normal",
+            AudioLatency::High => "High",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.label() == label)
+    }
+
+    pub fn buffer_ms(self) -> Option<u32> {
+        match self {
+            AudioLatency::Auto => None,
+            AudioLatency::Low => Some(40),
+            AudioLatency::Normal => Some(100),
+            AudioLatency::High => Some(250),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormatPreset {
+    pub name: &'static str,
+    pub fps: f64,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub const FORMAT_PRESETS: [FormatPreset; 9] = [
+    FormatPreset {
+        name: "720p30",
+        fps: 30.0,
+        width: 1280,
+        height: 720,
+    },
+    FormatPreset {
+        name: "1080p23.976",
+        fps: 24000.0 / 1001.0,
+        width: 1920,
+        height: 1080,
+    },
+    FormatPreset {
+        name: "1080p24",
+        fps: 24.0,
+        width: 1920,
+        height: 1080,
+    },
+    FormatPreset {
+        name: "1080p25",
+        fps: 25.0,
+        width: 1920,
+        height: 1080,
+    },
+    FormatPreset {
+        name: "1080p30",
+        fps: 30.0,
+        width: 1920,
+        height: 1080,
+    },
+    FormatPreset {
+        name: "1080p60",
+        fps: 60.0,
+        width: 1920,
+        height: 1080,
+    },
+    FormatPreset {
+        name: "4K24",
+        fps: 24.0,
+        width: 3840,
+        height: 2160,
+    },
+    FormatPreset {
+        name: "4K30",
+        fps: 30.0,
+        width: 3840,
+        height: 2160,
+    },
+    FormatPreset {
+        name: "4K60",
+        fps: 60.0,
+        width: 3840,
+        height: 2160,
+    },
+];
+
+#[derive(Debug, Clone)]
+pub struct ProjectSettings {
+    pub fps: f64,
+    pub width: u32,
+    pub height: u32,
+    // False until the user picks a format or the first video clip supplies one.
+    pub configured: bool,
+    // Seconds of fade in and out given to newly placed clips and by "Add Fades".
+    pub default_fade: f64,
+}
+
+impl Default for ProjectSettings {
+    fn default() -> Self {
+        Self {
+            fps: 24.0,
+            width: 1920,
+            height: 1080,
+            configured: false,
+            default_fade: 0.0,
+        }
+    }
+}
+
+impl ProjectSettings {
+    pub fn resolution(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn frame_duration(&self) -> f64 {
+        1.0 / self.fps.max(1.0)
+    }
+
+    // The start of the frame containing `time`, nudged so times already on a boundary
+    // don't fall back a frame through rounding.
+    pub fn frame_start(&self, time: f64) -> f64 {
+        let fps = self.fps.max(1.0);
+        ((time * fps + FRAME_EPSILON).floor() / fps).max(0.0)
+    }
+
+    pub fn nearest_frame(&self, time: f64) -> f64 {
+        let fps = self.fps.max(1.0);
+        ((time * fps).round() / fps).max(0.0)
+    }
+
+    pub fn next_frame_boundary(&self, time: f64) -> f64 {
+        let fps = self.fps.max(1.0);
+        ((time * fps - FRAME_EPSILON).ceil() / fps).max(0.0)
+    }
+
+    pub fn apply_preset(&mut self, preset: &FormatPreset) {
+        self.fps = preset.fps;
+        self.width = preset.width;
+        self.height = preset.height;
+        self.configured = true;
+    }
+
+    pub fn matching_preset(&self) -> Option<&'static FormatPreset> {
+        FORMAT_PRESETS.iter().find(|p| {
+            (p.fps - self.fps).abs() <= FPS_TOLERANCE && (p.width, p.height) == self.resolution()
+        })
+    }
+
+    // Until a format is chosen, the first video clip with known metadata sets it.
+    pub fn adopt_clip_format(&mut self, clip: &Clip) {
+        if self.configured || clip.audio_only {
+            return;
+        }
+        let (Some((width, height)), Some(fps)) = (clip.resolution, clip.fps) else {
+            return;
+        };
+        self.fps = fps;
+        self.width = width;
+        self.height = height;
+        self.configured = true;
+    }
+
+    pub fn resolution_mismatch(&self, clip: &Clip) -> bool {
+        !clip.audio_only && clip.resolution.is_some_and(|r| r != self.resolution())
+    }
+
+    pub fn fps_mismatch(&self, clip: &Clip) -> bool {
+        !clip.audio_only
+            && clip
+                .fps
+                .is_some_and(|fps| (fps - self.fps).abs() > FPS_TOLERANCE)
+    }
+}
+
+const RESTORED_TAG_COLOR: [u8; 3] = [160, 160, 170];
+
+// NTSC rates (29.97 vs 30000/1001) shouldn't count as a mismatch.
+const FPS_TOLERANCE: f64 = 0.01;
+const FRAME_EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatMismatch {
+    pub timeline_clip: TimelineClipId,
+    pub source_id: ClipId,
+    pub fps: Option<f64>,
+    pub resolution: Option<(u32, u32)>,
+}
+
 #[derive(Default)]
 pub struct ProjectState {
     pub clips: HashMap<ClipId, Clip>,
     pub clip_order: Vec<ClipId>,
     pub starred: HashSet<ClipId>,
     pub clip_tags: HashMap<ClipId, u32>,
+    pub tags: TagSet,
+    // Bumped whenever stars, tag assignments or tag names change, so callers can cheaply
+    // notice when clip organisation needs saving.
+    pub organize_revision: u64,
     pub timeline: Timeline,
     pub playback: Playback,
     pub undo: UndoManager,
+    pub settings: ProjectSettings,
 }
 
 impl ProjectState {
+    // Clips are half-open, so the timeline duration itself shows nothing; playback parks on
+    // the last frame instead so the preview keeps it on screen.
+    pub fn playback_end(&self) -> f64 {
+        (self.timeline.timeline_duration() - self.settings.frame_duration()).max(0.0)
+    }
+
+    // Nearest-frame conform: the timeline time is held to its project frame, and the source
+    // frame closest to that moment is the one shown.
+    fn conformed_source_time(&self, hit: &PlayheadHit, time: f64) -> f64 {
+        let tc = &hit.clip;
+        let frame_time = self.settings.frame_start(time).max(tc.timeline_start);
+        let source_time = tc.source_in + (frame_time - tc.timeline_start);
+        let Some(clip_fps) = self.clips.get(&tc.source_id).and_then(|c| c.fps) else {
+            return source_time;
+        };
+        if clip_fps <= 0.0 {
+            return source_time;
+        }
+        let nearest = (source_time * clip_fps).round() / clip_fps;
+        let last_frame = (tc.source_out - 1.0 / clip_fps).max(tc.source_in);
+        nearest.clamp(tc.source_in, last_frame)
+    }
+
+    // The clip lookups playback, scrubbing and the engine go through instead of the
+    // timeline's own, so every path shows the same conformed source frame for a playhead.
+    pub fn video_clip_at_time(&self, time: f64) -> Option<PlayheadHit> {
+        self.timeline
+            .video_clip_at_time(time)
+            .map(|hit| self.conform_hit(hit))
+    }
+
+    pub fn audio_clip_at_time(&self, time: f64) -> Option<PlayheadHit> {
+        self.timeline
+            .audio_clip_at_time(time)
+            .map(|hit| self.conform_hit(hit))
+    }
+
+    pub fn audio_clips_at_time(&self, time: f64) -> Vec<PlayheadHit> {
+        self.timeline
+            .audio_clips_at_time(time)
+            .into_iter()
+            .map(|hit| self.conform_hit(hit))
+            .collect()
+    }
+
+    pub fn next_clip_after(&self, id: TimelineClipId) -> Option<PlayheadHit> {
+        self.timeline
+            .next_clip_after(id)
+            .map(|hit| self.conform_hit(hit))
+    }
+
+    pub fn previous_clip_before(&self, id: TimelineClipId) -> Option<PlayheadHit> {
+        self.timeline
+            .previous_clip_before(id)
+            .map(|hit| self.conform_hit(hit))
+    }
+
+    pub fn next_video_clip_after_time(&self, time: f64) -> Option<PlayheadHit> {
+        self.timeline
+            .next_video_clip_after_time(time)
+            .map(|hit| self.conform_hit(hit))
+    }
+
+    fn conform_hit(&self, mut hit: PlayheadHit) -> PlayheadHit {
+        let time = hit.clip.timeline_start + (hit.source_time - hit.clip.source_in);
+        hit.source_time = self.conformed_source_time(&hit, time);
+        hit
+    }
+
+    // One entry per source used on the timeline, pointing at its earliest instance so
+    // linked audio doesn't list the same file twice.
+    pub fn format_mismatches(&self) -> Vec<FormatMismatch> {
+        let mut first_use: HashMap<ClipId, (f64, TimelineClipId)> = HashMap::new();
+        for track in self.timeline.all_tracks() {
+            for tc in &track.clips {
+                let entry = first_use
+                    .entry(tc.source_id)
+                    .or_insert((tc.timeline_start, tc.id));
+                if tc.timeline_start < entry.0 {
+                    *entry = (tc.timeline_start, tc.id);
+                }
+            }
+        }
+
+        let mut mismatches: Vec<(f64, FormatMismatch)> = first_use
+            .into_iter()
+            .filter_map(|(source_id, (start, timeline_clip))| {
+                let clip = self.clips.get(&source_id)?;
+                let fps_off = self.settings.fps_mismatch(clip);
+                let resolution_off = self.settings.resolution_mismatch(clip);
+                (fps_off || resolution_off).then_some((
+                    start,
+                    FormatMismatch {
+                        timeline_clip,
+                        source_id,
+                        fps: clip.fps.filter(|_| fps_off),
+                        resolution: clip.resolution.filter(|_| resolution_off),
+                    },
+                ))
+            })
+            .collect();
+        mismatches.sort_by(|a, b| a.0.total_cmp(&b.0));
+        mismatches.into_iter().map(|(_, m)| m).collect()
+    }
+
     pub fn add_clip(&mut self, clip: Clip) {
         let id = clip.id;
         self.clip_order.push(id);
@@ -72,34 +445,306 @@ impl ProjectState {
     }
 
     pub fn toggle_star(&mut self, id: ClipId) {
+        self.snapshot_for_undo();
+        self.organize_revision += 1;
         if !self.starred.remove(&id) {
             self.starred.insert(id);
         }
     }
 
+    // Stars every clip unless all of them already are, in which case it unstars them.
+    pub fn toggle_star_many(&mut self, ids: &[ClipId]) {
+        self.snapshot_for_undo();
+        self.organize_revision += 1;
+        let all_starred = ids.iter().all(|id| self.starred.contains(id));
+        for &id in ids {
+            if all_starred {
+                self.starred.remove(&id);
+            } else {
+                self.starred.insert(id);
+            }
+        }
+    }
+
+    pub fn toggle_tag_many(&mut self, ids: &[ClipId], tag: Tag) {
+        self.snapshot_for_undo();
+        self.organize_revision += 1;
+        let all_tagged = ids
+            .iter()
+            .all(|id| self.clip_tag_mask(*id) & tag.bit() != 0);
+        for &id in ids {
+            let entry = self.clip_tags.entry(id).or_insert(0);
+            if all_tagged {
+                *entry &= !tag.bit();
+            } else {
+                *entry |= tag.bit();
+            }
+            self.refresh_search_haystack(id);
+        }
+    }
+
+    // Reapplies saved stars and tags to a freshly imported clip. Tags are matched by name and
+    // created if this project doesn't define them yet. Not an edit, so no undo step.
+    pub fn restore_organization(&mut self, id: ClipId, starred: bool, tag_names: &[String]) {
+        if starred {
+            self.starred.insert(id);
+        }
+        let mut mask = 0;
+        for name in tag_names {
+            let tag = self
+                .tags
+                .find_by_name(name)
+                .or_else(|| self.tags.add(name, RESTORED_TAG_COLOR));
+            if let Some(tag) = tag {
+                mask |= tag.bit();
+            }
+        }
+        if mask != 0 {
+            *self.clip_tags.entry(id).or_insert(0) |= mask;
+            self.refresh_search_haystack(id);
+        }
+    }
+
+    pub fn refresh_search_haystack(&mut self, id: ClipId) {
+        let mask = self.clip_tag_mask(id);
+        if let Some(clip) = self.clips.get_mut(&id) {
+            clip.rebuild_search_haystack(&self.tags, mask);
+        }
+    }
+
+    fn refresh_all_search_haystacks(&mut self) {
+        for (id, clip) in &mut self.clips {
+            let mask = self.clip_tags.get(id).copied().unwrap_or(0);
+            clip.rebuild_search_haystack(&self.tags, mask);
+        }
+    }
+
+    pub fn add_tag(&mut self, name: &str, color: [u8; 3]) -> Option<Tag> {
+        if self.tags.is_full() {
+            return None;
+        }
+        self.snapshot_for_undo();
+        self.tags.add(name, color)
+    }
+
+    pub fn rename_tag(&mut self, tag: Tag, name: &str) {
+        if self.tags.get(tag).is_none_or(|d| d.name == name) {
+            return;
+        }
+        self.snapshot_for_undo();
+        if let Some(def) = self.tags.get_mut(tag) {
+            def.name = name.to_string();
+        }
+        self.organize_revision += 1;
+        self.refresh_all_search_haystacks();
+    }
+
+    pub fn set_tag_color(&mut self, tag: Tag, color: [u8; 3]) {
+        if let Some(def) = self.tags.get_mut(tag) {
+            def.color = color;
+        }
+    }
+
+    // Clears the bit from every clip so a tag added later in the same slot starts empty.
+    pub fn remove_tag(&mut self, tag: Tag) {
+        self.snapshot_for_undo();
+        self.tags.remove(tag);
+        for mask in self.clip_tags.values_mut() {
+            *mask &= !tag.bit();
+        }
+        self.organize_revision += 1;
+        self.refresh_all_search_haystacks();
+    }
+
     pub fn clip_tag_mask(&self, id: ClipId) -> u32 {
         self.clip_tags.get(&id).copied().unwrap_or(0)
     }
 
     pub fn toggle_tag(&mut self, id: ClipId, tag: Tag) {
-        let entry = self.clip_tags.entry(id).or_insert(0);
-        *entry ^= tag.bit();
+        self.toggle_tag_many(&[id], tag);
+    }
+
+    fn snapshot(&self) -> ProjectSnapshot {
+        ProjectSnapshot {
+            timeline: self.timeline.clone(),
+            starred: self.starred.clone(),
+            clip_tags: self.clip_tags.clone(),
+            tags: self.tags.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: ProjectSnapshot) {
+        self.timeline = snapshot.timeline;
+        self.starred = snapshot.starred;
+        self.organize_revision += 1;
+        let mut tags = snapshot.tags;
+        // Tag colours are presentation rather than edits, so they survive undo.
+        for def in self.tags.iter() {
+            if let Some(restored) = tags.get_mut(def.tag) {
+                restored.color = def.color;
+            }
+        }
+        if self.clip_tags != snapshot.clip_tags || self.tags != tags {
+            self.clip_tags = snapshot.clip_tags;
+            self.tags = tags;
+            self.refresh_all_search_haystacks();
+        }
     }
 
     pub fn snapshot_for_undo(&mut self) {
-        self.undo.save(self.timeline.clone());
+        let snapshot = self.snapshot();
+        self.undo.save(snapshot);
+    }
+
+    pub fn begin_undo_gesture(&mut self) -> UndoCoalesceToken {
+        let snapshot = self.snapshot();
+        self.undo.begin_coalesce(snapshot)
+    }
+
+    pub fn end_undo_gesture(&mut self, token: UndoCoalesceToken) {
+        let current = self.snapshot();
+        self.undo.end_coalesce(token, &current);
+    }
+
+    pub fn cancel_undo_gesture(&mut self, token: UndoCoalesceToken) {
+        self.undo.cancel_coalesce(token);
     }
 
     pub fn undo(&mut self) {
-        if let Some(previous) = self.undo.undo(self.timeline.clone()) {
-            self.timeline = previous;
+        if self.undo.is_coalescing() {
+            return;
+        }
+        let current = self.snapshot();
+        if let Some(previous) = self.undo.undo(current) {
+            self.restore(previous);
         }
     }
 
     pub fn redo(&mut self) {
-        if let Some(next) = self.undo.redo(self.timeline.clone()) {
-            self.timeline = next;
+        if self.undo.is_coalescing() {
+            return;
+        }
+        let current = self.snapshot();
+        if let Some(next) = self.undo.redo(current) {
+            self.restore(next);
+        }
+    }
+
+    // The clip the trim-to-playhead commands act on: the selection, or whatever sits under
+    // the playhead when nothing is selected.
+    pub fn clip_for_playhead_edit(&self, selection: &Selection) -> Option<TimelineClipId> {
+        if let Some(id) = selection.primary_timeline_clip() {
+            return Some(id);
+        }
+        let playhead = self.playback.playhead;
+        self.timeline
+            .video_clip_at_time(playhead)
+            .or_else(|| self.timeline.audio_clip_at_time(playhead))
+            .map(|hit| hit.clip.id)
+    }
+
+    // One undo step, and none at all when nothing under the playhead could be cut.
+    pub fn split_all_at_playhead(&mut self) {
+        let token = self.begin_undo_gesture();
+        if self.timeline.split_clip_at_playhead(self.playback.playhead) {
+            self.end_undo_gesture(token);
+        } else {
+            self.cancel_undo_gesture(token);
+        }
+    }
+
+    // Two selected clips that could be merged. Linked partners are usually selected along
+    // with them, so up to four clips may be selected.
+    pub fn mergeable_selection(
+        &self,
+        selection: &Selection,
+    ) -> Option<(TimelineClipId, TimelineClipId)> {
+        let selected: Vec<_> = selection.selected_timeline_clips.iter().copied().collect();
+        if selected.len() > 4 {
+            return None;
+        }
+        selected.iter().enumerate().find_map(|(i, &a)| {
+            selected[i + 1..]
+                .iter()
+                .find(|&&b| self.timeline.can_merge(a, b))
+                .map(|&b| (a, b))
+        })
+    }
+
+    pub fn trim_clip_to_playhead(&mut self, clip_id: TimelineClipId, edge: TrimEdge) {
+        let Some((_, _, tc)) = self.timeline.find_clip(clip_id) else {
+            return;
+        };
+        let playhead = self.playback.playhead;
+        let source_duration = self
+            .clips
+            .get(&tc.source_id)
+            .and_then(|c| c.duration)
+            .unwrap_or(tc.source_out);
+
+        let (start, source_in, source_out) = match edge {
+            TrimEdge::Left => {
+                let delta = (playhead - tc.timeline_start)
+                    .min(tc.duration - MIN_CLIP_DURATION)
+                    .max(-tc.source_in.min(tc.timeline_start));
+                (
+                    tc.timeline_start + delta,
+                    tc.source_in + delta,
+                    tc.source_out,
+                )
+            }
+            TrimEdge::Right => {
+                let out = (tc.source_in + playhead - tc.timeline_start)
+                    .max(tc.source_in + MIN_CLIP_DURATION)
+                    .min(source_duration.max(tc.source_out));
+                (tc.timeline_start, tc.source_in, out)
+            }
+        };
+        if (start - tc.timeline_start).abs() < f64::EPSILON
+            && (source_out - tc.source_out).abs() < f64::EPSILON
+        {
+            return;
+        }
+
+        self.snapshot_for_undo();
+        if let Some((track, idx)) = self.timeline.find_clip_track_mut(clip_id) {
+            let tc = &mut track.clips[idx];
+            tc.timeline_start = start;
+            tc.source_in = source_in;
+            tc.source_out = source_out;
+            tc.duration = source_out - source_in;
+        }
+        self.timeline.finalize_trim(clip_id);
+    }
+
+    // Keeps the clip's timeline start; overlaps are left for finalize_trim.
+    pub fn set_clip_source_range(
+        &mut self,
+        clip_id: TimelineClipId,
+        source_in: f64,
+        source_out: f64,
+    ) {
+        let Some((_, _, tc)) = self.timeline.find_clip(clip_id) else {
+            return;
+        };
+        let source_duration = self
+            .clips
+            .get(&tc.source_id)
+            .and_then(|c| c.duration)
+            .unwrap_or(tc.source_out)
+            .max(tc.source_out);
+
+        let source_out = source_out.min(source_duration);
+        let source_in = source_in.clamp(0.0, (source_out - MIN_CLIP_DURATION).max(0.0));
+        let source_out = source_out.max(source_in + MIN_CLIP_DURATION);
+        if let Some((track, idx)) = self.timeline.find_clip_track_mut(clip_id) {
+            let tc = &mut track.clips[idx];
+            tc.source_in = source_in;
+            tc.source_out = source_out;
+            tc.duration = source_out - source_in;
+            tc.validate_bounds();
         }
+        self.timeline.sync_linked_clip(clip_id, false);
     }
 
     pub fn add_clip_to_track(
@@ -107,10 +752,40 @@ impl ProjectState {
         source_id: ClipId,
         track_id: TrackId,
         position_seconds: f64,
+        link_audio: bool,
+        overlap: OverlapSettings,
+    ) {
+        let duration = self
+            .clips
+            .get(&source_id)
+            .and_then(|c| c.duration)
+            .unwrap_or(3.0)
+            .max(0.1);
+        self.add_clip_range_to_track(
+            source_id,
+            track_id,
+            position_seconds,
+            (0.0, duration),
+            link_audio,
+            overlap,
+        );
+    }
+
+    // Places `source_range` of the source, rather than all of it, as with three-point edits.
+    pub fn add_clip_range_to_track(
+        &mut self,
+        source_id: ClipId,
+        track_id: TrackId,
+        position_seconds: f64,
+        source_range: (f64, f64),
+        link_audio: bool,
+        overlap: OverlapSettings,
     ) {
+        let (source_in, source_out) = source_range;
+        let duration = source_out - source_in;
         let clip = self.clips.get(&source_id);
-        let duration = clip.and_then(|c| c.duration).unwrap_or(3.0).max(0.1);
         let audio_only = clip.map(|c| c.audio_only).unwrap_or(false);
+        let has_audio = clip.is_none_or(|c| c.has_audio);
 
         let track_kind = self.timeline.track_index_and_kind(track_id);
 
@@ -122,25 +797,70 @@ impl ProjectState {
                 }
                 None => track_id,
             };
-            self.timeline
-                .add_clip_to_track(source_id, audio_track_id, position_seconds, duration);
+            let position_seconds = position_seconds.max(0.0)
+                + self.timeline.no_overlap_shift(
+                    &[(audio_track_id, position_seconds.max(0.0), duration)],
+                    &HashSet::new(),
+                    overlap,
+                );
+            let id = self.timeline.add_clip_to_track(
+                source_id,
+                audio_track_id,
+                position_seconds,
+                source_in,
+                source_out,
+            );
+            let fade = self.settings.default_fade;
+            self.timeline.set_clip_fades(id, fade, fade);
             return;
         }
 
-        let paired = self.timeline.paired_track_id(track_id);
+        // Sources with sound get a linked clip on the paired audio track unless the user
+        // asked for video-only drops.
+        let paired = self
+            .timeline
+            .paired_track_id(track_id)
+            .filter(|_| link_audio && has_audio);
+        let mut placements = vec![(track_id, position_seconds.max(0.0), duration)];
+        if let Some(paired_track) = paired {
+            placements.push((paired_track, position_seconds.max(0.0), duration));
+        }
+        let position_seconds = position_seconds.max(0.0)
+            + self
+                .timeline
+                .no_overlap_shift(&placements, &HashSet::new(), overlap);
 
-        let primary_id =
-            self.timeline
-                .add_clip_to_track(source_id, track_id, position_seconds, duration);
+        let primary_id = self.timeline.add_clip_to_track(
+            source_id,
+            track_id,
+            position_seconds,
+            source_in,
+            source_out,
+        );
+        let fade = self.settings.default_fade;
+        self.timeline.set_clip_fades(primary_id, fade, fade);
 
         if let Some(paired_track) = paired {
             let linked_id = self.timeline.add_clip_to_track(
                 source_id,
                 paired_track,
                 position_seconds,
-                duration,
+                source_in,
+                source_out,
             );
             self.timeline.link_clips(primary_id, linked_id);
+            self.timeline.set_clip_fades(linked_id, fade, fade);
+        }
+    }
+
+    pub fn add_default_fades(&mut self, clip_ids: &[TimelineClipId]) {
+        if clip_ids.is_empty() {
+            return;
+        }
+        self.snapshot_for_undo();
+        let fade = self.settings.default_fade;
+        for &id in clip_ids {
+            self.timeline.set_clip_fades(id, fade, fade);
         }
     }
 }
@@ -157,6 +877,14 @@ pub struct BrowserUiState {
     pub hover_active_clip: Option<ClipId>,
     pub hover_started_at: Option<f64>,
     pub hovered_scrub_t: Option<f32>,
+    // Scrolled into view the next time the browser is drawn.
+    pub reveal_clip: Option<ClipId>,
+    pub tag_manager_open: bool,
+    pub renaming_tag: Option<Tag>,
+    pub tag_rename_buffer: String,
+    pub new_tag_name: String,
+    pub new_tag_color: [u8; 3],
+    pub quality_checks: bool,
 }
 
 impl Default for BrowserUiState {
@@ -173,6 +901,13 @@ impl Default for BrowserUiState {
             hover_active_clip: None,
             hover_started_at: None,
             hovered_scrub_t: None,
+            reveal_clip: None,
+            tag_manager_open: false,
+            renaming_tag: None,
+            tag_rename_buffer: String::new(),
+            new_tag_name: String::new(),
+            new_tag_color: [200, 200, 90],
+            quality_checks: false,
         }
     }
 }
@@ -188,6 +923,22 @@ pub struct TimelineUiState {
     pub trimming_clip: Option<TrimState>,
     pub marquee_origin: Option<(f32, f32)>,
     pub marquee_current: Option<(f32, f32)>,
+    pub undo_coalesce_token: Option<UndoCoalesceToken>,
+    pub timecode_edit: Option<String>,
+    pub zoom_edit: Option<String>,
+    pub reveal_playhead: bool,
+    pub zoom_anchor: ZoomAnchor,
+    pub link_audio_on_drop: bool,
+    pub overlap: OverlapSettings,
+    // Off, drags, drops, trims and ruler scrubs all go exactly where the pointer is.
+    pub snapping_enabled: bool,
+    pub trim_mode: bool,
+    pub stereo_waveforms: bool,
+    // Timeline span reverse playback can replay from memory, as reported by the engine.
+    pub rewind_cached_span: Option<(f64, f64)>,
+    // In/out range painted on the ruler, start before end, and where the drag painting it began.
+    pub range_selection: Option<(f64, f64)>,
+    pub range_anchor: Option<f64>,
 }
 
 impl Default for TimelineUiState {
@@ -203,10 +954,32 @@ impl Default for TimelineUiState {
             trimming_clip: None,
             marquee_origin: None,
             marquee_current: None,
+            undo_coalesce_token: None,
+            timecode_edit: None,
+            zoom_edit: None,
+            reveal_playhead: false,
+            zoom_anchor: ZoomAnchor::default(),
+            link_audio_on_drop: true,
+            overlap: OverlapSettings::default(),
+            snapping_enabled: true,
+            trim_mode: false,
+            stereo_waveforms: false,
+            rewind_cached_span: None,
+            range_selection: None,
+            range_anchor: None,
         }
     }
 }
 
+// An inspector edit runs from the first change until no field is being dragged or typed
+// in, so it undoes as one step. A changed source range is only resolved against the
+// neighbouring clips once the edit ends, like a trim with the mouse.
+#[derive(Default)]
+pub struct InspectorUiState {
+    pub undo_coalesce_token: Option<UndoCoalesceToken>,
+    pub trimmed_clip: Option<TimelineClipId>,
+}
+
 #[derive(Default)]
 pub struct DebugUiState {
     pub show_fps: bool,
@@ -214,21 +987,292 @@ pub struct DebugUiState {
     pub video_fps: f32,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioUiState {
+    pub scrub_audio: bool,
+    pub preview_volume: f32,
+    pub output_device: Option<String>,
+    pub latency: AudioLatency,
+}
+
+impl Default for AudioUiState {
+    fn default() -> Self {
+        Self {
+            scrub_audio: true,
+            preview_volume: 1.0,
+            output_device: None,
+            latency: AudioLatency::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PlaybackUiState {
+    pub reverse_budget: ReverseBudget,
+    pub scrub_quality: ScrubQuality,
+    pub snap_playhead_to_frames: bool,
+}
+
+// A browser clip looping in the preview by itself, away from the timeline. The engine
+// reports how far it has played into the source; the monitor's transport pauses it or
+// asks for a seek.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceMonitor {
+    pub clip: ClipId,
+    pub position: f64,
+    pub playing: bool,
+    pub seek_to: Option<f64>,
+}
+
+// How the pipeline feeding playback is doing, reported by the engine every frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineHealth {
+    StartingUp,
+    Delivering,
+    Stalled,
+    LongStall,
+}
+
+#[derive(Default)]
+pub struct PreviewUiState {
+    pub buffering: bool,
+    pub pipeline_health: Option<PipelineHealth>,
+    pub scale: PreviewScale,
+    pub source_monitor: Option<SourceMonitor>,
+    // Where the monitor last left each clip, so reopening one resumes there.
+    pub source_positions: HashMap<ClipId, f64>,
+}
+
 #[derive(Default)]
 pub struct UiState {
     pub browser: BrowserUiState,
     pub timeline: TimelineUiState,
     pub debug: DebugUiState,
+    pub audio: AudioUiState,
+    pub playback: PlaybackUiState,
+    pub preview: PreviewUiState,
     pub selection: Selection,
+    pub inspector: InspectorUiState,
+    pub project_settings_open: bool,
+    pub toasts: Toasts,
+    pub clipboard: Option<Vec<TimelineClip>>,
 }
 
 #[derive(Default)]
 pub struct AppState {
     pub project: ProjectState,
     pub ui: UiState,
+    pub tasks: BackgroundTasks,
 }
 
 impl AppState {
+    // "Match frame": selects the source of the video clip under the playhead in the browser,
+    // scrubbed to the frame the playhead is showing, and opens it paused on that frame in the
+    // source monitor, so it can be found and reused.
+    pub fn match_frame(&mut self) {
+        let playhead = self.project.playback.playhead;
+        let Some(hit) = self.project.video_clip_at_time(playhead) else {
+            return;
+        };
+        let clip_id = hit.clip.source_id;
+        let Some(clip) = self.project.clips.get(&clip_id) else {
+            return;
+        };
+        let source_time = hit.source_time;
+        self.ui.selection.select_single(clip_id);
+        self.ui.selection.selected_scrub_t = clip
+            .duration
+            .filter(|d| *d > 0.0)
+            .map(|d| (source_time / d).clamp(0.0, 1.0) as f32);
+        self.ui.browser.reveal_clip = Some(clip_id);
+        self.open_source_monitor(clip_id);
+        if let Some(monitor) = self.ui.preview.source_monitor.as_mut() {
+            monitor.playing = false;
+            monitor.position = source_time;
+            monitor.seek_to = Some(source_time);
+        }
+    }
+
+    // The browser clip a source mark applies to and the source time it lands on: the clip
+    // being hover-scrubbed if there is one, otherwise the primary selection's scrub point.
+    fn source_position(&self) -> Option<(ClipId, f64)> {
+        let (clip_id, t) = match (
+            self.ui.selection.hovered_clip,
+            self.ui.browser.hovered_scrub_t,
+        ) {
+            (Some(clip_id), Some(t)) => (clip_id, t),
+            _ => (
+                self.ui.selection.primary_clip()?,
+                self.ui.selection.selected_scrub_t?,
+            ),
+        };
+        let duration = self.project.clips.get(&clip_id)?.duration?;
+        Some((clip_id, t.clamp(0.0, 1.0) as f64 * duration))
+    }
+
+    // Marking a different clip starts its marks over.
+    fn source_marks_for(&mut self, clip_id: ClipId) -> &mut SourceMarks {
+        let marks = self.ui.selection.source_marks.get_or_insert(SourceMarks {
+            clip: clip_id,
+            source_in: None,
+            source_out: None,
+        });
+        if marks.clip != clip_id {
+            *marks = SourceMarks {
+                clip: clip_id,
+                source_in: None,
+                source_out: None,
+            };
+        }
+        marks
+    }
+
+    pub fn mark_source_in(&mut self) {
+        if let Some((clip_id, time)) = self.source_position() {
+            self.source_marks_for(clip_id).source_in = Some(time);
+        }
+    }
+
+    pub fn mark_source_out(&mut self) {
+        if let Some((clip_id, time)) = self.source_position() {
+            self.source_marks_for(clip_id).source_out = Some(time);
+        }
+    }
+
+    // The timeline stops so the two never play over each other.
+    pub fn open_source_monitor(&mut self, clip_id: ClipId) {
+        if !self.project.clips.contains_key(&clip_id) {
+            return;
+        }
+        self.project.playback.stop();
+        self.close_source_monitor();
+        let position = self
+            .ui
+            .preview
+            .source_positions
+            .get(&clip_id)
+            .copied()
+            .unwrap_or(0.0);
+        self.ui.preview.source_monitor = Some(SourceMonitor {
+            clip: clip_id,
+            position,
+            playing: true,
+            seek_to: None,
+        });
+    }
+
+    pub fn merge_selected_clips(&mut self) {
+        let Some((a, b)) = self.project.mergeable_selection(&self.ui.selection) else {
+            return;
+        };
+        self.project.snapshot_for_undo();
+        self.project.timeline.merge_adjacent(a, b);
+        let timeline = &self.project.timeline;
+        self.ui
+            .selection
+            .selected_timeline_clips
+            .retain(|&id| timeline.find_clip(id).is_some());
+    }
+
+    // Fits every instance of a source into its (possibly new, shorter) file. Instances that
+    // can't fit at all are removed, which is reported since it changes the edit.
+    pub fn clamp_source_range(&mut self, source_id: ClipId, source_duration: f64, now: f64) {
+        let removed = self
+            .project
+            .timeline
+            .clamp_source_range(source_id, source_duration);
+        if removed == 0 {
+            return;
+        }
+        let timeline = &self.project.timeline;
+        self.ui
+            .selection
+            .selected_timeline_clips
+            .retain(|&id| timeline.find_clip(id).is_some());
+        self.ui.toasts.push(
+            format!("Source is too short: removed {removed} clip(s) from the timeline"),
+            now,
+        );
+    }
+
+    // Ends a trim gesture. A trim that needed its bounds corrected is reported rather than
+    // silently fixed, since it means the trim went somewhere it shouldn't have.
+    pub fn finalize_trim(&mut self, clip_id: TimelineClipId, now: f64) {
+        if self.project.timeline.finalize_trim(clip_id) {
+            self.ui
+                .toasts
+                .push("Trim went out of range and was corrected", now);
+        }
+    }
+
+    pub fn copy_selected_clips(&mut self) {
+        let timeline = &self.project.timeline;
+        let clips: Vec<TimelineClip> = self
+            .ui
+            .selection
+            .selected_timeline_clips
+            .iter()
+            .filter_map(|&id| timeline.find_clip(id).map(|(_, _, tc)| tc.clone()))
+            .collect();
+        if !clips.is_empty() {
+            self.ui.clipboard = Some(clips);
+        }
+    }
+
+    pub fn paste_clipboard(&mut self) {
+        let Some(clips) = self.ui.clipboard.as_ref().filter(|c| !c.is_empty()) else {
+            return;
+        };
+        let token = self.project.begin_undo_gesture();
+        let playhead = self.project.playback.playhead;
+        let pasted = self.project.timeline.paste_clips(clips, playhead);
+        if pasted.is_empty() {
+            self.project.cancel_undo_gesture(token);
+            return;
+        }
+        self.project.end_undo_gesture(token);
+        self.ui.selection.selected_timeline_clips = pasted.into_iter().collect();
+    }
+
+    pub fn close_source_monitor(&mut self) {
+        if let Some(monitor) = self.ui.preview.source_monitor.take() {
+            let position = monitor.seek_to.unwrap_or(monitor.position);
+            self.ui
+                .preview
+                .source_positions
+                .insert(monitor.clip, position);
+        }
+    }
+
+    // Three-point insert: the marked source range goes onto the first track pair at the
+    // playhead, and whatever was there moves right to make room.
+    pub fn insert_source_at_playhead(&mut self) {
+        let Some(marks) = self.ui.selection.source_marks else {
+            return;
+        };
+        let Some(duration) = self.project.clips.get(&marks.clip).and_then(|c| c.duration) else {
+            return;
+        };
+        let Some(track_id) = self.project.timeline.video_tracks.first().map(|t| t.id) else {
+            return;
+        };
+        let (source_in, source_out) = marks.range(duration);
+        let playhead = self.project.playback.playhead;
+
+        self.project.snapshot_for_undo();
+        self.project
+            .timeline
+            .open_gap(playhead, source_out - source_in);
+        self.project.add_clip_range_to_track(
+            marks.clip,
+            track_id,
+            playhead,
+            (source_in, source_out),
+            self.ui.timeline.link_audio_on_drop,
+            self.ui.timeline.overlap,
+        );
+    }
+
     pub fn filtered_clips(&self) -> Vec<ClipId> {
         let query = self.ui.browser.search_query.to_lowercase();
         let tokens: Vec<&str> = query.split_whitespace().filter(|t| !t.is_empty()).collect();
@@ -327,3 +1371,144 @@ impl AppState {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn project_with_source(duration: f64, has_audio: bool) -> (ProjectState, ClipId) {
+        let mut project = ProjectState::default();
+        let mut clip = Clip::from_path(PathBuf::from("source.mov"));
+        clip.duration = Some(duration);
+        clip.has_audio = has_audio;
+        let id = clip.id;
+        project.clips.insert(id, clip);
+        project.clip_order.push(id);
+        (project, id)
+    }
+
+    fn track_clips(project: &ProjectState, track_id: TrackId) -> &[TimelineClip] {
+        &project.timeline.track_by_id(track_id).unwrap().clips
+    }
+
+    #[test]
+    fn drop_with_sound_places_a_linked_audio_clip_on_the_paired_track() {
+        let (mut project, source) = project_with_source(5.0, true);
+        let video = project.timeline.video_tracks[0].id;
+        let audio = project.timeline.paired_track_id(video).unwrap();
+
+        project.add_clip_to_track(source, video, 2.0, true, OverlapSettings::default());
+
+        let v = &track_clips(&project, video)[0];
+        let a = &track_clips(&project, audio)[0];
+        assert_eq!((v.timeline_start, v.duration), (2.0, 5.0));
+        assert_eq!((a.timeline_start, a.duration), (2.0, 5.0));
+        assert_eq!(v.linked_to, Some(a.id));
+        assert_eq!(a.linked_to, Some(v.id));
+    }
+
+    #[test]
+    fn playback_lookups_return_the_conformed_source_frame() {
+        let (mut project, source) = project_with_source(5.0, true);
+        project.settings.fps = 30.0;
+        project.clips.get_mut(&source).unwrap().fps = Some(24.0);
+        let video = project.timeline.video_tracks[0].id;
+        project.add_clip_to_track(source, video, 0.0, true, OverlapSettings::default());
+
+        let raw = project.timeline.video_clip_at_time(0.05).unwrap();
+        let video_hit = project.video_clip_at_time(0.05).unwrap();
+        let audio_hit = project.audio_clip_at_time(0.05).unwrap();
+        assert!((raw.source_time - 0.05).abs() < 1e-9);
+        assert!((video_hit.source_time - 1.0 / 24.0).abs() < 1e-9);
+        assert!((audio_hit.source_time - video_hit.source_time).abs() < 1e-9);
+    }
+
+    #[test]
+    fn match_frame_opens_the_source_paused_on_the_playhead_frame() {
+        let (project, source) = project_with_source(5.0, false);
+        let mut state = AppState {
+            project,
+            ..AppState::default()
+        };
+        let video = state.project.timeline.video_tracks[0].id;
+        state
+            .project
+            .add_clip_to_track(source, video, 2.0, false, OverlapSettings::default());
+        state.project.playback.playhead = 3.0;
+
+        state.match_frame();
+
+        assert_eq!(state.ui.selection.primary_clip(), Some(source));
+        let monitor = state.ui.preview.source_monitor.as_ref().unwrap();
+        assert_eq!(monitor.clip, source);
+        assert!(!monitor.playing);
+        assert!((monitor.seek_to.unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn video_only_drop_leaves_the_audio_track_empty() {
+        let (mut project, source) = project_with_source(5.0, true);
+        let video = project.timeline.video_tracks[0].id;
+        let audio = project.timeline.paired_track_id(video).unwrap();
+
+        project.add_clip_to_track(source, video, 0.0, false, OverlapSettings::default());
+
+        assert_eq!(track_clips(&project, video).len(), 1);
+        assert!(track_clips(&project, audio).is_empty());
+    }
+
+    #[test]
+    fn dropping_several_clips_in_a_row_keeps_them_back_to_back() {
+        let (mut project, first) = project_with_source(3.0, true);
+        let second = ClipId::new();
+        let mut clip = project.clips[&first].clone();
+        clip.id = second;
+        project.clips.insert(second, clip);
+        let video = project.timeline.video_tracks[0].id;
+
+        let mut cursor = 0.0;
+        for source in [first, second] {
+            project.add_clip_to_track(source, video, cursor, true, OverlapSettings::default());
+            cursor += project.clips[&source].duration.unwrap();
+        }
+
+        let starts: Vec<f64> = track_clips(&project, video)
+            .iter()
+            .map(|c| c.timeline_start)
+            .collect();
+        assert_eq!(starts, vec![0.0, 3.0]);
+    }
+
+    #[test]
+    fn trim_to_playhead_moves_the_edge_and_is_undoable() {
+        let (mut project, source) = project_with_source(10.0, true);
+        let video = project.timeline.video_tracks[0].id;
+        project.add_clip_to_track(source, video, 0.0, true, OverlapSettings::default());
+        let clip_id = track_clips(&project, video)[0].id;
+
+        project.playback.playhead = 4.0;
+        project.trim_clip_to_playhead(clip_id, TrimEdge::Right);
+        let (_, _, tc) = project.timeline.find_clip(clip_id).unwrap();
+        assert_eq!((tc.source_in, tc.source_out, tc.duration), (0.0, 4.0, 4.0));
+
+        project.undo();
+        let (_, _, tc) = project.timeline.find_clip(clip_id).unwrap();
+        assert_eq!(tc.duration, 10.0);
+    }
+
+    #[test]
+    fn trim_to_playhead_never_goes_below_the_minimum_length() {
+        let (mut project, source) = project_with_source(10.0, true);
+        let video = project.timeline.video_tracks[0].id;
+        project.add_clip_to_track(source, video, 2.0, true, OverlapSettings::default());
+        let clip_id = track_clips(&project, video)[0].id;
+
+        project.playback.playhead = 0.0;
+        project.trim_clip_to_playhead(clip_id, TrimEdge::Right);
+
+        let (_, _, tc) = project.timeline.find_clip(clip_id).unwrap();
+        assert!((tc.duration - MIN_CLIP_DURATION).abs() < 1e-9);
+    }
+}
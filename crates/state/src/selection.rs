@@ -1,7 +1,25 @@
 use std::collections::HashSet;
 
 use crate::clip::ClipId;
-use crate::timeline::TimelineClipId;
+use crate::timeline::{TimelineClipId, MIN_CLIP_DURATION};
+
+// Source in/out marks set on a browser clip, the "source" side of a three-point edit.
+// An unset mark falls back to that end of the clip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceMarks {
+    pub clip: ClipId,
+    pub source_in: Option<f64>,
+    pub source_out: Option<f64>,
+}
+
+impl SourceMarks {
+    pub fn range(&self, duration: f64) -> (f64, f64) {
+        let a = self.source_in.unwrap_or(0.0).clamp(0.0, duration);
+        let b = self.source_out.unwrap_or(duration).clamp(0.0, duration);
+        let (start, end) = if a <= b { (a, b) } else { (b, a) };
+        (start, end.max(start + MIN_CLIP_DURATION))
+    }
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct Selection {
@@ -10,6 +28,7 @@ pub struct Selection {
     pub hovered_clip: Option<ClipId>,
     pub selected_scrub_t: Option<f32>,
     pub selected_timeline_clips: HashSet<TimelineClipId>,
+    pub source_marks: Option<SourceMarks>,
 }
 
 impl Selection {
@@ -1,29 +1,95 @@
+// Tags are bits in a per-clip `u32` mask; the definitions give each bit a name and colour.
+pub const MAX_TAGS: usize = 32;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Tag {
-    BRoll,
-    VO,
-    Music,
-    Sfx,
-}
+pub struct Tag(u8);
 
 impl Tag {
-    pub const ALL: [Tag; 4] = [Tag::BRoll, Tag::VO, Tag::Music, Tag::Sfx];
+    pub fn bit(self) -> u32 {
+        1 << self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagDefinition {
+    pub tag: Tag,
+    pub name: String,
+    pub color: [u8; 3],
+}
 
-    pub fn label(self) -> &'static str {
-        match self {
-            Tag::BRoll => "B-roll",
-            Tag::VO => "VO",
-            Tag::Music => "Music",
-            Tag::Sfx => "SFX",
-        }
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagSet {
+    definitions: Vec<TagDefinition>,
+}
+
+impl Default for TagSet {
+    fn default() -> Self {
+        let mut tags = Self {
+            definitions: Vec::new(),
+        };
+        tags.add("B-roll", [90, 140, 220]);
+        tags.add("VO", [220, 120, 70]);
+        tags.add("Music", [170, 100, 210]);
+        tags.add("SFX", [60, 180, 170]);
+        tags
     }
+}
 
-    pub fn bit(self) -> u32 {
-        match self {
-            Tag::BRoll => 1 << 0,
-            Tag::VO => 1 << 1,
-            Tag::Music => 1 << 2,
-            Tag::Sfx => 1 << 3,
-        }
+impl TagSet {
+    pub fn iter(&self) -> impl Iterator<Item = &TagDefinition> {
+        self.definitions.iter()
+    }
+
+    pub fn get(&self, tag: Tag) -> Option<&TagDefinition> {
+        self.definitions.iter().find(|d| d.tag == tag)
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<Tag> {
+        self.definitions
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case(name))
+            .map(|d| d.tag)
+    }
+
+    pub fn get_mut(&mut self, tag: Tag) -> Option<&mut TagDefinition> {
+        self.definitions.iter_mut().find(|d| d.tag == tag)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.definitions.len() >= MAX_TAGS
+    }
+
+    pub fn add(&mut self, name: &str, color: [u8; 3]) -> Option<Tag> {
+        let used = self.used_mask();
+        let index = (0..MAX_TAGS as u8).find(|i| used & (1 << i) == 0)?;
+        let tag = Tag(index);
+        self.definitions.push(TagDefinition {
+            tag,
+            name: name.to_string(),
+            color,
+        });
+        Some(tag)
+    }
+
+    pub fn remove(&mut self, tag: Tag) {
+        self.definitions.retain(|d| d.tag != tag);
+    }
+
+    pub fn used_mask(&self) -> u32 {
+        self.definitions
+            .iter()
+            .fold(0, |mask, d| mask | d.tag.bit())
+    }
+
+    // The first tag in definition order wins, so reordering isn't needed to pick a colour.
+    pub fn primary(&self, mask: u32) -> Option<&TagDefinition> {
+        self.definitions.iter().find(|d| mask & d.tag.bit() != 0)
+    }
+
+    pub fn names(&self, mask: u32) -> impl Iterator<Item = &str> {
+        self.definitions
+            .iter()
+            .filter(move |d| mask & d.tag.bit() != 0)
+            .map(|d| d.name.as_str())
     }
 }
@@ -0,0 +1,103 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+// Written by the worker doing the job and read by the UI, so everything is atomic.
+#[derive(Debug)]
+pub struct BackgroundTask {
+    pub id: TaskId,
+    pub label: String,
+    progress: AtomicU32,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+    // Why the work gave up, for the UI to report once the task is pruned.
+    failure: Mutex<Option<String>>,
+}
+
+impl BackgroundTask {
+    pub fn progress(&self) -> f32 {
+        f32::from_bits(self.progress.load(Ordering::Relaxed))
+    }
+
+    pub fn set_progress(&self, fraction: f32) {
+        self.progress
+            .store(fraction.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn finish(&self) {
+        self.finished.store(true, Ordering::Release);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+
+    pub fn fail(&self, message: impl Into<String>) {
+        if let Ok(mut failure) = self.failure.lock() {
+            *failure = Some(message.into());
+        }
+        self.finish();
+    }
+}
+
+#[derive(Debug, Default)]
+struct TaskList {
+    next_id: u64,
+    tasks: Vec<Arc<BackgroundTask>>,
+}
+
+// Cloned into worker threads so they can register their own work; the UI thread lists
+// what's running and prunes what has finished.
+#[derive(Debug, Clone, Default)]
+pub struct BackgroundTasks {
+    inner: Arc<Mutex<TaskList>>,
+}
+
+impl BackgroundTasks {
+    pub fn start(&self, label: impl Into<String>) -> Arc<BackgroundTask> {
+        let mut list = self.inner.lock().expect("task list lock poisoned");
+        let task = Arc::new(BackgroundTask {
+            id: TaskId(list.next_id),
+            label: label.into(),
+            progress: AtomicU32::new(0f32.to_bits()),
+            cancelled: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            failure: Mutex::new(None),
+        });
+        list.next_id += 1;
+        list.tasks.push(Arc::clone(&task));
+        task
+    }
+
+    pub fn active(&self) -> Vec<Arc<BackgroundTask>> {
+        let list = self.inner.lock().expect("task list lock poisoned");
+        list.tasks
+            .iter()
+            .filter(|t| !t.is_finished())
+            .cloned()
+            .collect()
+    }
+
+    // Returns the failures of the tasks it drops, so they can be shown to the user.
+    pub fn prune_finished(&self) -> Vec<String> {
+        let mut list = self.inner.lock().expect("task list lock poisoned");
+        let (finished, running): (Vec<Arc<BackgroundTask>>, _) = std::mem::take(&mut list.tasks)
+            .into_iter()
+            .partition(|t| t.is_finished());
+        list.tasks = running;
+        finished
+            .iter()
+            .filter_map(|t| t.failure.lock().ok()?.take())
+            .collect()
+    }
+}
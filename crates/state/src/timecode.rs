@@ -0,0 +1,44 @@
+pub fn format_timecode(seconds: f64, fps: f64) -> String {
+    let fps_whole = nominal_fps(fps);
+    let total_frames = (seconds.max(0.0) * fps + 1e-6).floor() as u64;
+    let frames = total_frames % fps_whole;
+    let total_secs = total_frames / fps_whole;
+    format!(
+        "{:02}:{:02}:{:02}:{:02}",
+        total_secs / 3600,
+        (total_secs / 60) % 60,
+        total_secs % 60,
+        frames
+    )
+}
+
+// Accepts `HH:MM:SS:FF` and any shorter right-aligned form (`SS:FF`, `MM:SS:FF`).
+pub fn parse_timecode(text: &str, fps: f64) -> Option<f64> {
+    let fps_whole = nominal_fps(fps);
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    if parts.is_empty() || parts.len() > 4 {
+        return None;
+    }
+
+    let mut fields = [0u64; 4];
+    let offset = 4 - parts.len();
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        fields[offset + i] = part.parse().ok()?;
+    }
+
+    let [hours, minutes, secs, frames] = fields;
+    if frames >= fps_whole || (parts.len() > 2 && secs >= 60) || (parts.len() > 3 && minutes >= 60)
+    {
+        return None;
+    }
+
+    let total_frames = ((hours * 60 + minutes) * 60 + secs) * fps_whole + frames;
+    Some(total_frames as f64 / fps.max(1.0))
+}
+
+fn nominal_fps(fps: f64) -> u64 {
+    fps.round().max(1.0) as u64
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::clip::ClipId;
 use uuid::Uuid;
@@ -39,7 +39,36 @@ pub enum TrackKind {
     Audio,
 }
 
-#[derive(Debug, Clone)]
+// Where a video clip's picture sits in the output frame. The offset is a fraction of the
+// frame's width and height from centre, scale 1 fills the frame, rotation is in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub x: f32,
+    pub y: f32,
+    pub scale: f32,
+    pub rotation: f32,
+}
+
+pub const MIN_TRANSFORM_SCALE: f32 = 0.01;
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            scale: 1.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Transform {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct TimelineClip {
     pub id: TimelineClipId,
     pub source_id: ClipId,
@@ -49,9 +78,102 @@ pub struct TimelineClip {
     pub source_in: f64,
     pub source_out: f64,
     pub linked_to: Option<TimelineClipId>,
+    pub enabled: bool,
+    // 0 is fully transparent; only video clips use it.
+    pub opacity: f32,
+    pub transform: Transform,
+    // Seconds of ramp at each end, each at most the clip's length.
+    pub fade_in: f64,
+    pub fade_out: f64,
 }
 
-#[derive(Debug, Clone)]
+impl TimelineClip {
+    pub fn timeline_end(&self) -> f64 {
+        self.timeline_start + self.duration
+    }
+
+    // Clips cover the half-open interval [start, end), so a time exactly on a cut belongs to
+    // the clip that starts there. Both edges are pulled in by the same epsilon, which keeps
+    // that true when the end of one clip and the start of the next were summed differently.
+    pub fn contains_time(&self, time: f64) -> bool {
+        time >= self.timeline_start - BOUNDARY_EPSILON
+            && time < self.timeline_end() - BOUNDARY_EPSILON
+    }
+
+    pub fn fade_gain_at(&self, offset: f64) -> f64 {
+        fade_gain(self.duration, self.fade_in, self.fade_out, offset)
+    }
+
+    // Shortens this clip to end at `timeline_time` and returns the rest as a new clip with
+    // its own id, unlinked. A cut on either end (to within BOUNDARY_EPSILON) leaves the clip
+    // alone; anywhere inside it, however close to an edge, is cut.
+    pub fn split_at(&mut self, timeline_time: f64) -> Option<TimelineClip> {
+        let offset = timeline_time - self.timeline_start;
+        if offset <= BOUNDARY_EPSILON || self.duration - offset <= BOUNDARY_EPSILON {
+            return None;
+        }
+        let right = TimelineClip {
+            id: TimelineClipId::new(),
+            timeline_start: timeline_time,
+            duration: self.duration - offset,
+            source_in: self.source_in + offset,
+            linked_to: None,
+            fade_in: 0.0,
+            ..self.clone()
+        };
+        self.duration = offset;
+        self.source_out = self.source_in + offset;
+        self.fade_out = 0.0;
+        Some(right)
+    }
+
+    // The one place a clip's bounds are put back in order after an edit, whatever the edit
+    // got wrong: it can't start before zero (the part that would is cut off the front), its
+    // source range runs forwards (an empty or backwards one becomes MIN_CLIP_DURATION long),
+    // and its length is always that range. A value that isn't finite falls back to the
+    // nearest sane one. Returns whether anything beyond float noise was corrected, which
+    // means an edit upstream got it wrong.
+    pub fn validate_bounds(&mut self) -> bool {
+        let before = (
+            self.timeline_start,
+            self.duration,
+            self.source_in,
+            self.source_out,
+        );
+        if !self.timeline_start.is_finite() {
+            self.timeline_start = 0.0;
+        }
+        if !self.source_in.is_finite() {
+            self.source_in = 0.0;
+        }
+        self.source_in = self.source_in.max(0.0);
+        if self.timeline_start < 0.0 {
+            self.source_in -= self.timeline_start;
+            self.timeline_start = 0.0;
+        }
+        if !self.source_out.is_finite() {
+            self.source_out = self.source_in;
+        }
+        if self.source_out <= self.source_in + BOUNDARY_EPSILON {
+            self.source_out = self.source_in + MIN_CLIP_DURATION;
+        }
+        self.duration = self.source_out - self.source_in;
+
+        let after = (
+            self.timeline_start,
+            self.duration,
+            self.source_in,
+            self.source_out,
+        );
+        let changed = |a: f64, b: f64| a.is_nan() || (a - b).abs() > BOUNDARY_EPSILON;
+        changed(before.0, after.0)
+            || changed(before.1, after.1)
+            || changed(before.2, after.2)
+            || changed(before.3, after.3)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Track {
     pub id: TrackId,
     pub name: String,
@@ -59,6 +181,7 @@ pub struct Track {
     pub clips: Vec<TimelineClip>,
     pub muted: bool,
     pub visible: bool,
+    pub gain_db: f32,
 }
 
 impl Track {
@@ -70,6 +193,7 @@ impl Track {
             clips: Vec::new(),
             muted: false,
             visible: true,
+            gain_db: 0.0,
         }
     }
 
@@ -106,12 +230,18 @@ impl Track {
                     source_in: right_in,
                     source_out: clip.source_out,
                     linked_to: None,
+                    enabled: clip.enabled,
+                    opacity: clip.opacity,
+                    transform: clip.transform,
+                    fade_in: 0.0,
+                    fade_out: clip.fade_out,
                 };
                 splits.push(right);
 
                 let clip = &mut self.clips[i];
                 clip.duration = left_duration;
                 clip.source_out = clip.source_in + left_duration;
+                clip.fade_out = 0.0;
                 i += 1;
                 continue;
             }
@@ -146,9 +276,156 @@ pub struct PlayheadHit {
     pub source_time: f64,
 }
 
+pub const MIN_CLIP_DURATION: f64 = 0.1;
+
+// The level `offset` seconds into a clip under its linear fades, each clamped to the clip.
+// Where the two overlap on a short clip the lower wins, so the envelope peaks where they
+// cross instead of jumping.
+pub fn fade_gain(duration: f64, fade_in: f64, fade_out: f64, offset: f64) -> f64 {
+    let fade_in = fade_in.min(duration);
+    let fade_out = fade_out.min(duration);
+    let rise = if fade_in > 0.0 {
+        (offset / fade_in).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    let fall = if fade_out > 0.0 {
+        ((duration - offset) / fade_out).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    rise.min(fall)
+}
+
+// Where an audio source started within its timeline clip and the clip's fades, so whoever
+// mixes it (the live mixer, or an offline render) can follow the envelope from the samples
+// it has taken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceEnvelope {
+    start_offset: f64,
+    speed: f64,
+    duration: f64,
+    fade_in: f64,
+    fade_out: f64,
+}
+
+impl SourceEnvelope {
+    pub fn for_clip(clip: &TimelineClip, source_time: f64, speed: f64) -> Self {
+        Self {
+            start_offset: (source_time - clip.source_in).max(0.0),
+            speed,
+            duration: clip.duration,
+            fade_in: clip.fade_in,
+            fade_out: clip.fade_out,
+        }
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.fade_in <= 0.0 && self.fade_out <= 0.0
+    }
+
+    // `consumed` counts interleaved samples, so `samples_per_second` is rate times channels.
+    pub fn gain(&self, consumed: usize, samples_per_second: f64) -> f32 {
+        if self.is_flat() {
+            return 1.0;
+        }
+        let offset = self.start_offset + consumed as f64 / samples_per_second * self.speed;
+        fade_gain(self.duration, self.fade_in, self.fade_out, offset) as f32
+    }
+}
+
+// The last step of every mix: master level, then a hard limit so the sum can't wrap.
+pub fn finish_mix(buf: &mut [f32], master_gain: f32) {
+    for sample in buf.iter_mut() {
+        *sample = (*sample * master_gain).clamp(-1.0, 1.0);
+    }
+}
+
+pub const MIN_GAIN_DB: f32 = -60.0;
+pub const MAX_GAIN_DB: f32 = 12.0;
+
+// The bottom of the gain range is treated as fully silent rather than -60 dB.
+pub fn db_to_linear(gain_db: f32) -> f32 {
+    if gain_db <= MIN_GAIN_DB {
+        0.0
+    } else {
+        10f32.powf(gain_db / 20.0)
+    }
+}
+
 pub const DEFAULT_TRACK_PAIRS: usize = 3;
 
-#[derive(Debug, Clone)]
+const OVERLAP_EPSILON: f64 = 1e-9;
+// Lets a playhead parked on a boundary step past it instead of landing on it again.
+const EDIT_POINT_EPSILON: f64 = 1e-6;
+const BOUNDARY_EPSILON: f64 = 1e-9;
+// Splits land both halves on the same source time, but the timeline side is a sum that
+// can drift by a rounding error.
+const SEAM_EPSILON: f64 = 1e-6;
+
+// Two clips play as one when the second picks up on the same track, from the same source
+// and looking the same, exactly where the first leaves off.
+fn is_seamless(left: &TimelineClip, right: &TimelineClip) -> bool {
+    left.id != right.id
+        && left.track_id == right.track_id
+        && left.source_id == right.source_id
+        && left.enabled == right.enabled
+        && left.opacity == right.opacity
+        && left.transform == right.transform
+        && (left.timeline_end() - right.timeline_start).abs() < SEAM_EPSILON
+        && (left.source_out - right.source_in).abs() < SEAM_EPSILON
+}
+
+// What happens to clips a moved or dropped clip lands on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    // The incoming clip trims or removes whatever it covers.
+    Overwrite,
+    // The incoming clip shifts to the nearest gap that fits it.
+    NoOverlap,
+}
+
+impl OverlapPolicy {
+    pub const ALL: [OverlapPolicy; 2] = [OverlapPolicy::Overwrite, OverlapPolicy::NoOverlap];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OverlapPolicy::Overwrite => "Overwrite",
+            OverlapPolicy::NoOverlap => "No overlap",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.label() == label)
+    }
+}
+
+// Overlaps on audio tracks are usually mistakes, while video keeps them for crossfades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlapSettings {
+    pub video: OverlapPolicy,
+    pub audio: OverlapPolicy,
+}
+
+impl Default for OverlapSettings {
+    fn default() -> Self {
+        Self {
+            video: OverlapPolicy::Overwrite,
+            audio: OverlapPolicy::NoOverlap,
+        }
+    }
+}
+
+impl OverlapSettings {
+    pub fn for_kind(&self, kind: TrackKind) -> OverlapPolicy {
+        match kind {
+            TrackKind::Video => self.video,
+            TrackKind::Audio => self.audio,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Timeline {
     pub video_tracks: Vec<Track>,
     pub audio_tracks: Vec<Track>,
@@ -168,6 +445,86 @@ impl Timeline {
         }
     }
 
+    // The smallest shift to apply to every planned `(track, start, duration)` placement so
+    // none of them lands on a clip of a no-overlap track. Clips in `moving` don't count as
+    // obstacles. Placements move together so linked pairs stay in sync; if no shift fits,
+    // they stay put and overwrite.
+    pub fn no_overlap_shift(
+        &self,
+        placements: &[(TrackId, f64, f64)],
+        moving: &HashSet<TimelineClipId>,
+        overlap: OverlapSettings,
+    ) -> f64 {
+        let constrained: Vec<(&Track, f64, f64)> = placements
+            .iter()
+            .filter_map(|&(track_id, start, duration)| {
+                let track = self.track_by_id(track_id)?;
+                (overlap.for_kind(track.kind) == OverlapPolicy::NoOverlap)
+                    .then_some((track, start, duration))
+            })
+            .collect();
+        if constrained.is_empty() {
+            return 0.0;
+        }
+
+        let obstacles = |track: &'_ Track| {
+            track
+                .clips
+                .iter()
+                .filter(|c| !moving.contains(&c.id))
+                .map(|c| (c.timeline_start, c.timeline_start + c.duration))
+                .collect::<Vec<_>>()
+        };
+        let obstacles: Vec<Vec<(f64, f64)>> = constrained
+            .iter()
+            .map(|(track, _, _)| obstacles(track))
+            .collect();
+
+        let fits = |shift: f64| {
+            constrained
+                .iter()
+                .zip(&obstacles)
+                .all(|(&(_, start, duration), clips)| {
+                    let start = start + shift;
+                    start >= -OVERLAP_EPSILON
+                        && clips.iter().all(|&(c_start, c_end)| {
+                            start + duration <= c_start + OVERLAP_EPSILON
+                                || start >= c_end - OVERLAP_EPSILON
+                        })
+                })
+        };
+
+        let mut candidates = vec![0.0];
+        for (&(_, start, duration), clips) in constrained.iter().zip(&obstacles) {
+            for &(c_start, c_end) in clips {
+                candidates.push(c_start - duration - start);
+                candidates.push(c_end - start);
+            }
+        }
+        candidates.sort_by(|a, b| a.abs().total_cmp(&b.abs()));
+        candidates.into_iter().find(|&s| fits(s)).unwrap_or(0.0)
+    }
+
+    fn placement_with_linked(
+        &self,
+        clip_id: TimelineClipId,
+        track_id: TrackId,
+        position: f64,
+    ) -> (Vec<(TrackId, f64, f64)>, HashSet<TimelineClipId>) {
+        let mut placements = Vec::new();
+        let mut moving = HashSet::new();
+        if let Some((_, _, tc)) = self.find_clip(clip_id) {
+            placements.push((track_id, position.max(0.0), tc.duration));
+            moving.insert(clip_id);
+            if let Some((linked_track, _, linked)) = tc.linked_to.and_then(|id| self.find_clip(id))
+            {
+                placements.push((linked_track.id, position.max(0.0), linked.duration));
+                moving.insert(linked.id);
+            }
+        }
+        (placements, moving)
+    }
+
     pub fn all_tracks(&self) -> impl Iterator<Item = &Track> {
         self.video_tracks.iter().chain(self.audio_tracks.iter())
     }
@@ -229,10 +586,13 @@ impl Timeline {
         None
     }
 
+    // Disabled clips stay on the timeline but are skipped for playback and export, like a
+    // per-clip track mute. Every `*_at_time` lookup goes through here, so they all share
+    // the half-open boundary rule of `TimelineClip::contains_time`.
     fn clip_at_time_in_track(track: &Track, time: f64) -> Option<PlayheadHit> {
-        for tc in &track.clips {
-            if time >= tc.timeline_start && time < tc.timeline_start + tc.duration {
-                let source_time = tc.source_in + (time - tc.timeline_start);
+        for tc in track.clips.iter().filter(|tc| tc.enabled) {
+            if tc.contains_time(time) {
+                let source_time = tc.source_in + (time - tc.timeline_start).max(0.0);
                 return Some(PlayheadHit {
                     track_id: track.id,
                     clip: tc.clone(),
@@ -243,8 +603,10 @@ impl Timeline {
         None
     }
 
+    // Video tracks stack in index order, so the last one is in front and covers any clip
+    // beneath it. Lookups check front to back for that reason.
     pub fn video_clip_at_time(&self, time: f64) -> Option<PlayheadHit> {
-        for track in &self.video_tracks {
+        for track in self.video_tracks.iter().rev() {
             if !track.visible {
                 continue;
             }
@@ -289,6 +651,42 @@ impl Timeline {
         max_end
     }
 
+    // Keeps every instance of `source_id` inside a source of `source_duration` without moving
+    // it on the timeline. Instances that would collapse keep their length from the new tail,
+    // and none ends up shorter than MIN_CLIP_DURATION. A source too short for even that can't
+    // hold any instance, so they are removed; the count removed is returned.
+    pub fn clamp_source_range(&mut self, source_id: ClipId, source_duration: f64) -> usize {
+        let mut removed = 0;
+        for track in self.all_tracks_mut() {
+            let before = track.clips.len();
+            track.clips.retain(|tc| {
+                tc.source_id != source_id
+                    || tc.source_out <= source_duration
+                    || source_duration >= MIN_CLIP_DURATION
+            });
+            removed += before - track.clips.len();
+            for tc in track
+                .clips
+                .iter_mut()
+                .filter(|tc| tc.source_id == source_id)
+            {
+                if tc.source_out <= source_duration {
+                    continue;
+                }
+                let source_in = tc.source_in.min(source_duration);
+                if source_duration - source_in > 0.0 {
+                    tc.source_in = source_in;
+                } else {
+                    tc.source_in = (source_duration - tc.duration).max(0.0);
+                }
+                tc.source_in = tc.source_in.min(source_duration - MIN_CLIP_DURATION);
+                tc.source_out = source_duration;
+                tc.duration = tc.source_out - tc.source_in;
+            }
+        }
+        removed
+    }
+
     pub fn track_count(&self) -> usize {
         self.video_tracks.len() + self.audio_tracks.len()
     }
@@ -334,6 +732,7 @@ impl Timeline {
         track.resolve_overlaps(new_pos, new_pos + clip.duration);
         let mut moved = clip;
         moved.timeline_start = new_pos;
+        moved.validate_bounds();
         track.clips.push(moved);
         track
             .clips
@@ -345,7 +744,11 @@ impl Timeline {
         track_id: TrackId,
         clip_id: TimelineClipId,
         new_position: f64,
+        overlap: OverlapSettings,
     ) {
+        let (placements, moving) = self.placement_with_linked(clip_id, track_id, new_position);
+        let new_position =
+            new_position.max(0.0) + self.no_overlap_shift(&placements, &moving, overlap);
         self.move_clip_on_track_core(track_id, clip_id, new_position);
         let linked = self.find_clip(clip_id).and_then(|(_, _, c)| c.linked_to);
         if let Some(linked_id) = linked {
@@ -383,6 +786,7 @@ impl Timeline {
         dst.resolve_overlaps(new_pos, new_pos + clip.duration);
         clip.timeline_start = new_pos;
         clip.track_id = dst_track_id;
+        clip.validate_bounds();
         dst.clips.push(clip);
         dst.clips
             .sort_by(|a, b| a.timeline_start.total_cmp(&b.timeline_start));
@@ -393,7 +797,11 @@ impl Timeline {
         clip_id: TimelineClipId,
         dst_track_id: TrackId,
         new_position: f64,
+        overlap: OverlapSettings,
     ) {
+        let (placements, moving) = self.placement_with_linked(clip_id, dst_track_id, new_position);
+        let new_position =
+            new_position.max(0.0) + self.no_overlap_shift(&placements, &moving, overlap);
         self.move_clip_across_tracks_core(clip_id, dst_track_id, new_position);
         let linked = self.find_clip(clip_id).and_then(|(_, _, c)| c.linked_to);
         if let Some(linked_id) = linked {
@@ -404,7 +812,12 @@ impl Timeline {
         }
     }
 
-    pub fn move_clips_by_delta(&mut self, clips: &HashSet<TimelineClipId>, delta: f64) {
+    pub fn move_clips_by_delta(
+        &mut self,
+        clips: &HashSet<TimelineClipId>,
+        delta: f64,
+        overlap: OverlapSettings,
+    ) {
         let mut all_ids: HashSet<TimelineClipId> = HashSet::new();
         for &cid in clips {
             all_ids.insert(cid);
@@ -415,6 +828,18 @@ impl Timeline {
             }
         }
 
+        let placements: Vec<(TrackId, f64, f64)> = self
+            .all_tracks()
+            .flat_map(|track| {
+                track
+                    .clips
+                    .iter()
+                    .filter(|c| all_ids.contains(&c.id))
+                    .map(|c| (track.id, (c.timeline_start + delta).max(0.0), c.duration))
+            })
+            .collect();
+        let delta = delta + self.no_overlap_shift(&placements, &all_ids, overlap);
+
         let mut removed: Vec<(TrackId, TimelineClip)> = Vec::new();
         for track in self.all_tracks_mut() {
             let mut i = 0;
@@ -423,6 +848,7 @@ impl Timeline {
                     let mut clip = track.clips.remove(i);
                     let track_id = track.id;
                     clip.timeline_start = (clip.timeline_start + delta).max(0.0);
+                    clip.validate_bounds();
                     removed.push((track_id, clip));
                 } else {
                     i += 1;
@@ -460,7 +886,8 @@ impl Timeline {
         source_id: ClipId,
         track_id: TrackId,
         position_seconds: f64,
-        duration: f64,
+        source_in: f64,
+        source_out: f64,
     ) -> TimelineClipId {
         let id = TimelineClipId::new();
         let Some(track) = self.track_by_id_mut(track_id) else {
@@ -468,6 +895,7 @@ impl Timeline {
         };
 
         let pos = position_seconds.max(0.0);
+        let duration = source_out - source_in;
         track.resolve_overlaps(pos, pos + duration);
 
         track.clips.push(TimelineClip {
@@ -476,9 +904,14 @@ impl Timeline {
             track_id,
             timeline_start: pos,
             duration,
-            source_in: 0.0,
-            source_out: duration,
+            source_in,
+            source_out,
             linked_to: None,
+            enabled: true,
+            opacity: 1.0,
+            transform: Transform::default(),
+            fade_in: 0.0,
+            fade_out: 0.0,
         });
         track
             .clips
@@ -486,6 +919,54 @@ impl Timeline {
         id
     }
 
+    // Makes room for an insert edit: a clip spanning `at` is cut there, and everything from
+    // `at` onwards on every track moves right by `duration`, keeping the tracks in sync.
+    // Linked pairs cut this way stay linked on both sides of the gap.
+    pub fn open_gap(&mut self, at: f64, duration: f64) {
+        self.split_clip_at_playhead(at);
+        for track in self.all_tracks_mut() {
+            for tc in track.clips.iter_mut() {
+                if tc.timeline_start >= at {
+                    tc.timeline_start += duration;
+                }
+            }
+        }
+    }
+
+    // Cuts a clip at `time` into two that play exactly as the one did, returning the right
+    // half. A cut exactly on either end is skipped.
+    pub fn split_clip_at(&mut self, clip_id: TimelineClipId, time: f64) -> Option<TimelineClipId> {
+        let (track, idx) = self.find_clip_track_mut(clip_id)?;
+        let right = track.clips[idx].split_at(time)?;
+        let right_id = right.id;
+        track.clips.insert(idx + 1, right);
+        Some(right_id)
+    }
+
+    // "Cut everything here": splits every clip on every track under the playhead. Where
+    // both halves of a linked pair were cut, the right halves are linked to each other too.
+    pub fn split_clip_at_playhead(&mut self, playhead: f64) -> bool {
+        let under: Vec<(TimelineClipId, Option<TimelineClipId>)> = self
+            .all_tracks()
+            .flat_map(|track| track.clips.iter())
+            .filter(|tc| tc.contains_time(playhead))
+            .map(|tc| (tc.id, tc.linked_to))
+            .collect();
+        let mut rights: HashMap<TimelineClipId, TimelineClipId> = HashMap::new();
+        for &(id, _) in &under {
+            if let Some(right) = self.split_clip_at(id, playhead) {
+                rights.insert(id, right);
+            }
+        }
+        for &(id, linked) in &under {
+            let pair = linked.and_then(|l| Some((*rights.get(&id)?, *rights.get(&l)?)));
+            if let Some((right, linked_right)) = pair {
+                self.link_clips(right, linked_right);
+            }
+        }
+        !rights.is_empty()
+    }
+
     pub fn link_clips(&mut self, a: TimelineClipId, b: TimelineClipId) {
         if let Some((track, idx)) = self.find_clip_track_mut(a) {
             track.clips[idx].linked_to = Some(b);
@@ -495,6 +976,115 @@ impl Timeline {
         }
     }
 
+    // Pastes copies of `clips` with the earliest one starting at `at`, keeping their spacing,
+    // trims and links. Whatever the tracks' overlap policy, a paste never covers existing
+    // clips: if it would, the whole group moves later to the next gap that fits it.
+    pub fn paste_clips(&mut self, clips: &[TimelineClip], at: f64) -> Vec<TimelineClipId> {
+        let clips: Vec<&TimelineClip> = clips
+            .iter()
+            .filter(|c| self.track_by_id(c.track_id).is_some())
+            .collect();
+        let Some(earliest) = clips.iter().map(|c| c.timeline_start).reduce(f64::min) else {
+            return Vec::new();
+        };
+        let at = at.max(0.0);
+        let placements: Vec<(TrackId, f64, f64)> = clips
+            .iter()
+            .map(|c| (c.track_id, at + c.timeline_start - earliest, c.duration))
+            .collect();
+        let shift = self.next_free_shift(&placements);
+
+        let new_ids: HashMap<TimelineClipId, TimelineClipId> = clips
+            .iter()
+            .map(|c| (c.id, TimelineClipId::new()))
+            .collect();
+        for (clip, &(track_id, start, _)) in clips.iter().zip(&placements) {
+            let pasted = TimelineClip {
+                id: new_ids[&clip.id],
+                timeline_start: start + shift,
+                linked_to: clip.linked_to.and_then(|l| new_ids.get(&l).copied()),
+                ..(*clip).clone()
+            };
+            if let Some(track) = self.track_by_id_mut(track_id) {
+                track.clips.push(pasted);
+            }
+        }
+        for track in self.all_tracks_mut() {
+            track
+                .clips
+                .sort_by(|a, b| a.timeline_start.total_cmp(&b.timeline_start));
+        }
+        clips.iter().map(|c| new_ids[&c.id]).collect()
+    }
+
+    // The smallest non-negative shift that clears every clip on the placements' tracks. Each
+    // conflict pushes the group past the clip it hit, so this always ends.
+    fn next_free_shift(&self, placements: &[(TrackId, f64, f64)]) -> f64 {
+        let mut shift = 0.0;
+        loop {
+            let conflict_end = placements
+                .iter()
+                .filter_map(|&(track_id, start, duration)| {
+                    let track = self.track_by_id(track_id)?;
+                    let start = start + shift;
+                    track
+                        .clips
+                        .iter()
+                        .filter(|c| {
+                            start + duration > c.timeline_start + OVERLAP_EPSILON
+                                && start < c.timeline_start + c.duration - OVERLAP_EPSILON
+                        })
+                        .map(|c| c.timeline_start + c.duration - start)
+                        .reduce(f64::max)
+                })
+                .reduce(f64::max);
+            match conflict_end {
+                Some(push) => shift += push,
+                None => return shift,
+            }
+        }
+    }
+
+    // Linked partners follow, so a clip's picture and sound are switched off together.
+    pub fn set_clip_enabled(&mut self, clip_id: TimelineClipId, enabled: bool) {
+        let linked = self.find_clip(clip_id).and_then(|(_, _, tc)| tc.linked_to);
+        for id in std::iter::once(clip_id).chain(linked) {
+            if let Some((track, idx)) = self.find_clip_track_mut(id) {
+                track.clips[idx].enabled = enabled;
+            }
+        }
+    }
+
+    // Fades go on both halves of a linked pair so picture and sound ramp together.
+    pub fn set_clip_fades(&mut self, clip_id: TimelineClipId, fade_in: f64, fade_out: f64) {
+        let linked = self.find_clip(clip_id).and_then(|(_, _, tc)| tc.linked_to);
+        for id in std::iter::once(clip_id).chain(linked) {
+            if let Some((track, idx)) = self.find_clip_track_mut(id) {
+                let tc = &mut track.clips[idx];
+                tc.fade_in = fade_in.clamp(0.0, tc.duration);
+                tc.fade_out = fade_out.clamp(0.0, tc.duration);
+            }
+        }
+    }
+
+    pub fn set_clip_opacity(&mut self, clip_id: TimelineClipId, opacity: f32) {
+        if let Some((track, idx)) = self.find_clip_track_mut(clip_id) {
+            track.clips[idx].opacity = opacity.clamp(0.0, 1.0);
+        }
+    }
+
+    pub fn set_clip_transform(&mut self, clip_id: TimelineClipId, transform: Transform) {
+        if let Some((track, idx)) = self.find_clip_track_mut(clip_id) {
+            track.clips[idx].transform = Transform {
+                scale: transform.scale.max(MIN_TRANSFORM_SCALE),
+                ..transform
+            };
+        }
+    }
+
+    // Linked halves always come from the same source, so the partner takes the clip's
+    // bounds outright rather than the change to them. That holds for either half and for
+    // both edges, which keeps picture and sound frame-aligned however the pair is trimmed.
     pub fn sync_linked_clip(&mut self, clip_id: TimelineClipId, resolve_overlaps: bool) {
         let Some((_, _, clip)) = self.find_clip(clip_id) else {
             return;
@@ -554,7 +1144,11 @@ impl Timeline {
         }
     }
 
-    pub fn finalize_trim(&mut self, clip_id: TimelineClipId) {
+    // Returns whether the trimmed clip's bounds had to be corrected.
+    pub fn finalize_trim(&mut self, clip_id: TimelineClipId) -> bool {
+        let corrected = self
+            .find_clip_track_mut(clip_id)
+            .is_some_and(|(track, idx)| track.clips[idx].validate_bounds());
         if let Some((track, clip_idx, _)) = self.find_clip(clip_id) {
             let tc = &track.clips[clip_idx];
             let start = tc.timeline_start;
@@ -606,12 +1200,18 @@ impl Timeline {
                         source_in: right_in,
                         source_out: c.source_out,
                         linked_to: None,
+                        enabled: c.enabled,
+                        opacity: c.opacity,
+                        transform: c.transform,
+                        fade_in: 0.0,
+                        fade_out: c.fade_out,
                     };
                     splits.push(right);
 
                     let c = &mut track.clips[i];
                     c.duration = left_duration;
                     c.source_out = c.source_in + left_duration;
+                    c.fade_out = 0.0;
                     i += 1;
                     continue;
                 }
@@ -638,10 +1238,31 @@ impl Timeline {
                 .sort_by(|a, b| a.timeline_start.total_cmp(&b.timeline_start));
         }
         self.sync_linked_clip(clip_id, true);
+        corrected
     }
 }
 
 impl Timeline {
+    // Clip starts and ends on every track, hidden or muted included, since cuts there
+    // are still edits worth stepping through.
+    fn edit_points(&self) -> impl Iterator<Item = f64> + '_ {
+        self.all_tracks()
+            .flat_map(|track| track.clips.iter())
+            .flat_map(|tc| [tc.timeline_start, tc.timeline_start + tc.duration])
+    }
+
+    pub fn next_edit_point(&self, time: f64) -> Option<f64> {
+        self.edit_points()
+            .filter(|&t| t > time + EDIT_POINT_EPSILON)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    pub fn previous_edit_point(&self, time: f64) -> Option<f64> {
+        self.edit_points()
+            .filter(|&t| t < time - EDIT_POINT_EPSILON)
+            .max_by(|a, b| a.total_cmp(b))
+    }
+
     pub fn next_clip_after(&self, id: TimelineClipId) -> Option<PlayheadHit> {
         let (_, _, tc) = self.find_clip(id)?;
         let next_time = tc.timeline_start + tc.duration;
@@ -654,11 +1275,11 @@ impl Timeline {
         let mut best: Option<PlayheadHit> = None;
         let mut best_end = f64::NEG_INFINITY;
 
-        for track in &self.video_tracks {
+        for track in self.video_tracks.iter().rev() {
             if !track.visible {
                 continue;
             }
-            for tc in &track.clips {
+            for tc in track.clips.iter().filter(|tc| tc.enabled) {
                 let end = tc.timeline_start + tc.duration;
                 if end <= current_start && end > best_end {
                     let sample_t = (end - 0.001).max(tc.timeline_start);
@@ -680,11 +1301,11 @@ impl Timeline {
         let mut best: Option<PlayheadHit> = None;
         let mut best_start = f64::INFINITY;
 
-        for track in &self.video_tracks {
+        for track in self.video_tracks.iter().rev() {
             if !track.visible {
                 continue;
             }
-            for tc in &track.clips {
+            for tc in track.clips.iter().filter(|tc| tc.enabled) {
                 if tc.timeline_start > time && tc.timeline_start < best_start {
                     best_start = tc.timeline_start;
                     best = Some(PlayheadHit {
@@ -742,6 +1363,108 @@ impl Timeline {
         }
     }
 
+    // Removes the clip and its linked partner, then closes the gap each left on its own track
+    // (partners can be trimmed differently) by pulling every clip that starts at or after it
+    // left. Clips that started earlier stay put, and the pull never goes past the end of the
+    // last of them, so nothing lands on top of anything else.
+    pub fn ripple_delete(&mut self, clip_id: TimelineClipId) {
+        let Some((_, _, clip)) = self.find_clip(clip_id) else {
+            return;
+        };
+        let gaps: Vec<(TrackId, f64, f64)> = std::iter::once(clip_id)
+            .chain(clip.linked_to)
+            .filter_map(|id| self.find_clip(id))
+            .map(|(track, _, tc)| (track.id, tc.timeline_start, tc.duration))
+            .collect();
+
+        self.remove_clip(clip_id);
+        for (track_id, start, duration) in gaps {
+            let Some(track) = self.track_by_id_mut(track_id) else {
+                continue;
+            };
+            let is_later = |tc: &TimelineClip| tc.timeline_start >= start - BOUNDARY_EPSILON;
+            let earlier_end = track
+                .clips
+                .iter()
+                .filter(|tc| !is_later(tc))
+                .map(TimelineClip::timeline_end)
+                .fold(0.0, f64::max);
+            let Some(first_later) = track
+                .clips
+                .iter()
+                .filter(|tc| is_later(tc))
+                .map(|tc| tc.timeline_start)
+                .reduce(f64::min)
+            else {
+                continue;
+            };
+            let shift = duration.min(first_later - earlier_end).max(0.0);
+            for tc in track.clips.iter_mut().filter(|tc| is_later(tc)) {
+                tc.timeline_start -= shift;
+            }
+        }
+    }
+
+    // The left-to-right pairs merging `a` and `b` would join: theirs, and their linked
+    // partners', which have to be seamless too so picture and sound stay matched.
+    fn merge_pairs(
+        &self,
+        a: TimelineClipId,
+        b: TimelineClipId,
+    ) -> Option<Vec<(TimelineClipId, TimelineClipId)>> {
+        let (_, _, a) = self.find_clip(a)?;
+        let (_, _, b) = self.find_clip(b)?;
+        let (left, right) = if a.timeline_start <= b.timeline_start {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        if !is_seamless(left, right) {
+            return None;
+        }
+        let mut pairs = vec![(left.id, right.id)];
+        match (left.linked_to, right.linked_to) {
+            (None, None) => {}
+            (Some(left_link), Some(right_link)) => {
+                let (_, _, l) = self.find_clip(left_link)?;
+                let (_, _, r) = self.find_clip(right_link)?;
+                if !is_seamless(l, r) {
+                    return None;
+                }
+                pairs.push((left_link, right_link));
+            }
+            _ => return None,
+        }
+        Some(pairs)
+    }
+
+    pub fn can_merge(&self, a: TimelineClipId, b: TimelineClipId) -> bool {
+        self.merge_pairs(a, b).is_some()
+    }
+
+    // Joins two seamless clips, in either order, into the left one, taking linked partners
+    // along. The left clip keeps its fade in and takes the right one's fade out.
+    pub fn merge_adjacent(&mut self, a: TimelineClipId, b: TimelineClipId) -> bool {
+        let Some(pairs) = self.merge_pairs(a, b) else {
+            return false;
+        };
+        for (left_id, right_id) in pairs {
+            let Some((_, _, right)) = self.find_clip(right_id) else {
+                continue;
+            };
+            let (source_out, fade_out) = (right.source_out, right.fade_out);
+            let Some((track, idx)) = self.find_clip_track_mut(left_id) else {
+                continue;
+            };
+            let left = &mut track.clips[idx];
+            left.source_out = source_out;
+            left.duration = source_out - left.source_in;
+            left.fade_out = fade_out.min(left.duration);
+            track.clips.retain(|c| c.id != right_id);
+        }
+        true
+    }
+
     pub fn remove_clip_single(&mut self, clip_id: TimelineClipId) {
         let linked = self.find_clip(clip_id).and_then(|(_, _, c)| c.linked_to);
         for track in self.all_tracks_mut() {
@@ -760,3 +1483,408 @@ impl Default for Timeline {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track_ids(timeline: &Timeline) -> (TrackId, TrackId) {
+        (timeline.video_tracks[0].id, timeline.audio_tracks[0].id)
+    }
+
+    // A linked video/audio pair of one source covering `start..start + duration`.
+    fn add_linked_pair(
+        timeline: &mut Timeline,
+        start: f64,
+        duration: f64,
+    ) -> (TimelineClipId, TimelineClipId) {
+        let (video, audio) = track_ids(timeline);
+        let source = ClipId::new();
+        let v = timeline.add_clip_to_track(source, video, start, 0.0, duration);
+        let a = timeline.add_clip_to_track(source, audio, start, 0.0, duration);
+        timeline.link_clips(v, a);
+        (v, a)
+    }
+
+    fn clip(timeline: &Timeline, id: TimelineClipId) -> &TimelineClip {
+        timeline.find_clip(id).unwrap().2
+    }
+
+    #[test]
+    fn contains_time_is_half_open_at_the_clip_edges() {
+        let mut timeline = Timeline::new();
+        let (video, _) = track_ids(&timeline);
+        let id = timeline.add_clip_to_track(ClipId::new(), video, 2.0, 0.0, 3.0);
+        let tc = clip(&timeline, id);
+        let half = BOUNDARY_EPSILON / 2.0;
+
+        assert!(tc.contains_time(2.0));
+        assert!(!tc.contains_time(5.0));
+        assert!(tc.contains_time(2.0 - half));
+        assert!(tc.contains_time(2.0 + half));
+        assert!(!tc.contains_time(5.0 - half));
+        assert!(!tc.contains_time(5.0 + half));
+        assert!(tc.contains_time(5.0 - 2.0 * BOUNDARY_EPSILON));
+        assert!(!tc.contains_time(2.0 - 2.0 * BOUNDARY_EPSILON));
+    }
+
+    #[test]
+    fn a_cut_summed_differently_belongs_to_the_clip_after_it() {
+        let mut timeline = Timeline::new();
+        let (video, _) = track_ids(&timeline);
+        let source = ClipId::new();
+        let left = timeline.add_clip_to_track(source, video, 0.1, 0.0, 0.2);
+        let right = timeline.add_clip_to_track(source, video, 0.3, 0.0, 1.0);
+        let cut = clip(&timeline, left).timeline_end();
+        assert_ne!(cut, 0.3);
+
+        for time in [cut, 0.3] {
+            assert!(!clip(&timeline, left).contains_time(time));
+            assert!(clip(&timeline, right).contains_time(time));
+        }
+    }
+
+    #[test]
+    fn trimming_one_half_of_a_linked_pair_trims_the_other_to_match() {
+        let mut timeline = Timeline::new();
+        let (v, a) = add_linked_pair(&mut timeline, 1.0, 4.0);
+        let bounds =
+            |tc: &TimelineClip| (tc.timeline_start, tc.duration, tc.source_in, tc.source_out);
+
+        if let Some((track, idx)) = timeline.find_clip_track_mut(a) {
+            let tc = &mut track.clips[idx];
+            tc.timeline_start += 0.5;
+            tc.source_in += 0.5;
+            tc.source_out -= 1.0;
+            tc.duration -= 1.5;
+        }
+        timeline.sync_linked_clip(a, true);
+        assert_eq!(bounds(clip(&timeline, v)), (1.5, 2.5, 0.5, 3.0));
+        assert_eq!(bounds(clip(&timeline, v)), bounds(clip(&timeline, a)));
+
+        if let Some((track, idx)) = timeline.find_clip_track_mut(v) {
+            let tc = &mut track.clips[idx];
+            tc.source_out += 0.5;
+            tc.duration += 0.5;
+        }
+        timeline.sync_linked_clip(v, false);
+        assert_eq!(bounds(clip(&timeline, a)), (1.5, 3.0, 0.5, 3.5));
+        assert_eq!(bounds(clip(&timeline, v)), bounds(clip(&timeline, a)));
+    }
+
+    #[test]
+    fn insert_over_a_linked_pair_keeps_both_halves_linked() {
+        let mut timeline = Timeline::new();
+        let (v, a) = add_linked_pair(&mut timeline, 0.0, 4.0);
+
+        timeline.open_gap(1.0, 2.0);
+
+        assert_eq!(clip(&timeline, v).linked_to, Some(a));
+        assert_eq!(clip(&timeline, a).linked_to, Some(v));
+        let (video, audio) = track_ids(&timeline);
+        let right_v = &timeline.track_by_id(video).unwrap().clips[1];
+        let right_a = &timeline.track_by_id(audio).unwrap().clips[1];
+        assert_eq!(right_v.timeline_start, 3.0);
+        assert_eq!(right_a.timeline_start, 3.0);
+        assert_eq!(right_v.linked_to, Some(right_a.id));
+        assert_eq!(right_a.linked_to, Some(right_v.id));
+    }
+
+    fn validated(mutate: impl FnOnce(&mut TimelineClip)) -> (TimelineClip, bool) {
+        let mut timeline = Timeline::new();
+        let (video, _) = track_ids(&timeline);
+        let id = timeline.add_clip_to_track(ClipId::new(), video, 1.0, 2.0, 5.0);
+        let mut tc = clip(&timeline, id).clone();
+        mutate(&mut tc);
+        let corrected = tc.validate_bounds();
+        (tc, corrected)
+    }
+
+    #[test]
+    fn validate_bounds_leaves_a_sane_clip_alone() {
+        let (tc, corrected) = validated(|_| {});
+        assert!(!corrected);
+        assert_eq!((tc.timeline_start, tc.duration), (1.0, 3.0));
+    }
+
+    #[test]
+    fn validate_bounds_replaces_nan_with_sane_values() {
+        let (tc, corrected) = validated(|tc| {
+            tc.timeline_start = f64::NAN;
+            tc.source_in = f64::NAN;
+            tc.source_out = f64::NAN;
+            tc.duration = f64::NAN;
+        });
+        assert!(corrected);
+        assert_eq!(tc.timeline_start, 0.0);
+        assert_eq!(tc.source_in, 0.0);
+        assert_eq!(tc.source_out, MIN_CLIP_DURATION);
+        assert_eq!(tc.duration, MIN_CLIP_DURATION);
+    }
+
+    #[test]
+    fn validate_bounds_takes_the_length_from_the_source_range() {
+        let (tc, corrected) = validated(|tc| tc.duration = -2.0);
+        assert!(corrected);
+        assert_eq!(tc.duration, 3.0);
+    }
+
+    #[test]
+    fn validate_bounds_turns_a_backwards_source_range_into_the_minimum_clip() {
+        let (tc, corrected) = validated(|tc| tc.source_out = 1.0);
+        assert!(corrected);
+        assert_eq!(tc.source_in, 2.0);
+        assert_eq!(tc.source_out, 2.0 + MIN_CLIP_DURATION);
+        assert!((tc.duration - MIN_CLIP_DURATION).abs() < 1e-12);
+    }
+
+    #[test]
+    fn validate_bounds_keeps_a_short_forward_range() {
+        let (tc, corrected) = validated(|tc| {
+            tc.source_out = tc.source_in + 1.0 / 30.0;
+            tc.duration = 1.0 / 30.0;
+        });
+        assert!(!corrected);
+        assert!((tc.duration - 1.0 / 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn validate_bounds_cuts_off_what_starts_before_zero() {
+        let (tc, corrected) = validated(|tc| tc.timeline_start = -1.0);
+        assert!(corrected);
+        assert_eq!(
+            (tc.timeline_start, tc.source_in, tc.duration),
+            (0.0, 3.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn split_cuts_one_frame_inside_either_edge_but_not_on_it() {
+        let frame = 1.0 / 30.0;
+        let mut timeline = Timeline::new();
+        let (video, _) = track_ids(&timeline);
+        let id = timeline.add_clip_to_track(ClipId::new(), video, 1.0, 0.0, 3.0);
+
+        assert!(timeline.split_clip_at(id, 1.0).is_none());
+        assert!(timeline.split_clip_at(id, 4.0).is_none());
+        let right = timeline.split_clip_at(id, 1.0 + frame).unwrap();
+        assert!((clip(&timeline, id).duration - frame).abs() < 1e-9);
+        let tail = timeline.split_clip_at(right, 4.0 - frame).unwrap();
+        assert!((clip(&timeline, tail).duration - frame).abs() < 1e-9);
+        assert!((clip(&timeline, tail).source_in - (3.0 - frame)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn insert_one_frame_inside_a_clip_cuts_it_at_the_insert_point() {
+        let frame = 1.0 / 30.0;
+        let mut timeline = Timeline::new();
+        let (video, _) = track_ids(&timeline);
+        let id = timeline.add_clip_to_track(ClipId::new(), video, 1.0, 0.0, 3.0);
+
+        timeline.open_gap(1.0 + frame, 2.0);
+
+        let clips = &timeline.track_by_id(video).unwrap().clips;
+        assert_eq!(clips.len(), 2);
+        assert_eq!(clips[0].id, id);
+        assert_eq!(clips[0].timeline_start, 1.0);
+        assert!((clips[0].duration - frame).abs() < 1e-9);
+        assert!((clips[1].timeline_start - (3.0 + frame)).abs() < 1e-9);
+        assert!((clips[1].source_in - frame).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ripple_delete_closes_the_gap_on_the_same_track() {
+        let mut timeline = Timeline::new();
+        let (video, _) = track_ids(&timeline);
+        let source = ClipId::new();
+        let before = timeline.add_clip_to_track(source, video, 0.0, 0.0, 1.0);
+        let deleted = timeline.add_clip_to_track(source, video, 2.0, 0.0, 3.0);
+        let after = timeline.add_clip_to_track(source, video, 6.0, 0.0, 1.0);
+
+        timeline.ripple_delete(deleted);
+
+        assert!(timeline.find_clip(deleted).is_none());
+        assert_eq!(clip(&timeline, before).timeline_start, 0.0);
+        assert_eq!(clip(&timeline, after).timeline_start, 3.0);
+    }
+
+    #[test]
+    fn ripple_delete_shifts_each_linked_track_by_its_own_clip() {
+        let mut timeline = Timeline::new();
+        let (video, audio) = track_ids(&timeline);
+        let (v, a) = add_linked_pair(&mut timeline, 1.0, 4.0);
+        if let Some((track, idx)) = timeline.find_clip_track_mut(a) {
+            let tc = &mut track.clips[idx];
+            tc.source_out = 2.0;
+            tc.duration = 2.0;
+        }
+        let source = ClipId::new();
+        let video_after = timeline.add_clip_to_track(source, video, 6.0, 0.0, 1.0);
+        let audio_after = timeline.add_clip_to_track(source, audio, 6.0, 0.0, 1.0);
+
+        timeline.ripple_delete(v);
+
+        assert!(timeline.find_clip(a).is_none());
+        assert_eq!(clip(&timeline, video_after).timeline_start, 2.0);
+        assert_eq!(clip(&timeline, audio_after).timeline_start, 4.0);
+    }
+
+    #[test]
+    fn ripple_delete_leaves_earlier_clips_and_never_stacks_clips() {
+        let mut timeline = Timeline::new();
+        let (video, _) = track_ids(&timeline);
+        let source = ClipId::new();
+        let earlier = timeline.add_clip_to_track(source, video, 0.0, 0.0, 2.0);
+        let deleted = timeline.add_clip_to_track(source, video, 2.0, 0.0, 3.0);
+        let later = timeline.add_clip_to_track(source, video, 5.0, 0.0, 1.0);
+        if let Some((track, idx)) = timeline.find_clip_track_mut(earlier) {
+            track.clips[idx].duration = 2.5;
+        }
+
+        timeline.ripple_delete(deleted);
+
+        assert_eq!(clip(&timeline, earlier).timeline_start, 0.0);
+        assert_eq!(clip(&timeline, later).timeline_start, 2.5);
+    }
+
+    #[test]
+    fn replacing_with_a_shorter_source_keeps_instances_at_least_the_minimum_length() {
+        let mut timeline = Timeline::new();
+        let (video, _) = track_ids(&timeline);
+        let source = ClipId::new();
+        let id = timeline.add_clip_to_track(source, video, 2.0, 1.0, 5.0);
+
+        assert_eq!(timeline.clamp_source_range(source, 1.05), 0);
+
+        let tc = clip(&timeline, id);
+        assert_eq!(tc.timeline_start, 2.0);
+        assert_eq!(tc.source_out, 1.05);
+        assert!((tc.duration - MIN_CLIP_DURATION).abs() < 1e-9);
+        assert!((tc.source_in - (1.05 - MIN_CLIP_DURATION)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn replacing_with_a_source_too_short_for_any_clip_removes_its_instances() {
+        let mut timeline = Timeline::new();
+        let (v, a) = add_linked_pair(&mut timeline, 0.0, 3.0);
+        let (video, _) = track_ids(&timeline);
+        let other = timeline.add_clip_to_track(ClipId::new(), video, 4.0, 0.0, 1.0);
+        let source = clip(&timeline, v).source_id;
+
+        assert_eq!(timeline.clamp_source_range(source, 0.0), 2);
+
+        assert!(timeline.find_clip(v).is_none());
+        assert!(timeline.find_clip(a).is_none());
+        assert!(timeline.find_clip(other).is_some());
+    }
+
+    #[test]
+    fn source_envelope_follows_the_clip_fades_from_where_it_started() {
+        let mut timeline = Timeline::new();
+        let (_, audio) = track_ids(&timeline);
+        let id = timeline.add_clip_to_track(ClipId::new(), audio, 0.0, 10.0, 14.0);
+        timeline.set_clip_fades(id, 1.0, 2.0);
+        let tc = clip(&timeline, id);
+
+        let from_start = SourceEnvelope::for_clip(tc, tc.source_in, 1.0);
+        assert_eq!(from_start.gain(0, 100.0), 0.0);
+        assert_eq!(from_start.gain(50, 100.0), 0.5);
+        assert_eq!(from_start.gain(200, 100.0), 1.0);
+        let late_start = SourceEnvelope::for_clip(tc, tc.source_in + 3.0, 1.0);
+        assert_eq!(late_start.gain(50, 100.0), 0.25);
+    }
+
+    #[test]
+    fn finish_mix_applies_master_gain_and_limits() {
+        let mut buf = [0.25, 0.8, -0.9];
+        finish_mix(&mut buf, 2.0);
+        assert_eq!(buf, [0.5, 1.0, -1.0]);
+    }
+
+    #[test]
+    fn no_overlap_move_shifts_to_the_nearest_gap() {
+        let mut timeline = Timeline::new();
+        let (_, audio) = track_ids(&timeline);
+        let source = ClipId::new();
+        let fixed = timeline.add_clip_to_track(source, audio, 0.0, 0.0, 4.0);
+        let moved = timeline.add_clip_to_track(source, audio, 10.0, 0.0, 2.0);
+
+        timeline.move_clip_on_track(audio, moved, 3.0, OverlapSettings::default());
+
+        assert_eq!(clip(&timeline, moved).timeline_start, 4.0);
+        assert_eq!(clip(&timeline, fixed).duration, 4.0);
+    }
+
+    #[test]
+    fn overwrite_move_trims_the_covered_clip() {
+        let mut timeline = Timeline::new();
+        let (video, _) = track_ids(&timeline);
+        let source = ClipId::new();
+        let covered = timeline.add_clip_to_track(source, video, 0.0, 0.0, 4.0);
+        let moved = timeline.add_clip_to_track(source, video, 10.0, 0.0, 2.0);
+
+        timeline.move_clip_on_track(video, moved, 3.0, OverlapSettings::default());
+
+        assert_eq!(clip(&timeline, moved).timeline_start, 3.0);
+        assert_eq!(clip(&timeline, covered).duration, 3.0);
+    }
+
+    #[test]
+    fn linked_pair_moves_together_past_a_no_overlap_obstacle() {
+        let mut timeline = Timeline::new();
+        let (_, audio) = track_ids(&timeline);
+        timeline.add_clip_to_track(ClipId::new(), audio, 5.0, 0.0, 3.0);
+        let (v, a) = add_linked_pair(&mut timeline, 0.0, 2.0);
+
+        timeline.move_clip_on_track(audio, a, 6.0, OverlapSettings::default());
+
+        assert_eq!(clip(&timeline, a).timeline_start, 8.0);
+        assert_eq!(clip(&timeline, v).timeline_start, 8.0);
+    }
+
+    #[test]
+    fn dragging_a_selection_moves_it_with_linked_partners() {
+        let mut timeline = Timeline::new();
+        let (v1, a1) = add_linked_pair(&mut timeline, 0.0, 2.0);
+        let (v2, a2) = add_linked_pair(&mut timeline, 3.0, 2.0);
+
+        let dragged = HashSet::from([v1, v2]);
+        timeline.move_clips_by_delta(&dragged, 5.0, OverlapSettings::default());
+
+        for (id, start) in [(v1, 5.0), (a1, 5.0), (v2, 8.0), (a2, 8.0)] {
+            assert_eq!(clip(&timeline, id).timeline_start, start);
+        }
+    }
+
+    #[test]
+    fn dragging_to_another_track_takes_the_linked_partner_along_in_time() {
+        let mut timeline = Timeline::new();
+        let (v, a) = add_linked_pair(&mut timeline, 0.0, 2.0);
+        let dst = timeline.video_tracks[1].id;
+
+        timeline.move_clip_across_tracks(v, dst, 4.0, OverlapSettings::default());
+
+        let (track, _, moved) = timeline.find_clip(v).unwrap();
+        assert_eq!((track.id, moved.track_id), (dst, dst));
+        assert_eq!(moved.timeline_start, 4.0);
+        assert_eq!(clip(&timeline, a).timeline_start, 4.0);
+        assert!(timeline.video_tracks[0].clips.is_empty());
+    }
+
+    #[test]
+    fn paste_lands_in_the_next_free_gap_on_every_track() {
+        let mut timeline = Timeline::new();
+        let (v, a) = add_linked_pair(&mut timeline, 0.0, 2.0);
+        let copied = vec![clip(&timeline, v).clone(), clip(&timeline, a).clone()];
+
+        let pasted = timeline.paste_clips(&copied, 1.0);
+
+        assert_eq!(pasted.len(), 2);
+        for &id in &pasted {
+            assert_eq!(clip(&timeline, id).timeline_start, 2.0);
+        }
+        assert_eq!(clip(&timeline, pasted[0]).linked_to, Some(pasted[1]));
+        assert_eq!(clip(&timeline, pasted[1]).linked_to, Some(pasted[0]));
+        assert_eq!(clip(&timeline, v).duration, 2.0);
+    }
+}
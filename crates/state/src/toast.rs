@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+
+pub const TOAST_DURATION_S: f64 = 6.0;
+const MAX_TOASTS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub message: String,
+    pub created_at: f64,
+}
+
+impl Toast {
+    pub fn remaining(&self, now: f64) -> f64 {
+        (TOAST_DURATION_S - (now - self.created_at)).max(0.0)
+    }
+}
+
+// Short-lived notices shown over the editor, oldest first. A message that's already showing
+// is refreshed instead of stacked again, so a clip that keeps failing doesn't flood the list.
+#[derive(Debug, Default)]
+pub struct Toasts {
+    items: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, message: impl Into<String>, now: f64) {
+        let message = message.into();
+        self.items.retain(|t| t.message != message);
+        self.items.push_back(Toast {
+            message,
+            created_at: now,
+        });
+        while self.items.len() > MAX_TOASTS {
+            self.items.pop_front();
+        }
+    }
+
+    pub fn expire(&mut self, now: f64) {
+        self.items.retain(|t| t.remaining(now) > 0.0);
+    }
+
+    pub fn dismiss(&mut self, index: usize) {
+        self.items.remove(index);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.items.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    // Seconds until the next toast closes, for scheduling a repaint while nothing else is.
+    pub fn next_expiry(&self, now: f64) -> Option<f64> {
+        self.items
+            .iter()
+            .map(|t| t.remaining(now))
+            .min_by(f64::total_cmp)
+    }
+}
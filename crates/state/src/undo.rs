@@ -1,11 +1,32 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::clip::ClipId;
+use crate::tag::TagSet;
 use crate::timeline::Timeline;
 
 const MAX_HISTORY: usize = 100;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoCoalesceToken(u64);
+
+// Everything an edit can change: the timeline plus how clips are organised in the browser.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSnapshot {
+    pub timeline: Timeline,
+    pub starred: HashSet<ClipId>,
+    pub clip_tags: HashMap<ClipId, u32>,
+    pub tags: TagSet,
+}
+
 #[derive(Debug, Clone)]
 pub struct UndoManager {
-    undo_stack: Vec<Timeline>,
-    redo_stack: Vec<Timeline>,
+    undo_stack: Vec<ProjectSnapshot>,
+    redo_stack: Vec<ProjectSnapshot>,
+    // The state a gesture started from. It only becomes an undo step once the gesture ends
+    // having changed something, so a bare click neither evicts history nor clears redo.
+    coalesce_start: Option<ProjectSnapshot>,
+    coalesce_token: Option<UndoCoalesceToken>,
+    next_token: u64,
 }
 
 impl UndoManager {
@@ -13,29 +34,67 @@ impl UndoManager {
         Self {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            coalesce_start: None,
+            coalesce_token: None,
+            next_token: 0,
         }
     }
 
-    pub fn save(&mut self, snapshot: Timeline) {
-        self.undo_stack.push(snapshot);
-        self.redo_stack.clear();
-        if self.undo_stack.len() > MAX_HISTORY {
-            self.undo_stack.remove(0);
+    pub fn save(&mut self, snapshot: ProjectSnapshot) {
+        // A continuous gesture already captured its starting state; anything saved
+        // mid-gesture would split it into several undo steps.
+        if self.coalesce_token.is_some() {
+            return;
         }
+        push_bounded(&mut self.undo_stack, snapshot);
+        self.redo_stack.clear();
     }
 
-    pub fn undo(&mut self, current: Timeline) -> Option<Timeline> {
+    pub fn undo(&mut self, current: ProjectSnapshot) -> Option<ProjectSnapshot> {
         let previous = self.undo_stack.pop()?;
-        self.redo_stack.push(current);
+        push_bounded(&mut self.redo_stack, current);
         Some(previous)
     }
 
-    pub fn redo(&mut self, current: Timeline) -> Option<Timeline> {
+    pub fn redo(&mut self, current: ProjectSnapshot) -> Option<ProjectSnapshot> {
         let next = self.redo_stack.pop()?;
-        self.undo_stack.push(current);
+        push_bounded(&mut self.undo_stack, current);
         Some(next)
     }
 
+    pub fn begin_coalesce(&mut self, snapshot: ProjectSnapshot) -> UndoCoalesceToken {
+        self.coalesce_start = Some(snapshot);
+        self.next_token += 1;
+        let token = UndoCoalesceToken(self.next_token);
+        self.coalesce_token = Some(token);
+        token
+    }
+
+    // A gesture that ended where it started (a click on a trim handle, a zero-distance
+    // drag) leaves no undo step and keeps the redo history.
+    pub fn end_coalesce(&mut self, token: UndoCoalesceToken, current: &ProjectSnapshot) {
+        if self.coalesce_token != Some(token) {
+            return;
+        }
+        self.coalesce_token = None;
+        if let Some(start) = self.coalesce_start.take() {
+            if start != *current {
+                self.save(start);
+            }
+        }
+    }
+
+    pub fn cancel_coalesce(&mut self, token: UndoCoalesceToken) {
+        if self.coalesce_token == Some(token) {
+            self.coalesce_token = None;
+            self.coalesce_start = None;
+        }
+    }
+
+    pub fn is_coalescing(&self) -> bool {
+        self.coalesce_token.is_some()
+    }
+
     pub fn can_undo(&self) -> bool {
         !self.undo_stack.is_empty()
     }
@@ -45,8 +104,84 @@ impl UndoManager {
     }
 }
 
+// Both stacks drop their oldest snapshot once full, so a long session can't grow them forever.
+fn push_bounded(stack: &mut Vec<ProjectSnapshot>, snapshot: ProjectSnapshot) {
+    stack.push(snapshot);
+    if stack.len() > MAX_HISTORY {
+        stack.remove(0);
+    }
+}
+
 impl Default for UndoManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot_with_tag(name: &str) -> ProjectSnapshot {
+        let mut tags = TagSet::default();
+        tags.add(name, [0, 0, 0]);
+        ProjectSnapshot {
+            timeline: Timeline::new(),
+            starred: HashSet::new(),
+            clip_tags: HashMap::new(),
+            tags,
+        }
+    }
+
+    #[test]
+    fn multi_step_gesture_is_one_undo_step() {
+        let mut undo = UndoManager::new();
+        let start = snapshot_with_tag("start");
+        let token = undo.begin_coalesce(start.clone());
+        for step in ["a", "b", "c"] {
+            undo.save(snapshot_with_tag(step));
+        }
+        undo.end_coalesce(token, &snapshot_with_tag("end"));
+
+        assert_eq!(undo.undo(snapshot_with_tag("end")), Some(start));
+        assert!(!undo.can_undo());
+    }
+
+    #[test]
+    fn gesture_that_changes_nothing_leaves_no_undo_step() {
+        let mut undo = UndoManager::new();
+        let before = snapshot_with_tag("before");
+        let after = snapshot_with_tag("after");
+        undo.save(before.clone());
+        undo.undo(after.clone());
+        assert!(undo.can_redo());
+
+        let token = undo.begin_coalesce(before.clone());
+        undo.end_coalesce(token, &before);
+
+        assert!(!undo.can_undo());
+        assert_eq!(undo.redo(before), Some(after));
+    }
+
+    #[test]
+    fn gesture_that_changes_nothing_keeps_a_full_history_intact() {
+        let mut undo = UndoManager::new();
+        let first = snapshot_with_tag("0");
+        undo.save(first.clone());
+        for step in 1..MAX_HISTORY {
+            undo.save(snapshot_with_tag(&step.to_string()));
+        }
+        let current = snapshot_with_tag("current");
+
+        let token = undo.begin_coalesce(current.clone());
+        undo.end_coalesce(token, &current);
+        let token = undo.begin_coalesce(current.clone());
+        undo.cancel_coalesce(token);
+
+        let mut oldest = None;
+        while let Some(previous) = undo.undo(current.clone()) {
+            oldest = Some(previous);
+        }
+        assert_eq!(oldest, Some(first));
+    }
+}
@@ -7,12 +7,15 @@ use wizard_state::project::{AppState, SortMode};
 use wizard_state::tag::Tag;
 
 use crate::constants;
+use crate::file_manager;
+use crate::tag_manager;
 use crate::theme;
 use crate::TextureLookup;
 
 pub enum BrowserAction {
     None,
     ImportFolder(PathBuf),
+    ReplaceSource(ClipId, PathBuf),
 }
 
 pub fn browser_panel(
@@ -34,6 +37,11 @@ pub fn browser_panel(
                     action = BrowserAction::ImportFolder(path);
                 }
             }
+            ui.toggle_value(&mut state.ui.browser.quality_checks, "Quality checks")
+                .on_hover_text(
+                    "Flag clips with silent audio or black opening frames. \
+                     Costs extra decoding on import.",
+                );
         });
     });
     ui.separator();
@@ -59,16 +67,26 @@ pub fn browser_panel(
     ui.separator();
 
     ui.horizontal_wrapped(|ui| {
-        for tag in Tag::ALL {
-            let is_selected = (state.ui.browser.tag_filter_mask & tag.bit()) != 0;
-            if ui.selectable_label(is_selected, tag.label()).clicked() {
-                state.ui.browser.tag_filter_mask ^= tag.bit();
+        for def in state.project.tags.iter() {
+            let is_selected = (state.ui.browser.tag_filter_mask & def.tag.bit()) != 0;
+            if ui
+                .selectable_label(is_selected, tag_text(&def.name, def.color))
+                .clicked()
+            {
+                state.ui.browser.tag_filter_mask ^= def.tag.bit();
             }
         }
         if state.ui.browser.tag_filter_mask != 0 && ui.button("Clear").clicked() {
             state.ui.browser.tag_filter_mask = 0;
         }
+        if ui
+            .selectable_label(state.ui.browser.tag_manager_open, "Manage tags...")
+            .clicked()
+        {
+            state.ui.browser.tag_manager_open = !state.ui.browser.tag_manager_open;
+        }
     });
+    tag_manager::tag_manager_window(ui.ctx(), state);
     ui.horizontal(|ui| {
         let current_label = state.ui.browser.sort_mode.label();
         egui::ComboBox::from_id_salt("sort_mode")
@@ -123,8 +141,15 @@ pub fn browser_panel(
                 .spacing(vec2(constants::GRID_SPACING, constants::GRID_SPACING))
                 .show(ui, |ui| {
                     for (i, clip_id) in filtered.iter().enumerate() {
-                        is_any_tile_hovered |=
-                            clip_thumbnail(ui, *clip_id, thumb_size, state, textures, &filtered);
+                        is_any_tile_hovered |= clip_thumbnail(
+                            ui,
+                            *clip_id,
+                            thumb_size,
+                            state,
+                            textures,
+                            &filtered,
+                            &mut action,
+                        );
                         if (i + 1) % cols == 0 {
                             ui.end_row();
                         }
@@ -204,6 +229,7 @@ fn clip_thumbnail(
     state: &mut AppState,
     textures: &dyn TextureLookup,
     filtered: &[ClipId],
+    action: &mut BrowserAction,
 ) -> bool {
     let clip = match state.project.clips.get(&clip_id) {
         Some(c) => c,
@@ -213,6 +239,13 @@ fn clip_thumbnail(
     let duration = clip.duration;
     let resolution = clip.resolution;
     let is_audio_only = clip.audio_only;
+    let metadata_loaded = clip.metadata_loaded;
+    let resolution_badge = clip.resolution_badge();
+    let codec_badge = clip.codec_badge().map(str::to_string);
+    let quality_checks = state.ui.browser.quality_checks;
+    let silent_badge = (quality_checks && clip.silent).then_some("Silent");
+    let black_badge = (quality_checks && clip.black_opening).then_some("Black");
+    let resolution_mismatch = state.project.settings.resolution_mismatch(clip);
     let is_starred = state.project.starred.contains(&clip_id);
     let is_selected = state.ui.selection.is_clip_selected(clip_id);
     let is_primary = state.ui.selection.primary_clip() == Some(clip_id);
@@ -264,6 +297,11 @@ fn clip_thumbnail(
     let is_multi = multi_selected_count > 1;
     let right_clicked_in_multi = is_selected && is_multi;
 
+    if state.ui.browser.reveal_clip == Some(clip_id) {
+        ui.scroll_to_rect(rect, Some(egui::Align::Center));
+        state.ui.browser.reveal_clip = None;
+    }
+
     response.context_menu(|ui| {
         if right_clicked_in_multi {
             let all_starred = state
@@ -279,39 +317,26 @@ fn clip_thumbnail(
             };
             if ui.button(star_text).clicked() {
                 let ids: Vec<ClipId> = state.ui.selection.selected_clips.iter().copied().collect();
-                if all_starred {
-                    for id in ids {
-                        state.project.starred.remove(&id);
-                    }
-                } else {
-                    for id in ids {
-                        state.project.starred.insert(id);
-                    }
-                }
+                state.project.toggle_star_many(&ids);
                 ui.close_menu();
             }
 
             ui.separator();
             ui.label("Tags");
-            for tag in Tag::ALL {
+            for (tag, name, color) in tag_entries(state) {
                 let all_have_tag = state
                     .ui
                     .selection
                     .selected_clips
                     .iter()
                     .all(|id| (state.project.clip_tag_mask(*id) & tag.bit()) != 0);
-                if ui.selectable_label(all_have_tag, tag.label()).clicked() {
+                if ui
+                    .selectable_label(all_have_tag, tag_text(&name, color))
+                    .clicked()
+                {
                     let ids: Vec<ClipId> =
                         state.ui.selection.selected_clips.iter().copied().collect();
-                    for id in ids {
-                        if all_have_tag {
-                            let entry = state.project.clip_tags.entry(id).or_insert(0);
-                            *entry &= !tag.bit();
-                        } else {
-                            let entry = state.project.clip_tags.entry(id).or_insert(0);
-                            *entry |= tag.bit();
-                        }
-                    }
+                    state.project.toggle_tag_many(&ids, tag);
                 }
             }
         } else {
@@ -333,11 +358,29 @@ fn clip_thumbnail(
                 ui.close_menu();
             }
 
+            if ui.button("Replace Source...").clicked() {
+                if let Some(path) = rfd::FileDialog::new().pick_file() {
+                    *action = BrowserAction::ReplaceSource(clip_id, path);
+                }
+                ui.close_menu();
+            }
+
+            if let Some(path) = state.project.clips.get(&clip_id).map(|c| c.path.clone()) {
+                let reveal = egui::Button::new("Reveal in File Manager");
+                if ui.add_enabled(path.exists(), reveal).clicked() {
+                    file_manager::reveal_in_file_manager(&path);
+                    ui.close_menu();
+                }
+            }
+
             ui.separator();
             ui.label("Tags");
-            for tag in Tag::ALL {
+            for (tag, name, color) in tag_entries(state) {
                 let has_tag = (state.project.clip_tag_mask(clip_id) & tag.bit()) != 0;
-                if ui.selectable_label(has_tag, tag.label()).clicked() {
+                if ui
+                    .selectable_label(has_tag, tag_text(&name, color))
+                    .clicked()
+                {
                     state.project.toggle_tag(clip_id, tag);
                 }
             }
@@ -378,6 +421,9 @@ fn clip_thumbnail(
                 .map(|p| ((p.x - thumb_rect.left()) / thumb_rect.width()).clamp(0.0, 1.0));
             state.ui.selection.selected_scrub_t = hover_t.or(click_t);
         }
+        if response.double_clicked() && !is_audio_only {
+            state.open_source_monitor(clip_id);
+        }
 
         let is_selected = state.ui.selection.is_clip_selected(clip_id);
         let is_primary = state.ui.selection.primary_clip() == Some(clip_id);
@@ -517,6 +563,60 @@ fn clip_thumbnail(
             );
         }
 
+        if let (Some(marks), Some(dur)) = (state.ui.selection.source_marks, duration) {
+            if marks.clip == clip_id && dur > 0.0 {
+                let (source_in, source_out) = marks.range(dur);
+                let x = |t: f64| thumb_rect.left() + thumb_rect.width() * (t / dur) as f32;
+                let band = Rect::from_min_max(
+                    egui::pos2(x(source_in), thumb_rect.bottom() - 6.0),
+                    egui::pos2(
+                        x(source_out).min(thumb_rect.right()),
+                        thumb_rect.bottom() - 3.0,
+                    ),
+                );
+                ui.painter().rect_filled(band, 0.0, theme::STAR_COLOR);
+            }
+        }
+
+        if resolution_mismatch {
+            ui.painter()
+                .rect_filled(thumb_rect, theme::ROUNDING, Color32::from_black_alpha(100));
+        }
+        if metadata_loaded {
+            let mut badge_x = thumb_rect.left() + 4.0;
+            let resolution_color = if resolution_mismatch {
+                theme::BADGE_WARN
+            } else {
+                theme::TEXT_PRIMARY
+            };
+            let badges = [
+                (resolution_badge, resolution_color),
+                (codec_badge.as_deref(), theme::TEXT_PRIMARY),
+                (silent_badge, theme::BADGE_WARN),
+                (black_badge, theme::BADGE_WARN),
+            ];
+            for (badge, color) in badges {
+                let Some(badge) = badge else {
+                    continue;
+                };
+                badge_x += paint_badge(
+                    ui,
+                    egui::pos2(badge_x, thumb_rect.top() + 4.0),
+                    badge,
+                    color,
+                );
+            }
+        } else {
+            let spinner_size = 10.0;
+            let spinner_rect = Rect::from_min_size(
+                thumb_rect.left_top() + vec2(4.0, 4.0),
+                vec2(spinner_size, spinner_size),
+            );
+            egui::Spinner::new()
+                .size(spinner_size)
+                .paint_at(ui, spinner_rect);
+        }
+
         if is_starred {
             ui.painter().text(
                 thumb_rect.right_top() + vec2(-14.0, 4.0),
@@ -545,7 +645,7 @@ fn clip_thumbnail(
                         let tag_mask = state.project.clip_tag_mask(clip_id);
                         if let Some(clip) = state.project.clips.get_mut(&clip_id) {
                             clip.display_name = Some(new_name);
-                            clip.rebuild_search_haystack(tag_mask);
+                            clip.rebuild_search_haystack(&state.project.tags, tag_mask);
                         }
                     }
                 }
@@ -610,3 +710,30 @@ fn clip_thumbnail(
 
     is_hovered
 }
+
+// Returns the horizontal space used so badges can be laid out in a row.
+fn paint_badge(ui: &egui::Ui, pos: egui::Pos2, text: &str, color: Color32) -> f32 {
+    let galley =
+        ui.painter()
+            .layout_no_wrap(text.to_string(), egui::FontId::proportional(9.0), color);
+    let rect = Rect::from_min_size(pos, galley.size() + vec2(6.0, 2.0));
+    ui.painter()
+        .rect_filled(rect, theme::ROUNDING_SM, theme::BADGE_BG);
+    ui.painter()
+        .galley(rect.min + vec2(3.0, 1.0), galley, color);
+    rect.width() + 3.0
+}
+
+// Owned copies so menus can toggle tags on the project while listing them.
+pub(crate) fn tag_entries(state: &AppState) -> Vec<(Tag, String, [u8; 3])> {
+    state
+        .project
+        .tags
+        .iter()
+        .map(|def| (def.tag, def.name.clone(), def.color))
+        .collect()
+}
+
+pub(crate) fn tag_text(name: &str, color: [u8; 3]) -> egui::RichText {
+    egui::RichText::new(format!("\u{25CF} {name}")).color(theme::tag_color(color))
+}
@@ -6,3 +6,5 @@ pub const THUMB_SIZE: egui::Vec2 = vec2(140.0, 80.0);
 pub const GRID_SPACING: f32 = 8.0;
 pub const MIN_TILE_W: f32 = 140.0;
 pub const TRANSPORT_BTN_SIZE: egui::Vec2 = vec2(32.0, 26.0);
+pub const BUFFERING_SPINNER_SIZE: f32 = 24.0;
+pub const JOG_POINTS_PER_STEP: f32 = 40.0;
@@ -0,0 +1,25 @@
+use std::path::Path;
+use std::process::Command;
+
+// Opens the platform file manager with `path` selected. Offline paths are ignored.
+pub fn reveal_in_file_manager(path: &Path) {
+    if !path.exists() {
+        return;
+    }
+
+    #[cfg(target_os = "macos")]
+    let _ = Command::new("open").arg("-R").arg(path).spawn();
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut arg = std::ffi::OsString::from("/select,");
+        arg.push(path);
+        let _ = Command::new("explorer").arg(arg).spawn();
+    }
+
+    // xdg-open has no way to select a file, so open the containing folder instead.
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let _ = Command::new("xdg-open")
+        .arg(path.parent().unwrap_or(path))
+        .spawn();
+}
@@ -0,0 +1,56 @@
+use wizard_state::project::{AppState, FormatMismatch};
+
+use crate::theme;
+
+// Advisory only: playback and export still run, this just explains why they may look off.
+pub fn format_warning_banner(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    mismatches: &[FormatMismatch],
+) {
+    let (w, h) = state.project.settings.resolution();
+    let fps = state.project.settings.fps;
+    let heading = format!(
+        "\u{26A0} {} timeline clip{} differ from the project format ({w}x{h} @ {fps:.2} fps)",
+        mismatches.len(),
+        if mismatches.len() == 1 { "" } else { "s" },
+    );
+
+    egui::CollapsingHeader::new(egui::RichText::new(heading).color(theme::BADGE_WARN))
+        .id_salt("format_warning")
+        .show(ui, |ui| {
+            for mismatch in mismatches {
+                let Some(clip) = state.project.clips.get(&mismatch.source_id) else {
+                    continue;
+                };
+                let mut details = Vec::new();
+                if let Some(clip_fps) = mismatch.fps {
+                    details.push(format!("{clip_fps:.2} fps"));
+                }
+                if let Some((cw, ch)) = mismatch.resolution {
+                    details.push(format!("{cw}x{ch}"));
+                }
+                let name = clip.display_name().to_string();
+
+                ui.horizontal(|ui| {
+                    ui.colored_label(theme::TEXT_PRIMARY, name);
+                    ui.colored_label(theme::TEXT_DIM, details.join(", "));
+                    if ui.small_button("Jump to clip").clicked() {
+                        jump_to_clip(state, mismatch);
+                    }
+                });
+            }
+        });
+}
+
+fn jump_to_clip(state: &mut AppState, mismatch: &FormatMismatch) {
+    let Some((_, _, tc)) = state.project.timeline.find_clip(mismatch.timeline_clip) else {
+        return;
+    };
+    state.project.playback.playhead = tc.timeline_start;
+    state
+        .ui
+        .selection
+        .select_single_timeline_clip(mismatch.timeline_clip);
+    state.ui.selection.select_single(mismatch.source_id);
+}
@@ -1,12 +1,12 @@
 use wizard_state::clip::ClipId;
 use wizard_state::project::AppState;
-use wizard_state::tag::Tag;
-use wizard_state::timeline::TrackKind;
+use wizard_state::timeline::{TimelineClipId, TrackKind, Transform, MIN_TRANSFORM_SCALE};
 
+use crate::browser::{tag_entries, tag_text};
 use crate::theme;
 
-// FOURTH PANEL
 pub fn inspector_panel(ui: &mut egui::Ui, state: &mut AppState) {
+    let now = ui.input(|i| i.time);
     ui.heading("Inspector");
     ui.separator();
     show_project_summary(ui, state);
@@ -17,6 +17,7 @@ pub fn inspector_panel(ui: &mut egui::Ui, state: &mut AppState) {
 
     let Some(selected_clip) = selected_clip else {
         ui.colored_label(theme::TEXT_DIM, "Select a browser or timeline clip");
+        finish_edit(state, now);
         return;
     };
 
@@ -27,6 +28,7 @@ pub fn inspector_panel(ui: &mut egui::Ui, state: &mut AppState) {
         duration,
         resolution,
         codec,
+        channel_layout,
         audio_only,
         is_starred,
         tag_mask,
@@ -38,12 +40,14 @@ pub fn inspector_panel(ui: &mut egui::Ui, state: &mut AppState) {
             clip.duration,
             clip.resolution,
             clip.codec.clone().unwrap_or_else(|| "Unknown".to_string()),
+            clip.channel_layout(),
             clip.audio_only,
             state.project.starred.contains(&selected_clip),
             state.project.clip_tag_mask(selected_clip),
         ),
         None => {
             ui.colored_label(theme::TEXT_DIM, "Selected clip is no longer available");
+            finish_edit(state, now);
             return;
         }
     };
@@ -68,11 +72,13 @@ pub fn inspector_panel(ui: &mut egui::Ui, state: &mut AppState) {
     ui.add_space(6.0);
     ui.label("Tags");
     ui.horizontal_wrapped(|ui| {
-        for tag in Tag::ALL {
+        for (tag, name, color) in tag_entries(state) {
             let has_tag = (tag_mask & tag.bit()) != 0;
-            if ui.selectable_label(has_tag, tag.label()).clicked() {
+            if ui
+                .selectable_label(has_tag, tag_text(&name, color))
+                .clicked()
+            {
                 state.project.toggle_tag(selected_clip, tag);
-                rebuild_clip_search_haystack(state, selected_clip);
             }
         }
     });
@@ -94,6 +100,32 @@ pub fn inspector_panel(ui: &mut egui::Ui, state: &mut AppState) {
         },
     );
     ui.colored_label(theme::TEXT_DIM, format!("Codec: {codec}"));
+    if let Some(clip) = state.project.clips.get(&selected_clip) {
+        ui.horizontal(|ui| {
+            if !clip.metadata_loaded {
+                ui.spinner();
+                return;
+            }
+            for badge in [clip.resolution_badge(), clip.codec_badge()]
+                .into_iter()
+                .flatten()
+            {
+                ui.label(
+                    egui::RichText::new(badge)
+                        .small()
+                        .color(theme::TEXT_PRIMARY)
+                        .background_color(theme::BADGE_BG),
+                );
+            }
+        });
+        if state.project.settings.resolution_mismatch(clip) {
+            let (w, h) = state.project.settings.resolution();
+            ui.colored_label(
+                theme::BADGE_WARN,
+                format!("Differs from project resolution ({w}x{h})"),
+            );
+        }
+    }
     ui.colored_label(
         theme::TEXT_DIM,
         format!(
@@ -105,48 +137,198 @@ pub fn inspector_panel(ui: &mut egui::Ui, state: &mut AppState) {
             }
         ),
     );
+    if let Some(layout) = channel_layout {
+        ui.colored_label(theme::TEXT_DIM, format!("Audio: {layout}"));
+    }
 
+    let mut editing = false;
     if let Some(timeline_clip_id) = selected_timeline_clip {
         ui.separator();
-        if let Some((track, _, timeline_clip)) = state.project.timeline.find_clip(timeline_clip_id)
-        {
-            ui.label("Timeline Instance");
-            ui.colored_label(
-                theme::TEXT_DIM,
-                format!(
-                    "Track: {} ({})",
-                    track.name,
-                    match track.kind {
-                        TrackKind::Video => "video",
-                        TrackKind::Audio => "audio",
-                    }
-                ),
-            );
-            ui.colored_label(
-                theme::TEXT_DIM,
-                format!("Start: {:.2}s", timeline_clip.timeline_start),
-            );
-            ui.colored_label(
-                theme::TEXT_DIM,
-                format!("Duration: {:.2}s", timeline_clip.duration),
-            );
-            ui.colored_label(
-                theme::TEXT_DIM,
-                format!(
-                    "Source range: {:.2}s -> {:.2}s",
-                    timeline_clip.source_in, timeline_clip.source_out
-                ),
-            );
-            if ui.button("Jump Playhead To Clip Start").clicked() {
-                state.project.playback.playhead = timeline_clip.timeline_start;
-            }
+        editing = timeline_clip_editor(ui, state, timeline_clip_id);
+    }
+    if !editing {
+        finish_edit(state, now);
+    }
+}
+
+// Returns whether any field is still being dragged or typed in.
+fn timeline_clip_editor(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    timeline_clip_id: TimelineClipId,
+) -> bool {
+    let Some((track, _, timeline_clip)) = state.project.timeline.find_clip(timeline_clip_id) else {
+        return false;
+    };
+    let track_id = track.id;
+    let is_video = track.kind == TrackKind::Video;
+    let track_label = format!(
+        "Track: {} ({})",
+        track.name,
+        match track.kind {
+            TrackKind::Video => "video",
+            TrackKind::Audio => "audio",
         }
+    );
+    let tc = timeline_clip.clone();
+
+    ui.label("Timeline Instance");
+    ui.colored_label(theme::TEXT_DIM, track_label);
+    if tc.linked_to.is_some() {
+        ui.colored_label(theme::TEXT_DIM, "Linked to its audio/video partner");
+    }
+
+    let mut enabled = tc.enabled;
+    if ui.checkbox(&mut enabled, "Enabled").changed() {
+        state.project.snapshot_for_undo();
+        state
+            .project
+            .timeline
+            .set_clip_enabled(timeline_clip_id, enabled);
+    }
+
+    let mut start = tc.timeline_start;
+    let mut source_in = tc.source_in;
+    let mut source_out = tc.source_out;
+    let mut editing = false;
+    let mut changed = [false; 3];
+    egui::Grid::new("inspector_timeline_clip")
+        .num_columns(2)
+        .show(ui, |ui| {
+            let fields = [
+                ("Start", &mut start),
+                ("Source in", &mut source_in),
+                ("Source out", &mut source_out),
+            ];
+            for ((label, value), changed) in fields.into_iter().zip(changed.iter_mut()) {
+                ui.label(label);
+                let response = ui.add(seconds_field(value));
+                *changed = response.changed();
+                editing |= response.dragged() || response.has_focus();
+                ui.end_row();
+            }
+            ui.label("Duration");
+            ui.colored_label(theme::TEXT_DIM, format!("{:.3} s", tc.duration));
+            ui.end_row();
+            if is_video {
+                ui.label("Opacity");
+                let mut opacity = tc.opacity;
+                let response = ui.add(egui::Slider::new(&mut opacity, 0.0..=1.0).fixed_decimals(2));
+                if response.changed() {
+                    begin_edit(state);
+                    state
+                        .project
+                        .timeline
+                        .set_clip_opacity(timeline_clip_id, opacity);
+                }
+                editing |= response.dragged() || response.has_focus();
+                ui.end_row();
+
+                let mut transform = tc.transform;
+                let mut transform_changed = false;
+                let fields = [
+                    (
+                        "Position X",
+                        egui::DragValue::new(&mut transform.x)
+                            .speed(0.005)
+                            .max_decimals(3),
+                    ),
+                    (
+                        "Position Y",
+                        egui::DragValue::new(&mut transform.y)
+                            .speed(0.005)
+                            .max_decimals(3),
+                    ),
+                    (
+                        "Scale",
+                        egui::DragValue::new(&mut transform.scale)
+                            .speed(0.01)
+                            .range(MIN_TRANSFORM_SCALE..=10.0)
+                            .max_decimals(3),
+                    ),
+                    (
+                        "Rotation",
+                        egui::DragValue::new(&mut transform.rotation)
+                            .speed(0.5)
+                            .max_decimals(1)
+                            .suffix("\u{b0}"),
+                    ),
+                ];
+                for (label, field) in fields {
+                    ui.label(label);
+                    let response = ui.add(field);
+                    transform_changed |= response.changed();
+                    editing |= response.dragged() || response.has_focus();
+                    ui.end_row();
+                }
+                if transform_changed {
+                    begin_edit(state);
+                    state
+                        .project
+                        .timeline
+                        .set_clip_transform(timeline_clip_id, transform);
+                }
+            }
+        });
+    if is_video && !tc.transform.is_identity() && ui.button("Reset Transform").clicked() {
+        state.project.snapshot_for_undo();
+        state
+            .project
+            .timeline
+            .set_clip_transform(timeline_clip_id, Transform::default());
+    }
+
+    let [start_changed, in_changed, out_changed] = changed;
+    let range_changed = in_changed || out_changed;
+    if start_changed || range_changed {
+        begin_edit(state);
+    }
+    if start_changed {
+        let overlap = state.ui.timeline.overlap;
+        state
+            .project
+            .timeline
+            .move_clip_on_track(track_id, timeline_clip_id, start, overlap);
+    }
+    if range_changed {
+        state
+            .project
+            .set_clip_source_range(timeline_clip_id, source_in, source_out);
+        state.ui.inspector.trimmed_clip = Some(timeline_clip_id);
+    }
+
+    if ui.button("Jump Playhead To Clip Start").clicked() {
+        state.project.playback.playhead = tc.timeline_start;
+    }
+    editing
+}
+
+fn seconds_field(value: &mut f64) -> egui::DragValue<'_> {
+    egui::DragValue::new(value)
+        .speed(0.01)
+        .range(0.0..=f64::MAX)
+        .max_decimals(3)
+        .suffix(" s")
+}
+
+fn begin_edit(state: &mut AppState) {
+    if state.ui.inspector.undo_coalesce_token.is_none() {
+        state.ui.inspector.undo_coalesce_token = Some(state.project.begin_undo_gesture());
+    }
+}
+
+fn finish_edit(state: &mut AppState, now: f64) {
+    if let Some(clip_id) = state.ui.inspector.trimmed_clip.take() {
+        state.finalize_trim(clip_id, now);
+    }
+    if let Some(token) = state.ui.inspector.undo_coalesce_token.take() {
+        state.project.end_undo_gesture(token);
     }
 }
 
 fn selected_clip_id(
     state: &AppState,
-    selected_timeline_clip: Option<wizard_state::timeline::TimelineClipId>,
+    selected_timeline_clip: Option<TimelineClipId>,
 ) -> Option<ClipId> {
     if let Some(clip_id) = state.ui.selection.primary_clip() {
         return Some(clip_id);
@@ -160,13 +342,6 @@ fn selected_clip_id(
     })
 }
 
-fn rebuild_clip_search_haystack(state: &mut AppState, clip_id: ClipId) {
-    let tag_mask = state.project.clip_tag_mask(clip_id);
-    if let Some(clip) = state.project.clips.get_mut(&clip_id) {
-        clip.rebuild_search_haystack(tag_mask);
-    }
-}
-
 fn show_project_summary(ui: &mut egui::Ui, state: &AppState) {
     ui.label("Project");
     ui.colored_label(
@@ -1,10 +1,15 @@
 pub mod browser;
 pub mod constants;
-// FOURTH PANEL
+pub mod file_manager;
+pub mod format_warning;
 pub mod inspector;
 pub mod preview;
+pub mod project_settings;
+pub mod tag_manager;
+pub mod task_list;
 pub mod theme;
 pub mod timeline;
+pub mod toasts;
 pub mod waveform_gpu;
 
 use wizard_state::clip::ClipId;
@@ -14,7 +19,10 @@ pub trait TextureLookup {
     fn preview_frames(&self, id: &ClipId) -> Option<&Vec<egui::TextureHandle>>;
     fn is_pending(&self, id: &ClipId) -> bool;
     fn is_preview_loading(&self, id: &ClipId) -> bool;
-    fn waveform_peaks(&self, id: &ClipId) -> Option<&Vec<(f32, f32)>>;
+    fn waveform_peaks(&self, id: &ClipId) -> Option<&[Vec<(f32, f32)>]>;
+    fn stereo_waveform_peaks(&self, id: &ClipId) -> Option<[&[Vec<(f32, f32)>]; 2]>;
+    fn waveform_progress(&self, id: &ClipId) -> Option<f32>;
     fn playback_frame(&self) -> Option<&egui::TextureHandle>;
+    fn source_monitor_frame(&self) -> Option<&egui::TextureHandle>;
     fn scrub_frame_at_time(&self, id: &ClipId, source_time: f64) -> Option<&egui::TextureHandle>;
 }
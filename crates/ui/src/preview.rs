@@ -1,27 +1,76 @@
 use wizard_state::playback::PlaybackState;
-use wizard_state::project::AppState;
+use wizard_state::project::{AppState, PipelineHealth, PreviewScale};
+use wizard_state::timecode::{format_timecode, parse_timecode};
+use wizard_state::timeline::Transform;
 
 use crate::constants;
 use crate::theme;
 use crate::TextureLookup;
 
 pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn TextureLookup) {
+    if state.ui.preview.source_monitor.is_some() {
+        source_monitor_panel(ui, state, textures);
+        return;
+    }
     let available = ui.available_size();
+    let panel_rect = ui.available_rect_before_wrap();
 
     let is_active = state.project.playback.state != PlaybackState::Stopped
         || state.ui.timeline.scrubbing.is_some();
 
     let has_frame = textures.playback_frame().is_some();
+    let frame_size = project_frame_size(state);
+    let look = state
+        .project
+        .video_clip_at_time(state.project.playback.playhead)
+        .map_or(FrameLook::default(), |hit| FrameLook {
+            opacity: hit.clip.opacity,
+            transform: hit.clip.transform,
+        });
 
     let transport_height = 40.0;
     let video_area_height = available.y - transport_height;
+    if state.ui.preview.scale == PreviewScale::Fit {
+        let video_rect =
+            egui::Rect::from_min_size(panel_rect.min, egui::vec2(available.x, video_area_height));
+        jog_with_wheel(ui, state, video_rect);
+    }
 
     if has_frame {
         if let Some(tex) = textures.playback_frame() {
-            show_frame_texture(ui, tex, egui::vec2(available.x, video_area_height));
+            let video_area = egui::vec2(available.x, video_area_height);
+            match state.ui.preview.scale {
+                PreviewScale::Fit => show_frame_texture(ui, tex, frame_size, video_area, look),
+                PreviewScale::Native => {
+                    // Decoded frames are downscaled, so 100% means the project's own size.
+                    show_native_frame_texture(ui, tex, frame_size, video_area, look);
+                    let video_rect = egui::Rect::from_min_size(panel_rect.min, video_area);
+                    ui.painter().text(
+                        video_rect.left_top() + egui::vec2(8.0, 8.0),
+                        egui::Align2::LEFT_TOP,
+                        PreviewScale::Native.label(),
+                        egui::FontId::monospace(12.0),
+                        theme::TEXT_PRIMARY,
+                    );
+                }
+            }
         }
     } else if is_active {
-        show_black_frame(ui, egui::vec2(available.x, video_area_height));
+        // Before the first decoded frame lands, the clip thumbnail beats a black flash.
+        let thumbnail = state
+            .project
+            .video_clip_at_time(state.project.playback.playhead)
+            .and_then(|hit| textures.thumbnail(&hit.clip.source_id));
+        match thumbnail {
+            Some(tex) => show_frame_texture(
+                ui,
+                tex,
+                frame_size,
+                egui::vec2(available.x, video_area_height),
+                look,
+            ),
+            None => show_black_frame(ui, frame_size, egui::vec2(available.x, video_area_height)),
+        }
     } else if !is_active {
         match state.ui.selection.primary_clip() {
             Some(clip_id) => {
@@ -53,6 +102,22 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Tex
         }
     }
 
+    if let Some(health) = state.ui.preview.pipeline_health {
+        let video_rect =
+            egui::Rect::from_min_size(panel_rect.min, egui::vec2(available.x, video_area_height));
+        pipeline_status_dot(ui, health, video_rect);
+    }
+
+    if is_active && state.ui.preview.buffering {
+        let video_rect =
+            egui::Rect::from_min_size(panel_rect.min, egui::vec2(available.x, video_area_height));
+        let size = egui::Vec2::splat(constants::BUFFERING_SPINNER_SIZE);
+        egui::Spinner::new()
+            .size(constants::BUFFERING_SPINNER_SIZE)
+            .color(theme::TEXT_PRIMARY)
+            .paint_at(ui, egui::Rect::from_center_size(video_rect.center(), size));
+    }
+
     ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
         ui.add_space(4.0);
         transport_bar(ui, state);
@@ -60,11 +125,8 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Tex
 }
 
 fn transport_bar(ui: &mut egui::Ui, state: &mut AppState) {
-    let playhead = state.project.playback.playhead;
-    let minutes = (playhead / 60.0).floor() as i32;
-    let secs = playhead % 60.0;
-    let frames = ((secs.fract()) * 24.0).floor() as i32;
-    let timecode = format!("{minutes}:{:02}.{frames:02}", secs.floor() as i32);
+    let fps = state.project.settings.fps;
+    let timecode = format_timecode(state.project.playback.playhead, fps);
 
     let is_playing = state.project.playback.state == PlaybackState::Playing;
 
@@ -87,20 +149,243 @@ fn transport_bar(ui: &mut egui::Ui, state: &mut AppState) {
             .on_hover_text(if is_playing { "Pause" } else { "Play" })
             .clicked()
         {
-            state.project.playback.toggle_play();
+            let end = state.project.playback_end();
+            state.project.playback.toggle_play(end);
         }
 
         ui.add_space(8.0);
-        ui.label(
-            egui::RichText::new(timecode)
+        let mut text = state.ui.timeline.timecode_edit.clone().unwrap_or(timecode);
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut text)
                 .font(egui::FontId::monospace(12.0))
-                .color(theme::TEXT_PRIMARY),
+                .text_color(theme::TEXT_PRIMARY)
+                .desired_width(92.0),
         );
+        if response.has_focus() {
+            state.ui.timeline.timecode_edit = Some(text);
+        } else if response.lost_focus() {
+            // Anything that fails to parse falls back to the live playhead on the next frame.
+            if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                if let Some(t) = parse_timecode(&text, fps) {
+                    let duration = state.project.timeline.timeline_duration();
+                    state.project.playback.playhead = t.clamp(0.0, duration.max(0.0));
+                }
+            }
+            state.ui.timeline.timecode_edit = None;
+        }
+
+        let total = state.project.timeline.timeline_duration();
+        ui.colored_label(
+            theme::TEXT_DIM,
+            format!("/ {}", format_timecode(total, fps)),
+        )
+        .on_hover_text("Timeline duration");
+        if let Some(selected) = selection_duration(state) {
+            ui.colored_label(
+                theme::ACCENT,
+                format!("Sel {}", format_timecode(selected, fps)),
+            )
+            .on_hover_text("Selected duration");
+        }
+
+        ui.add_space(8.0);
+        for scale in PreviewScale::ALL {
+            ui.selectable_value(&mut state.ui.preview.scale, scale, scale.label())
+                .on_hover_text("Toggle with \\");
+        }
+    });
+}
+
+// Scrolling over the picture jogs the playhead a frame per notch, or a second with Shift,
+// and stops playback. The playhead is reported as scrubbed for that frame so the scrub path
+// decodes it sharp and plays a snippet of its sound. At native size the wheel is left to the
+// scroll area instead.
+fn jog_with_wheel(ui: &egui::Ui, state: &mut AppState, video_rect: egui::Rect) {
+    let id = egui::Id::new("preview_jog");
+    if !ui.rect_contains_pointer(video_rect) {
+        ui.data_mut(|d| d.remove::<f32>(id));
+        return;
+    }
+    // Shift turns the wheel sideways, so both axes count.
+    let (delta, shift) = ui.input(|i| (i.raw_scroll_delta, i.modifiers.shift));
+    let steps = ui.data_mut(|d| {
+        let pending = d.get_temp_mut_or_default::<f32>(id);
+        *pending -= delta.x + delta.y;
+        let steps = (*pending / constants::JOG_POINTS_PER_STEP).trunc();
+        *pending -= steps * constants::JOG_POINTS_PER_STEP;
+        steps
+    });
+    if steps == 0.0 {
+        return;
+    }
+
+    let settings = &state.project.settings;
+    let step = if shift {
+        1.0
+    } else {
+        settings.frame_duration()
+    };
+    let target = state.project.playback.playhead + steps as f64 * step;
+    let t = settings
+        .nearest_frame(target)
+        .clamp(0.0, state.project.playback_end());
+    state.project.playback.stop();
+    state.project.playback.playhead = t;
+    state.ui.timeline.scrubbing = Some(t);
+    state.ui.timeline.reveal_playhead = true;
+}
+
+// Green while frames are flowing, amber while the pipeline starts or catches up, red once
+// it has gone quiet long enough that the picture looks frozen.
+fn pipeline_status_dot(ui: &egui::Ui, health: PipelineHealth, video_rect: egui::Rect) {
+    let (color, text) = match health {
+        PipelineHealth::Delivering => (theme::STATUS_OK, "Playing: frames are arriving on time"),
+        PipelineHealth::StartingUp => (
+            theme::STATUS_WAIT,
+            "Starting up: the decoder is opening the clip",
+        ),
+        PipelineHealth::Stalled => (
+            theme::STATUS_WAIT,
+            "Catching up: the decoder is briefly behind",
+        ),
+        PipelineHealth::LongStall => (
+            theme::STATUS_STALL,
+            "Stalled: no new frames are arriving, so the picture is held",
+        ),
+    };
+    let center = video_rect.right_top() + egui::vec2(-12.0, 12.0);
+    let rect = egui::Rect::from_center_size(center, egui::Vec2::splat(12.0));
+    ui.painter().circle_filled(center, 4.0, color);
+    ui.interact(rect, egui::Id::new("pipeline_status"), egui::Sense::hover())
+        .on_hover_text(text);
+}
+
+// A browser clip looping by itself, shown at its own shape rather than framed by the
+// project, with a transport that only drives the monitor.
+fn source_monitor_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn TextureLookup) {
+    let Some(clip_id) = state.ui.preview.source_monitor.as_ref().map(|m| m.clip) else {
+        return;
+    };
+    let Some(clip) = state.project.clips.get(&clip_id) else {
+        state.close_source_monitor();
+        return;
+    };
+    let name = clip.display_name().to_string();
+    let duration = clip.duration.unwrap_or(0.0);
+    let resolution = clip.resolution;
+
+    let available = ui.available_size();
+    let panel_rect = ui.available_rect_before_wrap();
+    let transport_height = 40.0;
+    let video_area = egui::vec2(available.x, available.y - transport_height);
+
+    let frame = textures
+        .source_monitor_frame()
+        .or_else(|| textures.thumbnail(&clip_id));
+    let frame_size = match (resolution, frame) {
+        (Some((w, h)), _) => egui::vec2(w.max(1) as f32, h.max(1) as f32),
+        (None, Some(tex)) => tex.size_vec2(),
+        (None, None) => project_frame_size(state),
+    };
+    match frame {
+        Some(tex) => show_frame_texture(ui, tex, frame_size, video_area, FrameLook::default()),
+        None => show_black_frame(ui, frame_size, video_area),
+    }
+    ui.painter().text(
+        panel_rect.left_top() + egui::vec2(8.0, 8.0),
+        egui::Align2::LEFT_TOP,
+        format!("Source: {name}"),
+        egui::FontId::proportional(12.0),
+        theme::TEXT_PRIMARY,
+    );
+
+    ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+        ui.add_space(4.0);
+        source_monitor_transport(ui, state, duration);
     });
 }
 
-fn show_black_frame(ui: &mut egui::Ui, available: egui::Vec2) {
-    let aspect = 16.0 / 9.0;
+fn source_monitor_transport(ui: &mut egui::Ui, state: &mut AppState, duration: f64) {
+    let fps = state.project.settings.fps;
+    let Some(monitor) = state.ui.preview.source_monitor.as_mut() else {
+        return;
+    };
+    let mut close = false;
+
+    ui.horizontal(|ui| {
+        let btn = constants::TRANSPORT_BTN_SIZE;
+        let play_label = if monitor.playing {
+            "\u{23F8}"
+        } else {
+            "\u{25B6}"
+        };
+        if ui
+            .add_sized(btn, egui::Button::new(play_label))
+            .on_hover_text(if monitor.playing { "Pause" } else { "Play" })
+            .clicked()
+        {
+            monitor.playing = !monitor.playing;
+        }
+
+        let mut position = monitor.seek_to.unwrap_or(monitor.position);
+        let timecode = format!(
+            "{} / {}",
+            format_timecode(position, fps),
+            format_timecode(duration, fps)
+        );
+        ui.spacing_mut().slider_width = (ui.available_width() - 260.0).max(60.0);
+        let scrub = ui.add(
+            egui::Slider::new(&mut position, 0.0..=duration.max(0.0))
+                .show_value(false)
+                .trailing_fill(true),
+        );
+        if scrub.changed() {
+            monitor.seek_to = Some(position);
+        }
+        ui.colored_label(theme::TEXT_DIM, timecode);
+
+        if ui
+            .button("Back to Timeline")
+            .on_hover_text("Close the source monitor (Esc)")
+            .clicked()
+        {
+            close = true;
+        }
+    });
+
+    if close {
+        state.close_source_monitor();
+    }
+}
+
+// The clip being trimmed while a trim is under way, otherwise the span from the first
+// selected timeline clip to the end of the last.
+fn selection_duration(state: &AppState) -> Option<f64> {
+    let timeline = &state.project.timeline;
+    if let Some(trim) = &state.ui.timeline.trimming_clip {
+        return timeline
+            .find_clip(trim.clip_id)
+            .map(|(_, _, tc)| tc.duration);
+    }
+    state
+        .ui
+        .selection
+        .selected_timeline_clips
+        .iter()
+        .filter_map(|id| timeline.find_clip(*id))
+        .map(|(_, _, tc)| (tc.timeline_start, tc.timeline_end()))
+        .reduce(|(start, end), (s, e)| (start.min(s), end.max(e)))
+        .map(|(start, end)| end - start)
+}
+
+// The output frame in pixels. Everything the preview shows is framed by it, as export will be.
+fn project_frame_size(state: &AppState) -> egui::Vec2 {
+    let settings = &state.project.settings;
+    egui::vec2(settings.width.max(1) as f32, settings.height.max(1) as f32)
+}
+
+fn show_black_frame(ui: &mut egui::Ui, frame_size: egui::Vec2, available: egui::Vec2) {
+    let aspect = frame_size.x / frame_size.y;
     let video_h = available.y - 8.0;
     let w = (video_h * aspect).min(available.x);
     let h = w / aspect;
@@ -128,11 +413,37 @@ fn show_black_frame(ui: &mut egui::Ui, available: egui::Vec2) {
     });
 }
 
-fn show_frame_texture(ui: &mut egui::Ui, tex: &egui::TextureHandle, available: egui::Vec2) {
-    let tex_size = tex.size_vec2();
+fn show_native_frame_texture(
+    ui: &mut egui::Ui,
+    tex: &egui::TextureHandle,
+    frame_size: egui::Vec2,
+    available: egui::Vec2,
+    look: FrameLook,
+) {
+    let display_size = frame_size / ui.ctx().pixels_per_point();
+
+    ui.allocate_ui(available, |ui| {
+        egui::ScrollArea::both()
+            .id_salt("preview_native")
+            .max_width(available.x)
+            .max_height(available.y)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                frame_image(ui, tex, display_size, look);
+            });
+    });
+}
+
+fn show_frame_texture(
+    ui: &mut egui::Ui,
+    tex: &egui::TextureHandle,
+    frame_size: egui::Vec2,
+    available: egui::Vec2,
+    look: FrameLook,
+) {
     let video_h = available.y - 8.0;
-    let scale = (available.x / tex_size.x).min(video_h / tex_size.y);
-    let display_size = tex_size * scale;
+    let scale = (available.x / frame_size.x).min(video_h / frame_size.y);
+    let display_size = frame_size * scale;
 
     let vertical_pad = (available.y - display_size.y) / 2.0;
 
@@ -153,6 +464,44 @@ fn show_frame_texture(ui: &mut egui::Ui, tex: &egui::TextureHandle, available: e
             egui::StrokeKind::Outside,
         );
 
-        ui.image(egui::load::SizedTexture::new(tex.id(), display_size));
+        frame_image(ui, tex, display_size, look);
+    });
+}
+
+#[derive(Clone, Copy)]
+struct FrameLook {
+    opacity: f32,
+    transform: Transform,
+}
+
+impl Default for FrameLook {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            transform: Transform::default(),
+        }
+    }
+}
+
+// `size` is the output frame as displayed. The clip's picture is fitted inside it keeping its
+// own aspect, then placed by its transform and cropped to the frame. Whatever it leaves
+// uncovered (the letterbox or pillarbox bars included), or shows through when faded, is
+// black, as nothing is composited beneath it.
+fn frame_image(ui: &mut egui::Ui, tex: &egui::TextureHandle, size: egui::Vec2, look: FrameLook) {
+    let (canvas, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    ui.painter()
+        .rect_filled(canvas, egui::CornerRadius::ZERO, egui::Color32::BLACK);
+    let tex_size = tex.size_vec2();
+    let picture = tex_size * (size.x / tex_size.x).min(size.y / tex_size.y);
+    let alpha = (look.opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let transform = look.transform;
+    let center = canvas.center() + egui::vec2(transform.x, transform.y) * canvas.size();
+    let rect = egui::Rect::from_center_size(center, picture * transform.scale);
+    let image = egui::Image::new(egui::load::SizedTexture::new(tex.id(), picture))
+        .tint(egui::Color32::from_white_alpha(alpha))
+        .rotate(transform.rotation.to_radians(), egui::Vec2::splat(0.5));
+    ui.scope(|ui| {
+        ui.set_clip_rect(canvas.intersect(ui.clip_rect()));
+        image.paint_at(ui, rect);
     });
 }
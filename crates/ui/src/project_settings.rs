@@ -0,0 +1,76 @@
+use wizard_state::project::{AppState, FORMAT_PRESETS};
+
+use crate::theme;
+
+pub fn project_settings_window(ctx: &egui::Context, state: &mut AppState) {
+    let mut open = state.ui.project_settings_open;
+    egui::Window::new("Project settings")
+        .open(&mut open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            let settings = &mut state.project.settings;
+            let selected = settings.matching_preset().map_or("Custom", |p| p.name);
+            egui::ComboBox::from_label("Preset")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for preset in &FORMAT_PRESETS {
+                        if ui
+                            .selectable_label(selected == preset.name, preset.name)
+                            .clicked()
+                        {
+                            settings.apply_preset(preset);
+                        }
+                    }
+                });
+
+            let mut changed = false;
+            egui::Grid::new("project_settings_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Frame rate");
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut settings.fps)
+                                .range(1.0..=240.0)
+                                .speed(0.1)
+                                .max_decimals(3)
+                                .suffix(" fps"),
+                        )
+                        .changed();
+                    ui.end_row();
+
+                    ui.label("Width");
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut settings.width).range(16..=8192))
+                        .changed();
+                    ui.end_row();
+
+                    ui.label("Height");
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut settings.height).range(16..=8192))
+                        .changed();
+                    ui.end_row();
+
+                    // Not part of the format, so it doesn't mark the project configured.
+                    ui.label("Default fade");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.default_fade)
+                            .range(0.0..=10.0)
+                            .speed(0.01)
+                            .max_decimals(2)
+                            .suffix(" s"),
+                    );
+                    ui.end_row();
+                });
+            if changed {
+                settings.configured = true;
+            }
+
+            ui.colored_label(
+                theme::TEXT_DIM,
+                "Clips at other frame rates are conformed to the nearest frame.",
+            );
+        });
+    state.ui.project_settings_open = open;
+}
@@ -0,0 +1,77 @@
+use wizard_state::project::AppState;
+
+use crate::browser::{tag_entries, tag_text};
+use crate::theme;
+
+pub fn tag_manager_window(ctx: &egui::Context, state: &mut AppState) {
+    let mut open = state.ui.browser.tag_manager_open;
+    egui::Window::new("Tags")
+        .open(&mut open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            for (tag, name, color) in tag_entries(state) {
+                ui.horizontal(|ui| {
+                    let mut edited_color = color;
+                    if ui.color_edit_button_srgb(&mut edited_color).changed() {
+                        state.project.set_tag_color(tag, edited_color);
+                    }
+
+                    if state.ui.browser.renaming_tag == Some(tag) {
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut state.ui.browser.tag_rename_buffer)
+                                .desired_width(120.0),
+                        );
+                        if response.lost_focus() {
+                            let escaped = ui.input(|i| i.key_pressed(egui::Key::Escape));
+                            let new_name = state.ui.browser.tag_rename_buffer.trim().to_string();
+                            if !escaped && !new_name.is_empty() {
+                                state.project.rename_tag(tag, &new_name);
+                            }
+                            state.ui.browser.renaming_tag = None;
+                        } else {
+                            response.request_focus();
+                        }
+                    } else if ui
+                        .add(egui::Label::new(tag_text(&name, color)).sense(egui::Sense::click()))
+                        .on_hover_text("Double-click to rename")
+                        .double_clicked()
+                    {
+                        state.ui.browser.renaming_tag = Some(tag);
+                        state.ui.browser.tag_rename_buffer = name.clone();
+                    }
+
+                    let used_by = state
+                        .project
+                        .clip_tags
+                        .values()
+                        .filter(|mask| *mask & tag.bit() != 0)
+                        .count();
+                    ui.colored_label(theme::TEXT_DIM, format!("{used_by} clips"));
+
+                    if ui.small_button("Delete").clicked() {
+                        state.project.remove_tag(tag);
+                        state.ui.browser.tag_filter_mask &= !tag.bit();
+                    }
+                });
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgb(&mut state.ui.browser.new_tag_color);
+                ui.add(
+                    egui::TextEdit::singleline(&mut state.ui.browser.new_tag_name)
+                        .hint_text("New tag")
+                        .desired_width(120.0),
+                );
+                let name = state.ui.browser.new_tag_name.trim().to_string();
+                let can_add = !name.is_empty() && !state.project.tags.is_full();
+                if ui.add_enabled(can_add, egui::Button::new("Add")).clicked() {
+                    let color = state.ui.browser.new_tag_color;
+                    state.project.add_tag(&name, color);
+                    state.ui.browser.new_tag_name.clear();
+                }
+            });
+        });
+    state.ui.browser.tag_manager_open = open;
+}
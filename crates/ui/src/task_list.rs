@@ -0,0 +1,26 @@
+use wizard_state::task::BackgroundTasks;
+
+use crate::theme;
+
+const TASK_BAR_WIDTH: f32 = 110.0;
+
+pub fn task_list(ui: &mut egui::Ui, tasks: &BackgroundTasks) {
+    for task in tasks.active() {
+        ui.push_id(task.id, |ui| {
+            ui.colored_label(theme::TEXT_DIM, &task.label);
+            ui.add(
+                egui::ProgressBar::new(task.progress())
+                    .desired_width(TASK_BAR_WIDTH)
+                    .show_percentage(),
+            );
+            let cancelling = task.is_cancelled();
+            if ui
+                .add_enabled(!cancelling, egui::Button::new("\u{2715}").small())
+                .on_hover_text(if cancelling { "Cancelling" } else { "Cancel" })
+                .clicked()
+            {
+                task.cancel();
+            }
+        });
+    }
+}
@@ -10,17 +10,30 @@ pub const TRACK_BG: Color32 = Color32::from_rgb(28, 28, 32);
 pub const CLIP_VIDEO: Color32 = Color32::from_rgb(82, 120, 190);
 pub const CLIP_AUDIO: Color32 = Color32::from_rgb(80, 160, 100);
 pub const PLAYHEAD_COLOR: Color32 = Color32::from_rgb(235, 64, 52);
+pub const ACTIVE_CLIP_OUTLINE: Color32 = Color32::from_rgb(245, 150, 140);
 pub const STAR_COLOR: Color32 = Color32::from_rgb(255, 200, 50);
 pub const TRACK_HEADER_BG: Color32 = Color32::from_rgb(38, 38, 42);
 pub const RULER_BG: Color32 = Color32::from_rgb(24, 24, 28);
 pub const RULER_TICK: Color32 = Color32::from_rgb(65, 65, 70);
 pub const WAVEFORM_COLOR: Color32 = Color32::from_rgba_premultiplied(120, 200, 255, 230);
+pub const WAVEFORM_LOADING: Color32 = Color32::from_black_alpha(90);
+pub const FADE_LINE: Color32 = Color32::from_rgba_premultiplied(230, 230, 230, 200);
 pub const BG_HOVER: Color32 = Color32::from_rgb(50, 50, 55);
 pub const BORDER: Color32 = Color32::from_rgb(55, 55, 60);
+pub const BADGE_BG: Color32 = Color32::from_black_alpha(170);
+pub const BADGE_WARN: Color32 = Color32::from_rgb(240, 160, 60);
+pub const DROP_REJECT: Color32 = Color32::from_rgb(220, 70, 60);
+pub const STATUS_OK: Color32 = Color32::from_rgb(80, 200, 110);
+pub const STATUS_WAIT: Color32 = Color32::from_rgb(240, 180, 50);
+pub const STATUS_STALL: Color32 = Color32::from_rgb(230, 60, 50);
 
 pub const ROUNDING: CornerRadius = CornerRadius::same(4);
 pub const ROUNDING_SM: CornerRadius = CornerRadius::same(3);
 
+pub fn tag_color(color: [u8; 3]) -> Color32 {
+    Color32::from_rgb(color[0], color[1], color[2])
+}
+
 pub fn apply_theme(ctx: &egui::Context) {
     let mut style = Style::default();
     let mut visuals = Visuals::dark();
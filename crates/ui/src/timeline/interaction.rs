@@ -1,5 +1,9 @@
+use std::collections::HashSet;
+
 use egui::{pos2, CursorIcon, Rect, Stroke};
-use wizard_state::project::{AppState, TrimEdge};
+use wizard_state::playback::PlaybackState;
+use wizard_state::project::{AppState, TrimEdge, ZoomAnchor};
+use wizard_state::timeline::TimelineClipId;
 
 use crate::theme;
 
@@ -7,6 +11,7 @@ use super::layout::{
     build_track_layout, snap_time_to_clip_boundaries_with_duration, MIN_CLIP_DURATION,
     TRACK_HEIGHT, ZOOM_MAX, ZOOM_MIN,
 };
+use super::rendering::draw_snap_guide;
 
 pub fn handle_clip_trim(
     ui: &egui::Ui,
@@ -25,8 +30,11 @@ pub fn handle_clip_trim(
         let trim_clip_id = trim.clip_id;
         state.ui.timeline.trimming_clip = None;
 
-        state.project.snapshot_for_undo();
-        state.project.timeline.finalize_trim(trim_clip_id);
+        let now = ui.input(|i| i.time);
+        state.finalize_trim(trim_clip_id, now);
+        if let Some(token) = state.ui.timeline.undo_coalesce_token.take() {
+            state.project.end_undo_gesture(token);
+        }
         return;
     }
 
@@ -36,7 +44,7 @@ pub fn handle_clip_trim(
         return;
     };
 
-    let pointer_time = ((pointer.x - content_left + scroll) / pps).max(0.0) as f64;
+    let raw_pointer_time = ((pointer.x - content_left + scroll) / pps).max(0.0) as f64;
     let trim_clip_id = trim.clip_id;
     let edge = trim.edge;
     let original_position = trim.original_position;
@@ -51,6 +59,14 @@ pub fn handle_clip_trim(
         .and_then(|clip| clip.duration);
     let max_source_out = source_clip_duration
         .unwrap_or(original_out_point.max(original_in_point + original_duration));
+    let (pointer_time, snap_guide) = snap_time_to_clip_boundaries_with_duration(
+        state,
+        raw_pointer_time,
+        pps,
+        Some(trim_clip_id),
+        None,
+        true,
+    );
 
     if let Some((track, clip_idx)) = state.project.timeline.find_clip_track_mut(trim_clip_id) {
         let tc = &mut track.clips[clip_idx];
@@ -66,13 +82,14 @@ pub fn handle_clip_trim(
             TrimEdge::Left => {
                 let delta = pointer_time - original_position;
                 let max_delta = original_duration - MIN_CLIP_DURATION;
-                let min_delta = -original_in_point;
+                let min_delta = -original_in_point.min(original_position);
                 let clamped_delta = delta.clamp(min_delta, max_delta);
                 tc.timeline_start = original_position + clamped_delta;
                 tc.source_in = original_in_point + clamped_delta;
                 tc.duration = original_duration - clamped_delta;
             }
         }
+        tc.validate_bounds();
 
         let trim_x = match edge {
             TrimEdge::Left => content_left + tc.timeline_start as f32 * pps - scroll,
@@ -83,6 +100,14 @@ pub fn handle_clip_trim(
         let total_tracks = state.project.timeline.track_count();
         let line_top = tracks_top;
         let line_bottom = tracks_top + total_tracks as f32 * (TRACK_HEIGHT + 2.0);
+        // Clamping can hold the edge short of the snap target; only show the guide when the
+        // edge actually landed on it.
+        if let Some(guide) = snap_guide {
+            let guide_x = content_left + guide.time as f32 * pps - scroll;
+            if (guide_x - trim_x).abs() < 0.5 {
+                draw_snap_guide(ui.painter(), guide, guide_x, line_top, line_bottom);
+            }
+        }
         ui.painter().line_segment(
             [pos2(trim_x, line_top), pos2(trim_x, line_bottom)],
             Stroke::new(2.0, theme::ACCENT),
@@ -111,24 +136,58 @@ pub fn handle_clip_drag_drop(
     let dragging_clips = std::mem::take(&mut state.ui.timeline.dragging_clips);
     let primary_clip = state.ui.timeline.drag_primary_clip.take();
     let grab_offset = state.ui.timeline.drag_grab_offset.take().unwrap_or(0.0);
+    let undo_token = state.ui.timeline.undo_coalesce_token.take();
 
+    let moved = drop_dragged_clips(
+        ui,
+        state,
+        &dragging_clips,
+        primary_clip,
+        grab_offset,
+        tracks_top,
+        content_left,
+        pps,
+        scroll,
+    );
+
+    if let Some(token) = undo_token {
+        if moved {
+            state.project.end_undo_gesture(token);
+        } else {
+            state.project.cancel_undo_gesture(token);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn drop_dragged_clips(
+    ui: &egui::Ui,
+    state: &mut AppState,
+    dragging_clips: &HashSet<TimelineClipId>,
+    primary_clip: Option<TimelineClipId>,
+    grab_offset: f64,
+    tracks_top: f32,
+    content_left: f32,
+    pps: f32,
+    scroll: f32,
+) -> bool {
     let Some(pointer) = ui.input(|i| i.pointer.hover_pos()) else {
-        return;
+        return false;
     };
 
     let Some(primary_id) = primary_clip else {
-        return;
+        return false;
     };
 
     let Some((_, _, primary_tc)) = state.project.timeline.find_clip(primary_id) else {
-        return;
+        return false;
     };
     let original_start = primary_tc.timeline_start;
 
     if dragging_clips.len() == 1 {
         let src_clip_id = primary_id;
         let Some((src_track, _, _)) = state.project.timeline.find_clip(src_clip_id) else {
-            return;
+            return false;
         };
         let src_track_id = src_track.id;
 
@@ -138,7 +197,7 @@ pub fn handle_clip_drag_drop(
             .floor()
             .max(0.0) as usize;
         if dst_display_idx >= total_tracks {
-            return;
+            return false;
         }
         let dst_track_id = track_layouts[dst_display_idx].track_id;
         let dst_kind = track_layouts[dst_display_idx].kind;
@@ -146,7 +205,7 @@ pub fn handle_clip_drag_drop(
         let src_kind = state.project.timeline.track_kind_for_clip(src_clip_id);
         if let Some(sk) = src_kind {
             if sk != dst_kind {
-                return;
+                return false;
             }
         }
 
@@ -163,19 +222,22 @@ pub fn handle_clip_drag_drop(
             pps,
             Some(src_clip_id),
             clip_duration,
+            true,
         );
 
-        state.project.snapshot_for_undo();
+        let overlap = state.ui.timeline.overlap;
         if src_track_id == dst_track_id {
             state
                 .project
                 .timeline
-                .move_clip_on_track(src_track_id, src_clip_id, new_pos);
+                .move_clip_on_track(src_track_id, src_clip_id, new_pos, overlap);
         } else {
-            state
-                .project
-                .timeline
-                .move_clip_across_tracks(src_clip_id, dst_track_id, new_pos);
+            state.project.timeline.move_clip_across_tracks(
+                src_clip_id,
+                dst_track_id,
+                new_pos,
+                overlap,
+            );
         }
     } else {
         let new_primary_pos =
@@ -191,15 +253,17 @@ pub fn handle_clip_drag_drop(
             pps,
             Some(primary_id),
             primary_duration,
+            true,
         );
         let delta = new_primary_pos - original_start;
 
-        state.project.snapshot_for_undo();
+        let overlap = state.ui.timeline.overlap;
         state
             .project
             .timeline
-            .move_clips_by_delta(&dragging_clips, delta);
+            .move_clips_by_delta(dragging_clips, delta, overlap);
     }
+    true
 }
 
 pub fn handle_zoom_scroll(
@@ -223,7 +287,20 @@ pub fn handle_zoom_scroll(
         let old_zoom = state.ui.timeline.zoom;
         let new_zoom = (old_zoom * zoom_delta).clamp(ZOOM_MIN, ZOOM_MAX);
 
-        if let Some(pointer) = hover_pos {
+        let is_playing = state.project.playback.state != PlaybackState::Stopped;
+        let anchor_on_playhead = match state.ui.timeline.zoom_anchor {
+            ZoomAnchor::Pointer => false,
+            ZoomAnchor::PlayheadWhilePlaying => is_playing,
+            ZoomAnchor::Playhead => true,
+        };
+
+        if anchor_on_playhead {
+            // Keep the playhead at the same screen x so zooming never loses your place.
+            let playhead = state.project.playback.playhead as f32;
+            let playhead_offset = playhead * old_zoom - state.ui.timeline.scroll_offset;
+            state.ui.timeline.zoom = new_zoom;
+            state.ui.timeline.scroll_offset = (playhead * new_zoom - playhead_offset).max(0.0);
+        } else if let Some(pointer) = hover_pos {
             let pointer_time =
                 (pointer.x - content_left + state.ui.timeline.scroll_offset) / old_zoom;
             state.ui.timeline.zoom = new_zoom;
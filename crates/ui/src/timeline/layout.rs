@@ -1,5 +1,5 @@
 use egui::Rect;
-use wizard_state::project::AppState;
+use wizard_state::project::{AppState, TrimEdge};
 use wizard_state::timeline::{TrackId, TrackKind};
 
 pub const TRACK_HEIGHT: f32 = 60.0;
@@ -12,7 +12,70 @@ pub const ZOOM_MAX: f32 = 500.0;
 pub const SNAP_THRESHOLD_PX: f32 = 10.0;
 pub const THUMB_WIDTH: f32 = 50.0;
 pub const TRIM_HANDLE_WIDTH: f32 = 12.0;
-pub const MIN_CLIP_DURATION: f64 = 0.1;
+pub const TRIM_HANDLE_MODE_WIDTH: f32 = 24.0;
+pub const TRIM_HANDLE_MIN_WIDTH: f32 = 4.0;
+pub const MIN_BODY_DRAG_FRACTION: f32 = 0.4;
+pub const TRIM_AUTO_ZOOM_CLIP_WIDTH: f32 = 80.0;
+pub use wizard_state::timeline::MIN_CLIP_DURATION;
+pub const ACTIVE_CLIP_BAR_HEIGHT: f32 = 3.0;
+pub const SCROLL_RUNWAY_S: f32 = 5.0;
+pub const RULER_LABEL_MIN_SPACING_PX: f32 = 60.0;
+pub const PLAYHEAD_REVEAL_MARGIN: f32 = 0.1;
+
+// Labelled ruler intervals in seconds, each with the number of minor ticks it splits into.
+const RULER_SECOND_STEPS: [(f64, u32); 13] = [
+    (1.0, 4),
+    (2.0, 4),
+    (5.0, 5),
+    (10.0, 5),
+    (15.0, 3),
+    (30.0, 6),
+    (60.0, 4),
+    (120.0, 4),
+    (300.0, 5),
+    (600.0, 5),
+    (900.0, 3),
+    (1800.0, 6),
+    (3600.0, 4),
+];
+const RULER_FRAME_STEPS: [u32; 4] = [1, 2, 5, 10];
+
+// How far each trim handle reaches from the clip edge. Outside trim mode the middle
+// MIN_BODY_DRAG_FRACTION of the clip always stays free for moving it; in trim mode the
+// handles may cover the whole clip. Handles that would be thinner than
+// TRIM_HANDLE_MIN_WIDTH are dropped, and the clip can only be trimmed after zooming in.
+pub fn trim_handle_width(clip_w: f32, trim_mode: bool) -> f32 {
+    let width = if trim_mode {
+        TRIM_HANDLE_MODE_WIDTH.min(clip_w / 2.0)
+    } else {
+        TRIM_HANDLE_WIDTH.min(clip_w * (1.0 - MIN_BODY_DRAG_FRACTION) / 2.0)
+    };
+    if width < TRIM_HANDLE_MIN_WIDTH {
+        0.0
+    } else {
+        width
+    }
+}
+
+pub fn trim_edge_at(x: f32, clip_rect: Rect, handle_w: f32) -> Option<TrimEdge> {
+    let to_left = (x - clip_rect.min.x).abs();
+    let to_right = (x - clip_rect.max.x).abs();
+    if to_left < handle_w && to_left <= to_right {
+        Some(TrimEdge::Left)
+    } else if to_right < handle_w {
+        Some(TrimEdge::Right)
+    } else {
+        None
+    }
+}
+
+pub fn nearest_trim_edge(x: f32, clip_rect: Rect) -> TrimEdge {
+    if x - clip_rect.min.x <= clip_rect.max.x - x {
+        TrimEdge::Left
+    } else {
+        TrimEdge::Right
+    }
+}
 
 pub struct TrackLayout {
     pub track_id: TrackId,
@@ -24,6 +87,58 @@ pub struct TrackLayout {
     pub visible: bool,
 }
 
+// Width of the scrollable content: the end of the last clip plus a few seconds of runway,
+// never narrower than the visible area so an empty timeline still gets a full thumb.
+pub fn scroll_content_width(timeline_duration: f64, pps: f32, visible_width: f32) -> f32 {
+    (timeline_duration as f32 * pps + SCROLL_RUNWAY_S * pps).max(visible_width)
+}
+
+pub fn max_scroll_offset(timeline_duration: f64, pps: f32, visible_width: f32) -> f32 {
+    (scroll_content_width(timeline_duration, pps, visible_width) - visible_width).max(0.0)
+}
+
+pub struct RulerSpacing {
+    pub interval: f64,
+    pub subdivisions: u32,
+    pub frame_ticks: bool,
+}
+
+// Picks the smallest labelled interval that keeps labels at least
+// `RULER_LABEL_MIN_SPACING_PX` apart. Zoomed in past a second per label, the ruler steps
+// in whole frames at the project rate.
+pub fn ruler_spacing(pps: f32, fps: f64) -> RulerSpacing {
+    let fps = fps.max(1.0);
+    let min_interval = (RULER_LABEL_MIN_SPACING_PX / pps.max(f32::EPSILON)) as f64;
+
+    for frames in RULER_FRAME_STEPS {
+        let interval = frames as f64 / fps;
+        if interval >= 1.0 {
+            break;
+        }
+        if interval >= min_interval {
+            return RulerSpacing {
+                interval,
+                subdivisions: frames,
+                frame_ticks: true,
+            };
+        }
+    }
+
+    let (interval, subdivisions) = RULER_SECOND_STEPS
+        .iter()
+        .copied()
+        .find(|&(interval, _)| interval >= min_interval)
+        .unwrap_or_else(|| {
+            let hours = (min_interval / 3600.0).ceil();
+            (hours * 3600.0, 4)
+        });
+    RulerSpacing {
+        interval,
+        subdivisions,
+        frame_ticks: false,
+    }
+}
+
 pub fn build_track_layout(state: &AppState) -> Vec<TrackLayout> {
     let mut layouts = Vec::new();
     let mut idx = 0;
@@ -54,29 +169,66 @@ pub fn build_track_layout(state: &AppState) -> Vec<TrackLayout> {
     layouts
 }
 
-pub fn snap_time_to_clip_boundaries(
+// The time under an x position on the ruler, snapped the way the playhead is when scrubbing.
+pub fn ruler_time_at(
     state: &AppState,
-    candidate_time: f64,
+    x: f32,
+    content_left: f32,
+    scroll: f32,
     pps: f32,
-    exclude_clip: Option<wizard_state::timeline::TimelineClipId>,
-) -> (f64, bool) {
-    snap_time_to_clip_boundaries_with_duration(state, candidate_time, pps, exclude_clip, None)
+) -> (f64, Option<SnapGuide>) {
+    let raw_t = ((x - content_left + scroll) / pps).max(0.0) as f64;
+    let (mut t, guide) =
+        snap_time_to_clip_boundaries_with_duration(state, raw_t, pps, None, None, false);
+    if state.ui.playback.snap_playhead_to_frames {
+        t = state.project.settings.nearest_frame(t);
+    }
+    (t, guide)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapKind {
+    ClipEdge,
+    Playhead,
+    TimelineStart,
+}
+
+impl SnapKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SnapKind::ClipEdge => "Clip edge",
+            SnapKind::Playhead => "Playhead",
+            SnapKind::TimelineStart => "Start",
+        }
+    }
 }
 
+// Where a snap landed on the timeline and what it landed on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapGuide {
+    pub time: f64,
+    pub kind: SnapKind,
+}
+
+// Returns the snapped start time and, if a snap happened, the guide to draw at the left or
+// right edge that actually snapped. Clip edges win over the playhead and the timeline start
+// whenever one is in reach, however much closer the others are, so edits butt up against
+// neighbouring clips predictably.
 pub fn snap_time_to_clip_boundaries_with_duration(
     state: &AppState,
     candidate_time: f64,
     pps: f32,
     exclude_clip: Option<wizard_state::timeline::TimelineClipId>,
     clip_duration: Option<f64>,
-) -> (f64, bool) {
-    if pps <= 0.0 {
-        return (candidate_time.max(0.0), false);
+    snap_to_playhead: bool,
+) -> (f64, Option<SnapGuide>) {
+    if pps <= 0.0 || !state.ui.timeline.snapping_enabled {
+        return (candidate_time.max(0.0), None);
     }
 
     let snap_threshold_time = (SNAP_THRESHOLD_PX / pps) as f64;
-    let mut best_time = candidate_time.max(0.0);
-    let mut best_dist = f64::INFINITY;
+    let mut best: Option<(f64, SnapGuide)> = None;
+    let mut best_rank = (true, f64::INFINITY);
 
     let mut excluded_ids: std::collections::HashSet<wizard_state::timeline::TimelineClipId> =
         std::collections::HashSet::new();
@@ -89,54 +241,42 @@ pub fn snap_time_to_clip_boundaries_with_duration(
         }
     }
 
-    let right_edge = clip_duration.map(|d| candidate_time + d);
-
+    let mut targets: Vec<(f64, SnapKind)> = vec![(0.0, SnapKind::TimelineStart)];
+    if snap_to_playhead {
+        targets.push((state.project.playback.playhead, SnapKind::Playhead));
+    }
     for track in state.project.timeline.all_tracks() {
         for tc in &track.clips {
             if excluded_ids.contains(&tc.id) {
                 continue;
             }
-            let start = tc.timeline_start;
-            let end = tc.timeline_start + tc.duration;
-
-            let start_dist = (candidate_time - start).abs();
-            if start_dist <= snap_threshold_time && start_dist < best_dist {
-                best_dist = start_dist;
-                best_time = start;
-            }
+            targets.push((tc.timeline_start, SnapKind::ClipEdge));
+            targets.push((tc.timeline_start + tc.duration, SnapKind::ClipEdge));
+        }
+    }
 
-            let end_dist = (candidate_time - end).abs();
-            if end_dist <= snap_threshold_time && end_dist < best_dist {
-                best_dist = end_dist;
-                best_time = end;
-            }
+    for (target, kind) in targets {
+        let guide = SnapGuide { time: target, kind };
+        let left_dist = (candidate_time - target).abs();
+        let rank = (kind != SnapKind::ClipEdge, left_dist);
+        if left_dist <= snap_threshold_time && rank < best_rank {
+            best_rank = rank;
+            best = Some((target, guide));
+        }
 
-            if let Some(re) = right_edge {
-                let right_to_start_dist = (re - start).abs();
-                if right_to_start_dist <= snap_threshold_time && right_to_start_dist < best_dist {
-                    best_dist = right_to_start_dist;
-                    best_time = start - clip_duration.unwrap();
-                }
-
-                let right_to_end_dist = (re - end).abs();
-                if right_to_end_dist <= snap_threshold_time && right_to_end_dist < best_dist {
-                    best_dist = right_to_end_dist;
-                    best_time = end - clip_duration.unwrap();
-                }
+        if let Some(d) = clip_duration {
+            let right_dist = (candidate_time + d - target).abs();
+            let rank = (kind != SnapKind::ClipEdge, right_dist);
+            if right_dist <= snap_threshold_time && rank < best_rank && target - d >= 0.0 {
+                best_rank = rank;
+                best = Some((target - d, guide));
             }
         }
     }
 
-    let left_to_zero_dist = candidate_time.abs();
-    if left_to_zero_dist <= snap_threshold_time && left_to_zero_dist < best_dist {
-        best_dist = left_to_zero_dist;
-        best_time = 0.0;
-    }
-
-    if best_dist.is_finite() {
-        (best_time.max(0.0), true)
-    } else {
-        (candidate_time.max(0.0), false)
+    match best {
+        Some((time, guide)) => (time.max(0.0), Some(guide)),
+        None => (candidate_time.max(0.0), None),
     }
 }
 
@@ -160,7 +300,26 @@ pub fn center_crop_uv(tex: &egui::TextureHandle, display_h: f32, display_w: f32)
     }
 }
 
+// `levels` runs coarsest to finest; the first level that still gives at least one peak
+// per on-screen pixel wins, falling back to the finest one when zoomed right in.
 pub fn visible_peak_slice(
+    levels: &[Vec<(f32, f32)>],
+    source_in: f64,
+    source_out: f64,
+    source_duration: Option<f64>,
+    pixel_width: f32,
+) -> &[(f32, f32)] {
+    let mut slice: &[(f32, f32)] = &[];
+    for peaks in levels {
+        slice = peak_slice(peaks, source_in, source_out, source_duration);
+        if slice.len() as f32 >= pixel_width {
+            break;
+        }
+    }
+    slice
+}
+
+fn peak_slice(
     peaks: &[(f32, f32)],
     source_in: f64,
     source_out: f64,
@@ -4,9 +4,11 @@ mod rendering;
 
 use egui::{pos2, vec2, Color32, CornerRadius, CursorIcon, Rect, Sense, Stroke};
 use wizard_state::clip::ClipId;
+use wizard_state::playback::PlaybackState;
 use wizard_state::project::{AppState, TrimEdge, TrimState};
-use wizard_state::timeline::TrackKind;
+use wizard_state::timeline::{TrackKind, MAX_GAIN_DB, MIN_GAIN_DB};
 
+use crate::file_manager;
 use crate::theme;
 use crate::waveform_gpu::waveform_paint_callback;
 use crate::TextureLookup;
@@ -30,6 +32,7 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
     let total_track_height = track_layouts.len() as f32 * (TRACK_HEIGHT + 2.0);
     let available_track_height = timeline_rect.height() - RULER_HEIGHT - SCROLLBAR_HEIGHT - 8.0;
     let needs_vertical_scroll = total_track_height > available_track_height;
+    let timeline_duration = state.project.timeline.timeline_duration();
 
     interaction::handle_zoom_scroll(
         ui,
@@ -40,6 +43,11 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
         total_track_height,
         available_track_height,
     );
+    if std::mem::take(&mut state.ui.timeline.reveal_playhead) {
+        reveal_playhead(state, content_width);
+    }
+    let max_scroll = max_scroll_offset(timeline_duration, state.ui.timeline.zoom, content_width);
+    state.ui.timeline.scroll_offset = state.ui.timeline.scroll_offset.clamp(0.0, max_scroll);
     let pps = state.ui.timeline.zoom;
     let scroll = state.ui.timeline.scroll_offset;
     let v_scroll = state.ui.timeline.vertical_scroll_offset;
@@ -72,6 +80,9 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
         .data(|d| d.get_temp::<bool>(egui::Id::new("gpu_waveforms")))
         .unwrap_or(false);
 
+    let is_playing = state.project.playback.state != PlaybackState::Stopped;
+    let playhead = state.project.playback.playhead;
+
     for layout in &track_layouts {
         let track_id = layout.track_id;
         let y = tracks_top + layout.display_index as f32 * (TRACK_HEIGHT + 2.0);
@@ -88,7 +99,9 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
         if let Some(payload) = track_response.dnd_release_payload::<Vec<ClipId>>() {
             if let Some(pointer) = ui.ctx().pointer_interact_pos() {
                 let drop_t = ((pointer.x - content_left + scroll) / pps).max(0.0) as f64;
-                let (t, _) = snap_time_to_clip_boundaries(state, drop_t, pps, None);
+                let (t, _) = snap_time_to_clip_boundaries_with_duration(
+                    state, drop_t, pps, None, None, true,
+                );
                 pending_browser_drop = Some((payload.as_ref().clone(), track_id, t));
             }
         }
@@ -111,15 +124,7 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
             );
         }
 
-        let base_clip_color = match layout.kind {
-            TrackKind::Video => theme::CLIP_VIDEO,
-            TrackKind::Audio => theme::CLIP_AUDIO,
-        };
-        let clip_color = if layout.muted || !layout.visible {
-            base_clip_color.gamma_multiply(0.3)
-        } else {
-            base_clip_color
-        };
+        let dim_clips = layout.muted || !layout.visible;
 
         let clips: Vec<_> = state
             .project
@@ -143,33 +148,53 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
             let tc_id = tc.id;
             let tc_source_id = tc.source_id;
 
+            let base_clip_color = clip_fill_color(state, tc_source_id, layout.kind);
+            let clip_color = if dim_clips || !tc.enabled {
+                base_clip_color.gamma_multiply(0.3)
+            } else {
+                base_clip_color
+            };
+
             let is_selected = state.ui.selection.is_timeline_clip_selected(tc_id);
 
             let is_being_dragged = state.ui.timeline.dragging_clips.contains(&tc_id);
 
-            let mut drew_gpu_waveform = false;
-
-            if layout.kind == TrackKind::Audio && gpu_waveforms_available {
-                if let Some(peaks) = textures.waveform_peaks(&tc_source_id) {
-                    let visible_peaks = visible_peak_slice(
-                        peaks,
+            let source_duration = state
+                .project
+                .clips
+                .get(&tc_source_id)
+                .and_then(|c| c.duration);
+            let waveform_lanes = waveform_lanes(
+                textures,
+                tc_source_id,
+                clip_rect,
+                layout.kind == TrackKind::Audio,
+                state.ui.timeline.stereo_waveforms,
+            );
+            let visible_lanes: Vec<(Rect, &[(f32, f32)])> = waveform_lanes
+                .iter()
+                .map(|&(rect, levels)| {
+                    let peaks = visible_peak_slice(
+                        levels,
                         tc.source_in,
                         tc.source_out,
-                        state
-                            .project
-                            .clips
-                            .get(&tc_source_id)
-                            .and_then(|c| c.duration),
+                        source_duration,
+                        clip_w,
                     );
-                    let wave_color = theme::WAVEFORM_COLOR;
+                    (rect, peaks)
+                })
+                .collect();
+
+            let drew_gpu_waveform = gpu_waveforms_available && !visible_lanes.is_empty();
+            if drew_gpu_waveform {
+                for &(rect, peaks) in &visible_lanes {
                     content_painter.add(waveform_paint_callback(
-                        clip_rect,
-                        visible_peaks,
-                        wave_color,
+                        rect,
+                        peaks,
+                        theme::WAVEFORM_COLOR,
                         clip_color,
                         [screen_size.x, screen_size.y],
                     ));
-                    drew_gpu_waveform = true;
                 }
             }
 
@@ -185,22 +210,41 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
                         vec2(thumb_w, TRACK_HEIGHT - 4.0),
                     );
                     let uv = center_crop_uv(tex, TRACK_HEIGHT - 4.0, thumb_w);
-                    content_painter.image(tex.id(), thumb_rect, uv, Color32::WHITE);
+                    let tint = if tc.enabled {
+                        Color32::WHITE
+                    } else {
+                        Color32::WHITE.gamma_multiply(0.3)
+                    };
+                    content_painter.image(tex.id(), thumb_rect, uv, tint);
                 }
-            } else if layout.kind == TrackKind::Audio && !drew_gpu_waveform {
-                if let Some(peaks) = textures.waveform_peaks(&tc_source_id) {
-                    let visible_peaks = visible_peak_slice(
-                        peaks,
-                        tc.source_in,
-                        tc.source_out,
-                        state
-                            .project
-                            .clips
-                            .get(&tc_source_id)
-                            .and_then(|c| c.duration),
-                    );
-                    draw_waveform(&content_painter, clip_rect, visible_peaks);
+            } else if !drew_gpu_waveform {
+                for &(rect, peaks) in &visible_lanes {
+                    draw_waveform(&content_painter, rect, peaks);
+                }
+            }
+
+            if layout.kind == TrackKind::Audio {
+                if let Some(progress) = textures.waveform_progress(&tc_source_id) {
+                    let loaded_x = match source_duration {
+                        Some(dur) if tc.source_out > tc.source_in => {
+                            let loaded_t = progress as f64 * dur;
+                            let frac = ((loaded_t - tc.source_in) / (tc.source_out - tc.source_in))
+                                .clamp(0.0, 1.0);
+                            clip_x + frac as f32 * clip_w
+                        }
+                        _ => clip_x,
+                    };
+                    if loaded_x < clip_rect.max.x {
+                        let pending_rect =
+                            Rect::from_min_max(pos2(loaded_x, clip_rect.min.y), clip_rect.max);
+                        content_painter.rect_filled(
+                            pending_rect,
+                            CornerRadius::ZERO,
+                            theme::WAVEFORM_LOADING,
+                        );
+                    }
                 }
+                draw_fade_envelope(&content_painter, clip_rect, tc);
             }
 
             content_painter.rect_stroke(
@@ -254,6 +298,31 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
                 );
             }
 
+            let is_active = is_playing && tc.duration > 0.0 && tc.contains_time(playhead);
+            if is_active {
+                let progress = ((playhead - tc.timeline_start) / tc.duration).clamp(0.0, 1.0);
+                let progress_x = clip_rect.min.x + clip_w * progress as f32;
+                content_painter.rect_filled(
+                    Rect::from_min_max(clip_rect.min, pos2(progress_x, clip_rect.max.y)),
+                    theme::ROUNDING_SM,
+                    Color32::from_white_alpha(18),
+                );
+                content_painter.rect_filled(
+                    Rect::from_min_max(
+                        clip_rect.min,
+                        pos2(progress_x, clip_rect.min.y + ACTIVE_CLIP_BAR_HEIGHT),
+                    ),
+                    CornerRadius::ZERO,
+                    theme::PLAYHEAD_COLOR,
+                );
+                content_painter.rect_stroke(
+                    clip_rect,
+                    theme::ROUNDING_SM,
+                    Stroke::new(1.5, theme::ACTIVE_CLIP_OUTLINE),
+                    egui::StrokeKind::Inside,
+                );
+            }
+
             if is_selected {
                 content_painter.rect_stroke(
                     clip_rect,
@@ -272,17 +341,13 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
             }
 
             let hover_pos = ui.ctx().pointer_hover_pos();
-            let effective_trim_w = TRIM_HANDLE_WIDTH.min(clip_w / 3.0);
-            let hover_on_left = hover_pos.is_some_and(|p| {
-                p.y >= clip_rect.min.y
-                    && p.y <= clip_rect.max.y
-                    && (p.x - clip_rect.min.x).abs() < effective_trim_w
-            });
-            let hover_on_right = hover_pos.is_some_and(|p| {
-                p.y >= clip_rect.min.y
-                    && p.y <= clip_rect.max.y
-                    && (p.x - clip_rect.max.x).abs() < effective_trim_w
-            });
+            let trim_mode = state.ui.timeline.trim_mode;
+            let handle_w = trim_handle_width(clip_w, trim_mode);
+            let hover_edge = hover_pos
+                .filter(|p| p.y >= clip_rect.min.y && p.y <= clip_rect.max.y)
+                .and_then(|p| trim_edge_at(p.x, clip_rect, handle_w));
+            let hover_on_left = hover_edge == Some(TrimEdge::Left);
+            let hover_on_right = hover_edge == Some(TrimEdge::Right);
 
             let is_being_trimmed = state
                 .ui
@@ -329,19 +394,27 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
 
             if clip_response.drag_started() {
                 let origin = clip_response.interact_pointer_pos().unwrap_or_default();
-                let origin_on_left = origin.y >= clip_rect.min.y
-                    && origin.y <= clip_rect.max.y
-                    && (origin.x - clip_rect.min.x).abs() < effective_trim_w;
-                let origin_on_right = origin.y >= clip_rect.min.y
-                    && origin.y <= clip_rect.max.y
-                    && (origin.x - clip_rect.max.x).abs() < effective_trim_w;
-
-                if origin_on_left || origin_on_right {
-                    let edge = if origin_on_left {
-                        TrimEdge::Left
-                    } else {
-                        TrimEdge::Right
-                    };
+                // In trim mode a clip too short for handles is zoomed in on and trimmed from
+                // the nearer edge, rather than being moved.
+                let zoom_to_trim = trim_mode && handle_w == 0.0;
+                let origin_edge = if zoom_to_trim {
+                    Some(nearest_trim_edge(origin.x, clip_rect))
+                } else if origin.y >= clip_rect.min.y && origin.y <= clip_rect.max.y {
+                    trim_edge_at(origin.x, clip_rect, handle_w)
+                } else {
+                    None
+                };
+
+                if let Some(edge) = origin_edge {
+                    if zoom_to_trim {
+                        let edge_time = match edge {
+                            TrimEdge::Left => tc.timeline_start,
+                            TrimEdge::Right => tc.timeline_end(),
+                        };
+                        zoom_for_trim(state, tc.duration, edge_time, origin.x - content_left);
+                    }
+                    state.ui.timeline.undo_coalesce_token =
+                        Some(state.project.begin_undo_gesture());
                     state.ui.timeline.trimming_clip = Some(TrimState {
                         clip_id: tc_id,
                         edge,
@@ -367,6 +440,8 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
                         state.ui.timeline.dragging_clips = [tc_id].into_iter().collect();
                     }
                     state.ui.timeline.drag_primary_clip = Some(tc_id);
+                    state.ui.timeline.undo_coalesce_token =
+                        Some(state.project.begin_undo_gesture());
                 }
             }
 
@@ -387,8 +462,14 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
             }
 
             let is_starred = state.project.starred.contains(&tc_source_id);
+            let tc_enabled = tc.enabled;
             let multi_selected = state.ui.selection.selected_timeline_clips.len() > 1
                 && state.ui.selection.is_timeline_clip_selected(tc_id);
+            let mergeable = multi_selected
+                && state
+                    .project
+                    .mergeable_selection(&state.ui.selection)
+                    .is_some();
             clip_response.context_menu(|ui| {
                 if ui.button("Delete").clicked() {
                     state.project.snapshot_for_undo();
@@ -407,12 +488,88 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
                     }
                     ui.close_menu();
                 }
+                if ui.button("Ripple Delete").clicked() {
+                    state.project.snapshot_for_undo();
+                    let targets: Vec<_> = if multi_selected {
+                        state.ui.selection.selected_timeline_clips.drain().collect()
+                    } else {
+                        state.ui.selection.selected_timeline_clips.remove(&tc_id);
+                        if let Some(linked) = tc.linked_to {
+                            state.ui.selection.selected_timeline_clips.remove(&linked);
+                        }
+                        vec![tc_id]
+                    };
+                    for id in targets {
+                        state.project.timeline.ripple_delete(id);
+                    }
+                    ui.close_menu();
+                }
+                if mergeable && ui.button("Merge Clips").clicked() {
+                    state.merge_selected_clips();
+                    ui.close_menu();
+                }
+                if ui.button("Match Frame (F)").clicked() {
+                    state.match_frame();
+                    ui.close_menu();
+                }
+                let enable_label = if tc_enabled {
+                    "Disable Clip"
+                } else {
+                    "Enable Clip"
+                };
+                if ui.button(enable_label).clicked() {
+                    state.project.snapshot_for_undo();
+                    let targets: Vec<_> = if multi_selected {
+                        state
+                            .ui
+                            .selection
+                            .selected_timeline_clips
+                            .iter()
+                            .copied()
+                            .collect()
+                    } else {
+                        vec![tc_id]
+                    };
+                    for id in targets {
+                        state.project.timeline.set_clip_enabled(id, !tc_enabled);
+                    }
+                    ui.close_menu();
+                }
+                let default_fade = state.project.settings.default_fade;
+                let add_fades = egui::Button::new(format!("Add Fades ({default_fade:.2}s)"));
+                if ui.add_enabled(default_fade > 0.0, add_fades).clicked() {
+                    let targets: Vec<_> = if multi_selected {
+                        state
+                            .ui
+                            .selection
+                            .selected_timeline_clips
+                            .iter()
+                            .copied()
+                            .collect()
+                    } else {
+                        vec![tc_id]
+                    };
+                    state.project.add_default_fades(&targets);
+                    ui.close_menu();
+                }
                 if !multi_selected {
                     let star_label = if is_starred { "Unstar" } else { "Star" };
                     if ui.button(star_label).clicked() {
                         state.project.toggle_star(tc_source_id);
                         ui.close_menu();
                     }
+                    if let Some(path) = state
+                        .project
+                        .clips
+                        .get(&tc_source_id)
+                        .map(|c| c.path.clone())
+                    {
+                        let reveal = egui::Button::new("Reveal in File Manager");
+                        if ui.add_enabled(path.exists(), reveal).clicked() {
+                            file_manager::reveal_in_file_manager(&path);
+                            ui.close_menu();
+                        }
+                    }
                 }
             });
         }
@@ -422,7 +579,11 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
         state.project.snapshot_for_undo();
         let mut cursor = position_seconds;
         for clip_id in clip_ids {
-            state.project.add_clip_to_track(clip_id, track_id, cursor);
+            let link_audio = state.ui.timeline.link_audio_on_drop;
+            let overlap = state.ui.timeline.overlap;
+            state
+                .project
+                .add_clip_to_track(clip_id, track_id, cursor, link_audio, overlap);
             let dur = state
                 .project
                 .clips
@@ -449,6 +610,16 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
 
     interaction::handle_clip_trim(ui, state, content_left, pps, scroll, tracks_top);
     interaction::handle_clip_drag_drop(ui, state, tracks_top, content_left, pps, scroll);
+    merge_hint(
+        ui,
+        state,
+        &track_layouts,
+        tracks_top,
+        content_left,
+        pps,
+        scroll,
+        content_clip_rect,
+    );
 
     let primary_down = ui.input(|i| i.pointer.primary_down());
     let primary_pressed = ui.input(|i| i.pointer.primary_pressed());
@@ -555,6 +726,17 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
             egui::FontId::proportional(12.0),
             theme::TEXT_PRIMARY,
         );
+        // Stacking position among the video tracks, 1 being the one in front.
+        if layout.kind == TrackKind::Video {
+            let layer = state.project.timeline.video_tracks.len() - layout.pair_index;
+            header_painter.text(
+                pos2(header_rect.min.x + 6.0, header_rect.center().y),
+                egui::Align2::LEFT_CENTER,
+                format!("#{layer}"),
+                egui::FontId::monospace(9.0),
+                theme::TEXT_DIM,
+            );
+        }
 
         let header_response = ui.interact(
             header_rect,
@@ -566,6 +748,7 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
         let pair_count = state.project.timeline.pair_count();
         let is_muted = layout.muted;
         let is_visible = layout.visible;
+        let track_kind = layout.kind;
         header_response.context_menu(|ui| {
             let mute_label = if is_muted { "Unmute" } else { "Mute" };
             if ui.button(mute_label).clicked() {
@@ -583,10 +766,60 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
                 }
                 ui.close_menu();
             }
+            if track_kind == TrackKind::Audio {
+                let mut gain_db = state
+                    .project
+                    .timeline
+                    .track_by_id(track_id)
+                    .map(|t| t.gain_db)
+                    .unwrap_or(0.0);
+                let gain_response = ui.add(
+                    egui::Slider::new(&mut gain_db, MIN_GAIN_DB..=MAX_GAIN_DB)
+                        .text("Gain")
+                        .suffix(" dB")
+                        .fixed_decimals(1),
+                );
+                if gain_response.drag_started() || gain_response.clicked() {
+                    state.project.snapshot_for_undo();
+                }
+                if gain_response.changed() {
+                    if let Some(track) = state.project.timeline.track_by_id_mut(track_id) {
+                        track.gain_db = gain_db;
+                    }
+                }
+            }
+            if track_kind == TrackKind::Video {
+                ui.separator();
+                if ui
+                    .add_enabled(
+                        pair_index + 1 < pair_count,
+                        egui::Button::new("Bring Forward"),
+                    )
+                    .clicked()
+                {
+                    state.project.snapshot_for_undo();
+                    state
+                        .project
+                        .timeline
+                        .move_video_track(pair_index, pair_index + 1);
+                    ui.close_menu();
+                }
+                if ui
+                    .add_enabled(pair_index > 0, egui::Button::new("Send Backward"))
+                    .clicked()
+                {
+                    state.project.snapshot_for_undo();
+                    state
+                        .project
+                        .timeline
+                        .move_video_track(pair_index, pair_index - 1);
+                    ui.close_menu();
+                }
+            }
             ui.separator();
             let can_move_up = pair_index + 1 < pair_count;
             if ui
-                .add_enabled(can_move_up, egui::Button::new("Move Up"))
+                .add_enabled(can_move_up, egui::Button::new("Move Pair Up"))
                 .clicked()
             {
                 state.project.snapshot_for_undo();
@@ -598,7 +831,7 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
             }
             let can_move_down = pair_index > 0;
             if ui
-                .add_enabled(can_move_down, egui::Button::new("Move Down"))
+                .add_enabled(can_move_down, egui::Button::new("Move Pair Down"))
                 .clicked()
             {
                 state.project.snapshot_for_undo();
@@ -626,7 +859,37 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
         });
     }
 
-    draw_ruler(ui, content_left, ruler_top, content_width, pps, scroll);
+    draw_ruler(
+        ui,
+        content_left,
+        ruler_top,
+        content_width,
+        pps,
+        scroll,
+        state.project.settings.fps,
+    );
+    if let Some(span) = state.ui.timeline.rewind_cached_span {
+        draw_rewind_cache_band(
+            ui,
+            content_left,
+            ruler_top,
+            content_width,
+            pps,
+            scroll,
+            span,
+        );
+    }
+    if let Some(range) = state.ui.timeline.range_selection {
+        draw_range_selection(
+            ui,
+            content_left,
+            ruler_top,
+            content_width,
+            pps,
+            scroll,
+            range,
+        );
+    }
 
     let corner_rect = Rect::from_min_size(
         pos2(timeline_rect.min.x, ruler_top),
@@ -634,6 +897,7 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
     );
     ui.painter()
         .rect_filled(corner_rect, CornerRadius::ZERO, theme::RULER_BG);
+    draw_snap_toggle(ui, state, corner_rect);
 
     let playhead_time = state.project.playback.playhead;
     let mut playhead_x = content_left + playhead_time as f32 * pps - scroll;
@@ -647,10 +911,28 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
         egui::Id::new("timeline_scrub"),
         Sense::click_and_drag(),
     );
-    if scrub_response.dragged() || scrub_response.clicked() {
+    // Shift-dragging paints an in/out range instead of scrubbing; a plain click clears it.
+    if scrub_response.drag_started() && ui.input(|i| i.modifiers.shift) {
+        if let Some(origin) = ui.input(|i| i.pointer.press_origin()) {
+            let (t, _) = ruler_time_at(state, origin.x, content_left, scroll, pps);
+            state.ui.timeline.range_anchor = Some(t);
+        }
+    }
+    if let Some(anchor) = state.ui.timeline.range_anchor {
         if let Some(pointer) = scrub_response.interact_pointer_pos() {
-            let raw_t = ((pointer.x - content_left + scroll) / pps).max(0.0) as f64;
-            let (t, snapped) = snap_time_to_clip_boundaries(state, raw_t, pps, None);
+            let (t, _) = ruler_time_at(state, pointer.x, content_left, scroll, pps);
+            state.ui.timeline.range_selection =
+                (t != anchor).then(|| (anchor.min(t), anchor.max(t)));
+        }
+        if !scrub_response.dragged() {
+            state.ui.timeline.range_anchor = None;
+        }
+    } else if scrub_response.dragged() || scrub_response.clicked() {
+        if scrub_response.clicked() {
+            state.ui.timeline.range_selection = None;
+        }
+        if let Some(pointer) = scrub_response.interact_pointer_pos() {
+            let (t, snap_guide) = ruler_time_at(state, pointer.x, content_left, scroll, pps);
 
             state.project.playback.playhead = t;
             if scrub_response.dragged() || scrub_response.clicked() {
@@ -658,20 +940,15 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
             }
             playhead_x = content_left + t as f32 * pps - scroll;
 
-            if snapped {
+            if let Some(guide) = snap_guide {
                 let snap_line_top = tracks_top;
                 let snap_line_bottom = tracks_top + total_tracks as f32 * (TRACK_HEIGHT + 2.0);
                 let snap_clip = Rect::from_min_max(
                     pos2(content_left, snap_line_top),
                     pos2(content_left + content_width, snap_line_bottom),
                 );
-                ui.painter().with_clip_rect(snap_clip).line_segment(
-                    [
-                        pos2(playhead_x, snap_line_top),
-                        pos2(playhead_x, snap_line_bottom),
-                    ],
-                    Stroke::new(1.0, theme::ACCENT.gamma_multiply(0.5)),
-                );
+                let painter = ui.painter().with_clip_rect(snap_clip);
+                draw_snap_guide(&painter, guide, playhead_x, snap_line_top, snap_line_bottom);
             }
         }
     }
@@ -710,5 +987,85 @@ pub fn timeline_panel(ui: &mut egui::Ui, state: &mut AppState, textures: &dyn Te
         content_left,
         content_width,
         clip_area_bottom + 4.0,
+        timeline_duration,
     );
+    draw_zoom_readout(
+        ui,
+        state,
+        Rect::from_min_size(
+            pos2(timeline_rect.min.x + 2.0, clip_area_bottom + 2.0),
+            vec2(TRACK_HEADER_WIDTH - 6.0, SCROLLBAR_HEIGHT + 4.0),
+        ),
+        content_width,
+    );
+}
+
+// A small "Merge" button over the seam whenever the selection is two clips that could be
+// one, so the option shows up without digging into the context menu.
+#[allow(clippy::too_many_arguments)]
+fn merge_hint(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    track_layouts: &[TrackLayout],
+    tracks_top: f32,
+    content_left: f32,
+    pps: f32,
+    scroll: f32,
+    content_clip_rect: Rect,
+) {
+    if !state.ui.timeline.dragging_clips.is_empty() || state.ui.timeline.trimming_clip.is_some() {
+        return;
+    }
+    let Some((a, b)) = state.project.mergeable_selection(&state.ui.selection) else {
+        return;
+    };
+    let timeline = &state.project.timeline;
+    let (Some((track, _, a)), Some((_, _, b))) = (timeline.find_clip(a), timeline.find_clip(b))
+    else {
+        return;
+    };
+    let Some(layout) = track_layouts.iter().find(|l| l.track_id == track.id) else {
+        return;
+    };
+    // The pair comes in either order; the seam is where the later one starts.
+    let seam = a.timeline_start.max(b.timeline_start);
+    let x = content_left + seam as f32 * pps - scroll;
+    let y = tracks_top + layout.display_index as f32 * (TRACK_HEIGHT + 2.0) + TRACK_HEIGHT / 2.0;
+    let rect = Rect::from_center_size(pos2(x, y), vec2(44.0, 16.0));
+    if !content_clip_rect.contains_rect(rect) {
+        return;
+    }
+    let button = egui::Button::new(egui::RichText::new("Merge").size(10.0)).small();
+    if ui
+        .put(rect, button)
+        .on_hover_text("These clips continue the same source; join them into one")
+        .clicked()
+    {
+        state.merge_selected_clips();
+    }
+}
+
+// Zooms so the clip spans TRIM_AUTO_ZOOM_CLIP_WIDTH, keeping the grabbed edge under the
+// pointer so the trim carries on from where it was picked up.
+fn zoom_for_trim(state: &mut AppState, duration: f64, edge_time: f64, pointer_x: f32) {
+    let zoom = (TRIM_AUTO_ZOOM_CLIP_WIDTH / duration.max(MIN_CLIP_DURATION) as f32)
+        .clamp(ZOOM_MIN, ZOOM_MAX);
+    if zoom <= state.ui.timeline.zoom {
+        return;
+    }
+    state.ui.timeline.zoom = zoom;
+    state.ui.timeline.scroll_offset = (edge_time as f32 * zoom - pointer_x).max(0.0);
+}
+
+// Scrolls only when the playhead is off screen, leaving a margin so it isn't pinned
+// against the edge.
+fn reveal_playhead(state: &mut AppState, content_width: f32) {
+    let margin = content_width * PLAYHEAD_REVEAL_MARGIN;
+    let playhead_x = state.project.playback.playhead as f32 * state.ui.timeline.zoom;
+    let scroll = &mut state.ui.timeline.scroll_offset;
+    if playhead_x < *scroll + margin {
+        *scroll = (playhead_x - margin).max(0.0);
+    } else if playhead_x > *scroll + content_width - margin {
+        *scroll = playhead_x - content_width + margin;
+    }
 }
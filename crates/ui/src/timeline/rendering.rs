@@ -1,14 +1,15 @@
-use egui::{pos2, vec2, Color32, CornerRadius, Rect, Stroke};
+use egui::{pos2, vec2, Color32, CornerRadius, CursorIcon, Rect, Stroke};
 use wizard_state::clip::ClipId;
 use wizard_state::project::AppState;
-use wizard_state::timeline::TrackKind;
+use wizard_state::timeline::{TimelineClip, TrackKind};
 
 use crate::theme;
 use crate::TextureLookup;
 
 use super::layout::{
-    build_track_layout, snap_time_to_clip_boundaries_with_duration, RULER_HEIGHT, SCROLLBAR_HEIGHT,
-    THUMB_WIDTH, TRACK_HEIGHT, V_SCROLLBAR_WIDTH,
+    build_track_layout, max_scroll_offset, ruler_spacing, scroll_content_width,
+    snap_time_to_clip_boundaries_with_duration, SnapGuide, RULER_HEIGHT, SCROLLBAR_HEIGHT,
+    THUMB_WIDTH, TRACK_HEIGHT, V_SCROLLBAR_WIDTH, ZOOM_MAX, ZOOM_MIN,
 };
 
 pub struct ClipGhostParams<'a> {
@@ -27,6 +28,43 @@ pub struct ClipGhostParams<'a> {
     pub textures: &'a dyn TextureLookup,
 }
 
+// A clip's primary tag overrides the generic video/audio colour.
+pub fn clip_fill_color(state: &AppState, source_id: ClipId, kind: TrackKind) -> Color32 {
+    let mask = state.project.clip_tag_mask(source_id);
+    match state.project.tags.primary(mask) {
+        Some(def) => theme::tag_color(def.color),
+        None => match kind {
+            TrackKind::Video => theme::CLIP_VIDEO,
+            TrackKind::Audio => theme::CLIP_AUDIO,
+        },
+    }
+}
+
+// Where each waveform of an audio clip goes: the whole clip for the mixed waveform, or the
+// top and bottom halves for left and right once split peaks have arrived.
+pub fn waveform_lanes<'a>(
+    textures: &'a dyn TextureLookup,
+    source_id: ClipId,
+    clip_rect: Rect,
+    is_audio: bool,
+    stereo: bool,
+) -> Vec<(Rect, &'a [Vec<(f32, f32)>])> {
+    if !is_audio {
+        return Vec::new();
+    }
+    if let Some([left, right]) = stereo
+        .then(|| textures.stereo_waveform_peaks(&source_id))
+        .flatten()
+    {
+        let (top, bottom) = clip_rect.split_top_bottom_at_fraction(0.5);
+        return vec![(top, left), (bottom, right)];
+    }
+    textures
+        .waveform_peaks(&source_id)
+        .map(|levels| vec![(clip_rect, levels)])
+        .unwrap_or_default()
+}
+
 pub fn draw_waveform(painter: &egui::Painter, rect: Rect, peaks: &[(f32, f32)]) {
     if peaks.is_empty() {
         return;
@@ -57,24 +95,110 @@ pub fn draw_waveform(painter: &egui::Painter, rect: Rect, peaks: &[(f32, f32)])
     }
 }
 
-pub fn draw_ruler(ui: &mut egui::Ui, left: f32, top: f32, width: f32, pps: f32, scroll: f32) {
+// A small magnet in the corner above the track headers, lit while snapping is on. Clicking
+// it does the same as N.
+pub fn draw_snap_toggle(ui: &mut egui::Ui, state: &mut AppState, corner_rect: Rect) {
+    let rect = Rect::from_center_size(
+        pos2(corner_rect.max.x - 12.0, corner_rect.center().y),
+        vec2(18.0, 18.0),
+    );
+    let enabled = state.ui.timeline.snapping_enabled;
+    let response = ui
+        .interact(rect, egui::Id::new("snap_toggle"), egui::Sense::click())
+        .on_hover_text(if enabled {
+            "Snapping on (N)"
+        } else {
+            "Snapping off (N)"
+        });
+    if response.clicked() {
+        state.ui.timeline.snapping_enabled = !enabled;
+    }
+
+    let color = if enabled {
+        theme::ACCENT
+    } else if response.hovered() {
+        theme::TEXT_PRIMARY
+    } else {
+        theme::TEXT_DIM
+    };
+    let c = rect.center() + vec2(0.0, 1.0);
+    let r = 4.5;
+    let leg = 4.0;
+    let mut points = vec![pos2(c.x - r, c.y - leg)];
+    for i in 0..=8 {
+        let a = std::f32::consts::PI * (1.0 - i as f32 / 8.0);
+        points.push(pos2(c.x + r * a.cos(), c.y + r * a.sin()));
+    }
+    points.push(pos2(c.x + r, c.y - leg));
+    let painter = ui.painter();
+    painter.add(egui::Shape::line(points, Stroke::new(2.5, color)));
+    for x in [c.x - r, c.x + r] {
+        painter.line_segment(
+            [pos2(x, c.y - leg - 2.0), pos2(x, c.y - leg)],
+            Stroke::new(2.5, theme::TEXT_PRIMARY),
+        );
+    }
+}
+
+// The clip's fade envelope as a line over its waveform, from silence at the bottom of the
+// clip to full level along the top.
+pub fn draw_fade_envelope(painter: &egui::Painter, clip_rect: Rect, tc: &TimelineClip) {
+    if (tc.fade_in <= 0.0 && tc.fade_out <= 0.0) || tc.duration <= 0.0 {
+        return;
+    }
+    let d = tc.duration;
+    let fade_in = tc.fade_in.min(d);
+    let fade_out = tc.fade_out.min(d);
+    let mut times = vec![0.0, fade_in, d - fade_out, d];
+    if fade_in + fade_out > d {
+        times.push(d * fade_in / (fade_in + fade_out));
+    }
+    times.retain(|t| (0.0..=d).contains(t));
+    times.sort_by(f64::total_cmp);
+    times.dedup();
+
+    let top = clip_rect.min.y + 2.0;
+    let bottom = clip_rect.max.y - 2.0;
+    let points = times
+        .into_iter()
+        .map(|t| {
+            let x = clip_rect.min.x + (t / d) as f32 * clip_rect.width();
+            let gain = tc.fade_gain_at(t) as f32;
+            pos2(x, bottom - gain * (bottom - top))
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        Stroke::new(1.5, theme::FADE_LINE),
+    ));
+}
+
+pub fn draw_ruler(
+    ui: &mut egui::Ui,
+    left: f32,
+    top: f32,
+    width: f32,
+    pps: f32,
+    scroll: f32,
+    fps: f64,
+) {
     let ruler_rect = Rect::from_min_size(pos2(left, top), vec2(width, RULER_HEIGHT));
     ui.painter()
         .rect_filled(ruler_rect, CornerRadius::ZERO, theme::RULER_BG);
 
     let ruler_painter = ui.painter().with_clip_rect(ruler_rect);
 
-    let start_time = scroll / pps;
-    let visible_duration = width / pps;
-    let first_second = start_time.floor() as i32;
-    let last_second = (start_time + visible_duration).ceil() as i32 + 1;
-
-    for s in first_second..last_second {
-        if s < 0 {
-            continue;
-        }
-        let x = left + s as f32 * pps - scroll;
-        if x < left - pps || x > left + width + pps {
+    let spacing = ruler_spacing(pps, fps);
+    let interval_px = spacing.interval as f32 * pps;
+    let start_time = (scroll / pps) as f64;
+    let visible_duration = (width / pps) as f64;
+    let first_tick = (start_time / spacing.interval).floor().max(0.0) as i64;
+    let last_tick = ((start_time + visible_duration) / spacing.interval).ceil() as i64 + 1;
+
+    for tick in first_tick..last_tick {
+        let t = tick as f64 * spacing.interval;
+        let x = left + t as f32 * pps - scroll;
+        if x < left - interval_px || x > left + width + interval_px {
             continue;
         }
 
@@ -86,19 +210,16 @@ pub fn draw_ruler(ui: &mut egui::Ui, left: f32, top: f32, width: f32, pps: f32,
             Stroke::new(1.0, theme::TEXT_DIM),
         );
 
-        let minutes = s / 60;
-        let secs = s % 60;
-        let label = format!("{minutes}:{secs:02}");
         ruler_painter.text(
             pos2(x + 2.0, top + 2.0),
             egui::Align2::LEFT_TOP,
-            label,
+            ruler_label(t, fps, spacing.frame_ticks),
             egui::FontId::monospace(9.0),
             theme::TEXT_DIM,
         );
 
-        for sub in 1..4 {
-            let sub_x = x + sub as f32 * pps / 4.0;
+        for sub in 1..spacing.subdivisions {
+            let sub_x = x + sub as f32 * interval_px / spacing.subdivisions as f32;
             if sub_x >= left && sub_x < left + width {
                 ruler_painter.line_segment(
                     [
@@ -112,7 +233,98 @@ pub fn draw_ruler(ui: &mut egui::Ui, left: f32, top: f32, width: f32, pps: f32,
     }
 }
 
-pub fn draw_scrollbar(ui: &mut egui::Ui, state: &mut AppState, left: f32, width: f32, top: f32) {
+// Every kind of snap, whether scrubbing, dragging, dropping or trimming, draws the same
+// guide, labelled with what it caught on.
+pub fn draw_snap_guide(painter: &egui::Painter, guide: SnapGuide, x: f32, top: f32, bottom: f32) {
+    painter.line_segment(
+        [pos2(x, top), pos2(x, bottom)],
+        Stroke::new(2.0, theme::ACCENT.gamma_multiply(0.5)),
+    );
+    painter.text(
+        pos2(x + 4.0, top + 2.0),
+        egui::Align2::LEFT_TOP,
+        guide.kind.label(),
+        egui::FontId::proportional(10.0),
+        theme::ACCENT,
+    );
+}
+
+// A strip along the bottom of the ruler over the region reverse playback can replay from
+// memory rather than by decoding backwards.
+pub fn draw_rewind_cache_band(
+    ui: &mut egui::Ui,
+    left: f32,
+    top: f32,
+    width: f32,
+    pps: f32,
+    scroll: f32,
+    span: (f64, f64),
+) {
+    let (start, end) = span;
+    let x0 = left + start as f32 * pps - scroll;
+    let x1 = (left + end as f32 * pps - scroll).max(x0 + 2.0);
+    let ruler_rect = Rect::from_min_size(pos2(left, top), vec2(width, RULER_HEIGHT));
+    let band = Rect::from_min_max(
+        pos2(x0, top + RULER_HEIGHT - 3.0),
+        pos2(x1, top + RULER_HEIGHT),
+    );
+    ui.painter().with_clip_rect(ruler_rect).rect_filled(
+        band,
+        CornerRadius::ZERO,
+        theme::ACCENT.gamma_multiply(0.6),
+    );
+}
+
+pub fn draw_range_selection(
+    ui: &mut egui::Ui,
+    left: f32,
+    top: f32,
+    width: f32,
+    pps: f32,
+    scroll: f32,
+    range: (f64, f64),
+) {
+    let (start, end) = range;
+    let x0 = left + start as f32 * pps - scroll;
+    let x1 = left + end as f32 * pps - scroll;
+    let ruler_rect = Rect::from_min_size(pos2(left, top), vec2(width, RULER_HEIGHT));
+    let painter = ui.painter().with_clip_rect(ruler_rect);
+    let band = Rect::from_min_max(pos2(x0, top), pos2(x1, top + RULER_HEIGHT));
+    painter.rect_filled(band, CornerRadius::ZERO, theme::ACCENT.gamma_multiply(0.25));
+    for x in [x0, x1] {
+        painter.line_segment(
+            [pos2(x, top), pos2(x, top + RULER_HEIGHT)],
+            Stroke::new(1.0, theme::ACCENT),
+        );
+    }
+}
+
+fn ruler_label(t: f64, fps: f64, frame_ticks: bool) -> String {
+    let fps_whole = fps.round().max(1.0) as u64;
+    let total_frames = (t * fps).round() as u64;
+    let total_secs = total_frames / fps_whole;
+    let frames = total_frames % fps_whole;
+    let (hours, minutes, secs) = (total_secs / 3600, (total_secs / 60) % 60, total_secs % 60);
+
+    let mut label = if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes}:{secs:02}")
+    };
+    if frame_ticks && frames > 0 {
+        label.push_str(&format!(":{frames:02}"));
+    }
+    label
+}
+
+pub fn draw_scrollbar(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    left: f32,
+    width: f32,
+    top: f32,
+    timeline_duration: f64,
+) {
     let scrollbar_rect = Rect::from_min_size(pos2(left, top), vec2(width, SCROLLBAR_HEIGHT));
     ui.painter()
         .rect_filled(scrollbar_rect, CornerRadius::ZERO, theme::RULER_BG);
@@ -120,20 +332,10 @@ pub fn draw_scrollbar(ui: &mut egui::Ui, state: &mut AppState, left: f32, width:
     let pps = state.ui.timeline.zoom;
     let scroll = state.ui.timeline.scroll_offset;
 
-    let mut total_duration: f64 = 10.0;
-    for track in state.project.timeline.all_tracks() {
-        for tc in &track.clips {
-            let end = tc.timeline_start + tc.duration;
-            if end > total_duration {
-                total_duration = end;
-            }
-        }
-    }
-    total_duration += 5.0;
-
-    let total_width = total_duration as f32 * pps;
+    let total_width = scroll_content_width(timeline_duration, pps, width);
+    let max_scroll = max_scroll_offset(timeline_duration, pps, width);
     let visible_fraction = (width / total_width).clamp(0.05, 1.0);
-    let scroll_fraction = scroll / (total_width - width).max(1.0);
+    let scroll_fraction = (scroll / max_scroll.max(1.0)).clamp(0.0, 1.0);
 
     let thumb_w = (width * visible_fraction).max(20.0);
     let thumb_x = left + scroll_fraction * (width - thumb_w);
@@ -154,12 +356,70 @@ pub fn draw_scrollbar(ui: &mut egui::Ui, state: &mut AppState, left: f32, width:
     if response.dragged() || response.clicked() {
         if let Some(pointer) = response.interact_pointer_pos() {
             let frac = ((pointer.x - left) / width).clamp(0.0, 1.0);
-            let max_scroll = (total_width - width).max(0.0);
             state.ui.timeline.scroll_offset = frac * max_scroll;
         }
     }
 }
 
+// Shows how much time fits across the timeline; clicking it lets an exact span be typed in.
+// The new zoom keeps the playhead centred.
+pub fn draw_zoom_readout(ui: &mut egui::Ui, state: &mut AppState, rect: Rect, content_width: f32) {
+    let edit_id = egui::Id::new("timeline_zoom_edit");
+    let visible_s = content_width / state.ui.timeline.zoom;
+
+    let Some(mut text) = state.ui.timeline.zoom_edit.clone() else {
+        let readout = egui::Label::new(
+            egui::RichText::new(format_visible_span(visible_s))
+                .monospace()
+                .size(11.0)
+                .color(theme::TEXT_DIM),
+        )
+        .sense(egui::Sense::click());
+        let response = ui.put(rect, readout).on_hover_text(format!(
+            "{:.0} px/s. Click to set the visible span in seconds",
+            state.ui.timeline.zoom
+        ));
+        if response.clicked() {
+            state.ui.timeline.zoom_edit = Some(format!("{visible_s:.1}"));
+            ui.memory_mut(|m| m.request_focus(edit_id));
+        }
+        return;
+    };
+
+    let response = ui.put(
+        rect,
+        egui::TextEdit::singleline(&mut text)
+            .id(edit_id)
+            .font(egui::FontId::monospace(11.0))
+            .horizontal_align(egui::Align::Center),
+    );
+    if response.has_focus() {
+        state.ui.timeline.zoom_edit = Some(text);
+    } else {
+        if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let seconds = text.trim().trim_end_matches('s').trim().parse::<f32>();
+            if let Some(seconds) = seconds.ok().filter(|s| *s > 0.0) {
+                let zoom = (content_width / seconds).clamp(ZOOM_MIN, ZOOM_MAX);
+                let playhead_x = state.project.playback.playhead as f32 * zoom;
+                state.ui.timeline.zoom = zoom;
+                state.ui.timeline.scroll_offset = (playhead_x - content_width / 2.0).max(0.0);
+            }
+        }
+        state.ui.timeline.zoom_edit = None;
+    }
+}
+
+fn format_visible_span(seconds: f32) -> String {
+    if seconds >= 60.0 {
+        let total = seconds.round() as u32;
+        format!("{}:{:02}", total / 60, total % 60)
+    } else if seconds >= 10.0 {
+        format!("{seconds:.0}s")
+    } else {
+        format!("{seconds:.1}s")
+    }
+}
+
 pub fn draw_vertical_scrollbar(
     ui: &mut egui::Ui,
     state: &mut AppState,
@@ -243,14 +503,63 @@ pub fn draw_drag_ghosts(
     let clip_duration = exclude_clip
         .and_then(|id| state.project.timeline.find_clip(id))
         .map(|(_, _, tc)| tc.duration);
-    let (drop_time, _snapped) = snap_time_to_clip_boundaries_with_duration(
+    let (drop_time, snap_guide) = snap_time_to_clip_boundaries_with_duration(
         state,
         unsnapped_drop_time,
         pps,
         exclude_clip,
         clip_duration,
+        true,
     );
 
+    let is_dragging = ui.input(|i| i.pointer.any_down());
+    let has_browser_payload = egui::DragAndDrop::has_payload_of_type::<Vec<ClipId>>(ui.ctx());
+    if let Some(guide) = snap_guide {
+        if (has_timeline_drag && is_dragging) || (!has_timeline_drag && has_browser_payload) {
+            let guide_x = content_left + guide.time as f32 * pps - scroll;
+            let line_bottom = tracks_top + num_tracks as f32 * (TRACK_HEIGHT + 2.0);
+            let painter = ui.painter().with_clip_rect(content_clip_rect);
+            draw_snap_guide(&painter, guide, guide_x, tracks_top, line_bottom);
+        }
+    }
+
+    // Multi-clip drags keep every clip on its own track, so only single-clip and browser
+    // drags have a target row to show.
+    let single_drag_kind = if has_timeline_drag && state.ui.timeline.dragging_clips.len() == 1 {
+        state
+            .ui
+            .timeline
+            .drag_primary_clip
+            .and_then(|id| state.project.timeline.track_kind_for_clip(id))
+    } else {
+        None
+    };
+    let shows_target = if has_timeline_drag {
+        is_dragging && single_drag_kind.is_some()
+    } else {
+        has_browser_payload
+    };
+    if shows_target {
+        let kind_mismatch = single_drag_kind.is_some_and(|kind| kind != target_kind);
+        let row_y = tracks_top + target_display_idx as f32 * (TRACK_HEIGHT + 2.0);
+        draw_drop_target_row(ui, content_clip_rect, row_y, kind_mismatch);
+        if let Some(kind) = single_drag_kind.filter(|_| kind_mismatch) {
+            ui.ctx().set_cursor_icon(CursorIcon::NotAllowed);
+            let reason = match kind {
+                TrackKind::Video => "Video clips can only be placed on video tracks",
+                TrackKind::Audio => "Audio clips can only be placed on audio tracks",
+            };
+            egui::show_tooltip_at_pointer(
+                ui.ctx(),
+                ui.layer_id(),
+                egui::Id::new("drop_kind_mismatch"),
+                |ui| {
+                    ui.label(reason);
+                },
+            );
+        }
+    }
+
     let paired_track_id = track_layouts[target_display_idx].track_id;
     let paired_id = state.project.timeline.paired_track_id(paired_track_id);
     let paired_display_idx =
@@ -268,10 +577,7 @@ pub fn draw_drag_ghosts(
                     .and_then(|c| c.duration)
                     .unwrap_or(3.0)
                     .max(0.1);
-                let clip_color = match target_kind {
-                    TrackKind::Video => theme::CLIP_VIDEO,
-                    TrackKind::Audio => theme::CLIP_AUDIO,
-                };
+                let clip_color = clip_fill_color(state, *clip_id, target_kind);
                 let track_y = tracks_top + target_display_idx as f32 * (TRACK_HEIGHT + 2.0);
                 draw_clip_ghost(
                     ui,
@@ -294,10 +600,7 @@ pub fn draw_drag_ghosts(
                 );
                 if let Some(p_idx) = paired_display_idx {
                     let paired_kind = track_layouts[p_idx].kind;
-                    let paired_color = match paired_kind {
-                        TrackKind::Video => theme::CLIP_VIDEO,
-                        TrackKind::Audio => theme::CLIP_AUDIO,
-                    };
+                    let paired_color = clip_fill_color(state, *clip_id, paired_kind);
                     let paired_y = tracks_top + p_idx as f32 * (TRACK_HEIGHT + 2.0);
                     draw_clip_ghost(
                         ui,
@@ -325,7 +628,6 @@ pub fn draw_drag_ghosts(
     }
 
     if has_timeline_drag {
-        let is_dragging = ui.input(|i| i.pointer.any_down());
         if is_dragging {
             let primary_id = state.ui.timeline.drag_primary_clip;
             let primary_start = primary_id
@@ -346,10 +648,7 @@ pub fn draw_drag_ghosts(
                         track_layouts.iter().position(|l| l.track_id == tc_track_id)
                     {
                         let tk = track_layouts[l_idx].kind;
-                        let clip_color = match tk {
-                            TrackKind::Video => theme::CLIP_VIDEO,
-                            TrackKind::Audio => theme::CLIP_AUDIO,
-                        };
+                        let clip_color = clip_fill_color(state, clip_id, tk);
                         let track_y = tracks_top + l_idx as f32 * (TRACK_HEIGHT + 2.0);
                         draw_clip_ghost(
                             ui,
@@ -377,6 +676,26 @@ pub fn draw_drag_ghosts(
     }
 }
 
+fn draw_drop_target_row(ui: &egui::Ui, clip_rect: Rect, row_y: f32, rejected: bool) {
+    let color = if rejected {
+        theme::DROP_REJECT
+    } else {
+        theme::ACCENT
+    };
+    let row = Rect::from_min_max(
+        pos2(clip_rect.min.x, row_y),
+        pos2(clip_rect.max.x, row_y + TRACK_HEIGHT),
+    );
+    let painter = ui.painter().with_clip_rect(clip_rect);
+    painter.rect_filled(row, CornerRadius::ZERO, color.gamma_multiply(0.15));
+    painter.rect_stroke(
+        row,
+        CornerRadius::ZERO,
+        Stroke::new(1.5, color),
+        egui::StrokeKind::Inside,
+    );
+}
+
 fn draw_clip_ghost(ui: &mut egui::Ui, clip_rect: Rect, p: &ClipGhostParams<'_>) {
     let ghost_x = p.content_left + p.drop_time as f32 * p.pps - p.scroll;
     let ghost_w = p.duration as f32 * p.pps;
@@ -0,0 +1,46 @@
+use wizard_state::toast::Toasts;
+
+use crate::theme;
+
+const TOAST_WIDTH: f32 = 320.0;
+const TOAST_MARGIN: f32 = 12.0;
+
+// Stacked above the bottom-right corner, newest at the bottom. Each one closes on its own
+// once its time is up, or straight away from the close button.
+pub fn toasts(ctx: &egui::Context, toasts: &mut Toasts) {
+    let mut dismissed = None;
+    egui::Area::new(egui::Id::new("toasts"))
+        .anchor(
+            egui::Align2::RIGHT_BOTTOM,
+            egui::vec2(-TOAST_MARGIN, -TOAST_MARGIN),
+        )
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            ui.set_max_width(TOAST_WIDTH);
+            for (index, toast) in toasts.iter().enumerate() {
+                egui::Frame::new()
+                    .fill(theme::BG_SURFACE)
+                    .stroke(egui::Stroke::new(1.0, theme::BADGE_WARN))
+                    .corner_radius(theme::ROUNDING)
+                    .inner_margin(egui::Margin::same(8))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(theme::BADGE_WARN, "\u{26A0}");
+                            ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(&toast.message).color(theme::TEXT_PRIMARY),
+                                )
+                                .wrap(),
+                            );
+                            if ui.small_button("\u{2715}").clicked() {
+                                dismissed = Some(index);
+                            }
+                        });
+                    });
+                ui.add_space(4.0);
+            }
+        });
+    if let Some(index) = dismissed {
+        toasts.dismiss(index);
+    }
+}
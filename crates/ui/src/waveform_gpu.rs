@@ -100,6 +100,8 @@ struct WaveformDrawData {
     vertex_count: u32,
 }
 
+const MAX_GPU_PEAKS: usize = 2048;
+
 pub fn waveform_paint_callback(
     rect: Rect,
     peaks: &[(f32, f32)],
@@ -107,12 +109,18 @@ pub fn waveform_paint_callback(
     bg_color: Color32,
     screen_size: [f32; 2],
 ) -> egui::PaintCallback {
-    let peak_count = peaks.len().min(2048);
+    // Fold neighbouring peaks together rather than truncating, so a fine mip level on a
+    // wide clip still spans the whole rect.
+    let stride = peaks.len().div_ceil(MAX_GPU_PEAKS).max(1);
     let peaks_data: Vec<[f32; 2]> = peaks
-        .iter()
-        .take(peak_count)
-        .map(|(a, b)| [*a, *b])
+        .chunks(stride)
+        .map(|chunk| {
+            chunk.iter().fold([0.0f32, 0.0f32], |acc, (lo, hi)| {
+                [acc[0].min(*lo), acc[1].max(*hi)]
+            })
+        })
         .collect();
+    let peak_count = peaks_data.len();
 
     let uniforms = WaveformUniforms {
         rect_min: [rect.min.x, rect.min.y],